@@ -0,0 +1,6 @@
+-- schedule_kind: 'daily', 'weekdays' (due on the days in schedule_days) or
+-- 'weekly' (due weekly_target times per Monday-based week).
+-- schedule_days is a bitmask with bit 0 = Monday ... bit 6 = Sunday.
+ALTER TABLE tasks ADD COLUMN IF NOT EXISTS schedule_kind TEXT NOT NULL DEFAULT 'daily';
+ALTER TABLE tasks ADD COLUMN IF NOT EXISTS schedule_days SMALLINT NOT NULL DEFAULT 127;
+ALTER TABLE tasks ADD COLUMN IF NOT EXISTS weekly_target SMALLINT NOT NULL DEFAULT 1;
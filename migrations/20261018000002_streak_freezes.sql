@@ -0,0 +1,10 @@
+ALTER TABLE users ADD COLUMN IF NOT EXISTS freeze_tokens INTEGER NOT NULL DEFAULT 2;
+
+CREATE TABLE IF NOT EXISTS streak_freezes (
+    id BIGSERIAL PRIMARY KEY,
+    task_id BIGINT NOT NULL REFERENCES tasks(id),
+    frozen_date DATE NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE(task_id, frozen_date)
+);
+CREATE INDEX IF NOT EXISTS idx_streak_freezes_task_date ON streak_freezes(task_id, frozen_date);
@@ -0,0 +1,6 @@
+-- Tasks with a target_value accumulate completions.value over the day and
+-- only count as done once it reaches the target. Tasks without one keep the
+-- plain done/not-done behaviour with value = 1.
+ALTER TABLE tasks ADD COLUMN IF NOT EXISTS target_value INTEGER;
+ALTER TABLE tasks ADD COLUMN IF NOT EXISTS unit TEXT;
+ALTER TABLE completions ADD COLUMN IF NOT EXISTS value INTEGER NOT NULL DEFAULT 1;
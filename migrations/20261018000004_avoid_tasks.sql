@@ -0,0 +1,4 @@
+-- 'build' tasks record a completion on each day they are done. 'avoid' tasks
+-- record one on each day the user slipped, and their streak is the number of
+-- clean days since the last slip.
+ALTER TABLE tasks ADD COLUMN IF NOT EXISTS kind TEXT NOT NULL DEFAULT 'build';
@@ -0,0 +1,10 @@
+-- Deleting a task permanently takes its history with it.
+ALTER TABLE completions DROP CONSTRAINT IF EXISTS completions_task_id_fkey;
+ALTER TABLE completions
+    ADD CONSTRAINT completions_task_id_fkey
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE;
+
+ALTER TABLE streak_freezes DROP CONSTRAINT IF EXISTS streak_freezes_task_id_fkey;
+ALTER TABLE streak_freezes
+    ADD CONSTRAINT streak_freezes_task_id_fkey
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE;
@@ -0,0 +1,15 @@
+-- Manual ordering within a user's dashboard, lowest first, and an optional
+-- time-of-day section: 'morning', 'afternoon' or 'evening'.
+ALTER TABLE tasks ADD COLUMN IF NOT EXISTS position INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE tasks ADD COLUMN IF NOT EXISTS section TEXT;
+
+-- Keep the existing newest-first order
+UPDATE tasks
+SET position = ordered.rn
+FROM (
+    SELECT id, ROW_NUMBER() OVER (PARTITION BY user_id ORDER BY created_at DESC) AS rn
+    FROM tasks
+) AS ordered
+WHERE tasks.id = ordered.id;
+
+CREATE INDEX IF NOT EXISTS idx_tasks_user_position ON tasks(user_id, position);
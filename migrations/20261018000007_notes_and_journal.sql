@@ -0,0 +1,12 @@
+ALTER TABLE completions ADD COLUMN IF NOT EXISTS note TEXT;
+
+-- One free-form entry per user per day. Mood runs from 1 (low) to 5 (great).
+CREATE TABLE IF NOT EXISTS journal_entries (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    entry_date DATE NOT NULL,
+    mood SMALLINT CHECK (mood BETWEEN 1 AND 5),
+    body TEXT NOT NULL DEFAULT '',
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE(user_id, entry_date)
+);
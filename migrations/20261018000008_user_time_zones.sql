@@ -0,0 +1,3 @@
+-- IANA zone that decides when a user's day starts. Existing users keep the
+-- central time the app assumed before zones were stored.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS time_zone TEXT NOT NULL DEFAULT 'America/Chicago';
@@ -0,0 +1,4 @@
+-- Hour (local time) at which a user's day ends, so late-night check-ins can
+-- still count for the previous day. Zero keeps the midnight boundary.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS day_ends_hour SMALLINT NOT NULL DEFAULT 0
+    CHECK (day_ends_hour BETWEEN 0 AND 6);
@@ -0,0 +1,13 @@
+-- Denormalised streak counters, recomputed whenever a task's completions or
+-- freezes change and each day at rollover. `as_of` is the owner's date the
+-- row was computed for; older rows are stale and get recomputed on read.
+CREATE TABLE IF NOT EXISTS task_streaks (
+    task_id BIGINT PRIMARY KEY REFERENCES tasks(id) ON DELETE CASCADE,
+    current_streak INTEGER NOT NULL,
+    best_streak INTEGER NOT NULL,
+    frozen BOOLEAN NOT NULL,
+    total_completions INTEGER NOT NULL,
+    last_completed DATE,
+    as_of DATE NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,8 @@
+-- Each member's role in a group: 'owner' (the creator), 'admin' or 'member'.
+ALTER TABLE group_members ADD COLUMN IF NOT EXISTS role TEXT NOT NULL DEFAULT 'member'
+    CHECK (role IN ('owner', 'admin', 'member'));
+
+UPDATE group_members gm
+SET role = 'owner'
+FROM groups g
+WHERE g.id = gm.group_id AND g.created_by = gm.user_id;
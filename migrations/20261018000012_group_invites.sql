@@ -0,0 +1,14 @@
+-- Extra invites an admin hands out next to a group's main code, each with an
+-- optional expiry and use limit.
+CREATE TABLE IF NOT EXISTS group_invites (
+    id BIGSERIAL PRIMARY KEY,
+    group_id BIGINT NOT NULL REFERENCES groups(id) ON DELETE CASCADE,
+    code TEXT NOT NULL UNIQUE,
+    created_by BIGINT NOT NULL REFERENCES users(id),
+    expires_at TIMESTAMP,
+    max_uses INTEGER CHECK (max_uses > 0),
+    uses INTEGER NOT NULL DEFAULT 0,
+    revoked BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_group_invites_group_id ON group_invites(group_id);
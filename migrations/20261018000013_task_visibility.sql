@@ -0,0 +1,15 @@
+-- Which of its owner's groups can see a task: 'groups' (all of them),
+-- 'selected' (those listed in task_group_shares) or 'private'.
+ALTER TABLE tasks ADD COLUMN IF NOT EXISTS visibility TEXT NOT NULL DEFAULT 'groups'
+    CHECK (visibility IN ('groups', 'selected', 'private'));
+
+CREATE TABLE IF NOT EXISTS task_group_shares (
+    task_id BIGINT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    group_id BIGINT NOT NULL REFERENCES groups(id) ON DELETE CASCADE,
+    PRIMARY KEY (task_id, group_id)
+);
+CREATE INDEX IF NOT EXISTS idx_task_group_shares_group_id ON task_group_shares(group_id);
+
+-- Visibility given to a user's new tasks: 'groups' or 'private'.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS default_task_visibility TEXT NOT NULL DEFAULT 'groups'
+    CHECK (default_task_visibility IN ('groups', 'private'));
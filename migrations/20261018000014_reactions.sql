@@ -0,0 +1,10 @@
+-- Cheers one member leaves on another's task for a day, from a fixed set.
+CREATE TABLE IF NOT EXISTS reactions (
+    task_id BIGINT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    reaction_date DATE NOT NULL,
+    reaction TEXT NOT NULL CHECK (reaction IN ('fire', 'clap', 'muscle', 'heart', 'party')),
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (task_id, user_id, reaction_date, reaction)
+);
+CREATE INDEX IF NOT EXISTS idx_reactions_task_date ON reactions(task_id, reaction_date);
@@ -0,0 +1,14 @@
+-- What happened to members and their tasks, for group activity timelines.
+-- Task events are shown to every group that can see the task; membership
+-- events belong to one group.
+CREATE TABLE IF NOT EXISTS events (
+    id BIGSERIAL PRIMARY KEY,
+    kind TEXT NOT NULL CHECK (kind IN ('completed', 'uncompleted', 'milestone', 'streak_broken', 'joined', 'left', 'removed')),
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    task_id BIGINT REFERENCES tasks(id) ON DELETE CASCADE,
+    group_id BIGINT REFERENCES groups(id) ON DELETE CASCADE,
+    streak INTEGER,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_events_group ON events(group_id, id);
+CREATE INDEX IF NOT EXISTS idx_events_user ON events(user_id, id);
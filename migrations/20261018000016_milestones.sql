@@ -0,0 +1,11 @@
+-- Streak lengths each user wants celebrated, and the milestones their tasks
+-- have reached.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS milestone_thresholds INTEGER[] NOT NULL DEFAULT '{7,30,100}';
+
+CREATE TABLE IF NOT EXISTS milestones (
+    task_id BIGINT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    threshold INTEGER NOT NULL,
+    reached_on DATE NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (task_id, threshold, reached_on)
+);
@@ -0,0 +1,28 @@
+-- Habits a group keeps together. The group's streak runs while enough of the
+-- members taking part check in each day.
+CREATE TABLE IF NOT EXISTS group_habits (
+    id BIGSERIAL PRIMARY KEY,
+    group_id BIGINT NOT NULL REFERENCES groups(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    required_percent SMALLINT NOT NULL DEFAULT 100 CHECK (required_percent BETWEEN 1 AND 100),
+    created_by BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_group_habits_group ON group_habits(group_id);
+
+-- `joined_on` is the member's date when they opted in; earlier days don't
+-- expect a check-in from them.
+CREATE TABLE IF NOT EXISTS group_habit_members (
+    habit_id BIGINT NOT NULL REFERENCES group_habits(id) ON DELETE CASCADE,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    joined_on DATE NOT NULL,
+    PRIMARY KEY (habit_id, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS group_habit_checkins (
+    habit_id BIGINT NOT NULL REFERENCES group_habits(id) ON DELETE CASCADE,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    checkin_date DATE NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (habit_id, user_id, checkin_date)
+);
@@ -0,0 +1,19 @@
+-- Things a user should know about, listed in their notification centre
+-- until read. `actor_id` is whoever caused it; at-risk reminders have none
+-- and are sent once per task and day.
+CREATE TABLE IF NOT EXISTS notifications (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL CHECK (kind IN ('milestone', 'cheer', 'joined', 'streak_at_risk')),
+    actor_id BIGINT REFERENCES users(id) ON DELETE CASCADE,
+    task_id BIGINT REFERENCES tasks(id) ON DELETE CASCADE,
+    group_id BIGINT REFERENCES groups(id) ON DELETE CASCADE,
+    streak INTEGER,
+    reaction TEXT,
+    for_date DATE,
+    read_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_notifications_user ON notifications(user_id, id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_notifications_at_risk ON notifications(task_id, for_date)
+    WHERE kind = 'streak_at_risk';
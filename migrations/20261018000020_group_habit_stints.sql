@@ -0,0 +1,8 @@
+-- Participation in a team habit is kept as stints, so someone leaving stops
+-- counting from that day on without changing how earlier days were judged.
+-- `left_on` is the first day they're no longer expected to check in.
+ALTER TABLE group_habit_members ADD COLUMN IF NOT EXISTS left_on DATE;
+ALTER TABLE group_habit_members DROP CONSTRAINT IF EXISTS group_habit_members_pkey;
+ALTER TABLE group_habit_members ADD COLUMN IF NOT EXISTS id BIGSERIAL PRIMARY KEY;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_group_habit_members_active ON group_habit_members(habit_id, user_id)
+    WHERE left_on IS NULL;
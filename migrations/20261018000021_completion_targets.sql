@@ -0,0 +1,4 @@
+-- The target a past day was judged against. Stamped when a task's target
+-- changes, so earlier days keep the target they were logged under; NULL
+-- follows the task's current target.
+ALTER TABLE completions ADD COLUMN IF NOT EXISTS target INTEGER;
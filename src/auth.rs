@@ -4,14 +4,21 @@ use argon2::{
 };
 use axum::{
     extract::FromRequestParts,
-    http::request::Parts,
+    http::{Method, request::Parts},
     response::Redirect,
 };
 use chrono::NaiveDate;
+use chrono_tz::Tz;
 use tower_sessions::Session;
 
+use crate::AppState;
+use crate::models::user::{DayBoundary, User};
+
 const USER_ID_KEY: &str = "user_id";
 
+/// Page a signed-out visitor asked for, to send them back after logging in.
+const RETURN_TO_KEY: &str = "return_to";
+
 pub fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
     let salt = SaltString::generate(&mut OsRng);
     let hash = Argon2::default().hash_password(password.as_bytes(), &salt)?;
@@ -31,6 +38,29 @@ pub async fn logout_session(session: &Session) -> Result<(), tower_sessions::ses
     session.flush().await
 }
 
+/// The signed-in user, for pages that signed-out visitors can see too.
+pub async fn session_user_id(session: &Session) -> Option<i64> {
+    session.get(USER_ID_KEY).await.ok()?
+}
+
+/// Where to go after logging in, if a page sent the visitor to `/login`.
+pub async fn take_return_to(session: &Session) -> Option<String> {
+    session.remove(RETURN_TO_KEY).await.ok()?
+}
+
+/// Sends a signed-out visitor to log in, remembering the page they wanted.
+/// Only full-page GETs are remembered; a form post or htmx fragment can't be
+/// replayed as a redirect.
+async fn login_redirect(parts: &Parts, session: &Session) -> Redirect {
+    if parts.method == Method::GET
+        && !parts.headers.contains_key("HX-Request")
+        && let Some(target) = parts.uri.path_and_query()
+    {
+        let _ = session.insert(RETURN_TO_KEY, target.as_str()).await;
+    }
+    Redirect::to("/login")
+}
+
 pub struct AuthUser {
     pub id: i64,
 }
@@ -54,48 +84,34 @@ where
 
         match user_id {
             Some(id) => Ok(AuthUser { id }),
-            None => Err(Redirect::to("/login")),
+            None => Err(login_redirect(parts, session).await),
         }
     }
 }
 
-pub struct LocalDate(pub NaiveDate);
-
-impl<S> FromRequestParts<S> for LocalDate
-where
-    S: Send + Sync,
-{
-    type Rejection = std::convert::Infallible;
+/// Zone for visitors and for users whose stored zone is no longer valid.
+pub const DEFAULT_TIME_ZONE: Tz = chrono_tz::America::Chicago;
 
-    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        let date = parse_from_header(parts)
-            .or_else(|| parse_from_cookie(parts))
-            .unwrap_or_else(server_today);
-        Ok(LocalDate(date))
-    }
+pub fn parse_time_zone(name: &str) -> Option<Tz> {
+    name.trim().parse().ok()
 }
 
-fn parse_date(val: &str) -> Option<NaiveDate> {
-    NaiveDate::parse_from_str(val.trim(), "%Y-%m-%d").ok()
-}
+/// The current date for the signed-in user, from their stored time zone and
+/// day rollover hour: before that hour it is still yesterday. The browser's
+/// own date is never trusted, so a forged one can't check in ahead of time.
+pub struct LocalDate(pub NaiveDate);
 
-fn parse_from_header(parts: &Parts) -> Option<NaiveDate> {
-    parts.headers.get("X-Local-Date")?.to_str().ok().and_then(parse_date)
-}
+impl FromRequestParts<AppState> for LocalDate {
+    type Rejection = std::convert::Infallible;
 
-fn parse_from_cookie(parts: &Parts) -> Option<NaiveDate> {
-    let cookies = parts.headers.get("cookie")?.to_str().ok()?;
-    for pair in cookies.split(';') {
-        let pair = pair.trim();
-        if let Some(val) = pair.strip_prefix("local_date=") {
-            return parse_date(val);
-        }
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let boundary = user_day_boundary(parts, state).await.unwrap_or_default();
+        Ok(LocalDate(boundary.today(state.clock.now())))
     }
-    None
 }
 
-fn server_today() -> NaiveDate {
-    use chrono::{FixedOffset, Utc};
-    let offset = FixedOffset::west_opt(6 * 3600).unwrap(); // CST (UTC-6)
-    Utc::now().with_timezone(&offset).date_naive()
+async fn user_day_boundary(parts: &Parts, state: &AppState) -> Option<DayBoundary> {
+    let session = parts.extensions.get::<Session>()?;
+    let user_id = session_user_id(session).await?;
+    User::day_boundary(&state.db, user_id).await.ok()?
 }
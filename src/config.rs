@@ -1,6 +1,11 @@
+/// Furthest back the backfill window may reach, in days.
+pub const MAX_BACKFILL_DAYS: i64 = 365;
+
 pub struct Config {
     pub database_url: String,
     pub bind_addr: String,
+    /// How many days before today a completion may still be marked or unmarked.
+    pub backfill_days: i64,
 }
 
 impl Config {
@@ -10,6 +15,11 @@ impl Config {
                 .unwrap_or_else(|_| "sqlite:racha.db?mode=rwc".to_string()),
             bind_addr: std::env::var("BIND_ADDR")
                 .unwrap_or_else(|_| "127.0.0.1:3000".to_string()),
+            backfill_days: std::env::var("BACKFILL_DAYS")
+                .ok()
+                .and_then(|v| v.parse::<i64>().ok())
+                .unwrap_or(2)
+                .clamp(0, MAX_BACKFILL_DAYS),
         }
     }
 }
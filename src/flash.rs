@@ -0,0 +1,29 @@
+//! One-off messages kept in the session across a redirect and shown by the
+//! next page that renders.
+
+use serde::{Deserialize, Serialize};
+use tower_sessions::Session;
+
+const FLASH_KEY: &str = "flash";
+
+#[derive(Serialize, Deserialize)]
+struct Flash {
+    message: String,
+    is_error: bool,
+}
+
+pub async fn set(session: &Session, message: impl Into<String>, is_error: bool) {
+    let flash = Flash {
+        message: message.into(),
+        is_error,
+    };
+    if let Err(err) = session.insert(FLASH_KEY, flash).await {
+        tracing::warn!("failed to store flash message: {err}");
+    }
+}
+
+/// The pending message and whether it's an error, clearing it.
+pub async fn take(session: &Session) -> Option<(String, bool)> {
+    let flash: Flash = session.remove(FLASH_KEY).await.ok()??;
+    Some((flash.message, flash.is_error))
+}
@@ -0,0 +1,48 @@
+use std::time::Duration;
+
+use sqlx::PgPool;
+
+use crate::clock::Clock;
+use crate::models::{notification, streak_cache};
+
+/// How often the rollover job looks for users whose day has ended. Zones and
+/// rollover hours differ, so "nightly" happens at some point every hour.
+const ROLLOVER_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How often users nearing the end of their day are checked for streaks
+/// they haven't kept yet. Reminders go out once per task and day, so this
+/// only needs to run often enough to land inside the warning window.
+const AT_RISK_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// Keeps the streak cache current: once a user's day ends, each of their
+/// tasks is recomputed for the new day so the first dashboard load of the
+/// morning doesn't have to replay every history.
+pub fn spawn_streak_rollover(pool: PgPool, clock: Clock) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(ROLLOVER_INTERVAL);
+        loop {
+            interval.tick().await;
+            match streak_cache::roll_over(&pool, clock.now()).await {
+                Ok(0) => {}
+                Ok(refreshed) => tracing::info!("rolled over {refreshed} cached streaks"),
+                Err(err) => tracing::error!("streak rollover failed: {err}"),
+            }
+        }
+    });
+}
+
+/// Sends a notification for each streak that ends with the user's day
+/// unless they check in, a few hours before it does.
+pub fn spawn_streak_reminders(pool: PgPool, clock: Clock) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(AT_RISK_INTERVAL);
+        loop {
+            interval.tick().await;
+            match notification::flag_streaks_at_risk(&pool, clock.now()).await {
+                Ok(0) => {}
+                Ok(sent) => tracing::info!("sent {sent} streak reminders"),
+                Err(err) => tracing::error!("streak reminders failed: {err}"),
+            }
+        }
+    });
+}
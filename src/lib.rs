@@ -1,13 +1,20 @@
 pub mod auth;
+pub mod clock;
 pub mod config;
 pub mod db;
+pub mod flash;
+pub mod jobs;
 pub mod models;
 pub mod routes;
 pub mod templates;
 
 use sqlx::PgPool;
 
+use crate::clock::Clock;
+
 #[derive(Clone)]
 pub struct AppState {
     pub db: PgPool,
+    pub backfill_days: i64,
+    pub clock: Clock,
 }
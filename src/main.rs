@@ -3,7 +3,7 @@ use tower_http::services::ServeDir;
 use tower_sessions::SessionManagerLayer;
 use tower_sessions_sqlx_store::PostgresStore;
 
-use racha::{AppState, config, db, routes};
+use racha::{AppState, clock::Clock, config, db, jobs, models::streak_cache, routes};
 
 #[tokio::main]
 async fn main() {
@@ -12,13 +12,34 @@ async fn main() {
 
     let cfg = config::Config::from_env();
     let pool = db::create_pool(&cfg.database_url).await;
+    let clock = Clock::System;
+
+    // `racha rebuild-streaks` checks the streak cache against the raw
+    // completions, rewrites it and exits
+    if std::env::args().nth(1).as_deref() == Some("rebuild-streaks") {
+        let report = streak_cache::rebuild(&pool, clock.now())
+            .await
+            .expect("Failed to rebuild streak cache");
+        println!(
+            "Checked {} tasks: {} mismatched, {} missing. Cache rebuilt.",
+            report.checked, report.mismatched, report.missing
+        );
+        return;
+    }
+
+    jobs::spawn_streak_rollover(pool.clone(), clock);
+    jobs::spawn_streak_reminders(pool.clone(), clock);
 
     let session_store = PostgresStore::new(pool.clone());
     session_store.migrate().await.expect("Failed to migrate session store");
 
     let session_layer = SessionManagerLayer::new(session_store);
 
-    let state = AppState { db: pool };
+    let state = AppState {
+        db: pool,
+        backfill_days: cfg.backfill_days,
+        clock,
+    };
 
     let app = routes::build_router()
         .nest_service("/static", ServeDir::new("static"))
@@ -0,0 +1,338 @@
+//! Time-boxed group challenges. Members who opt in get a daily task of their
+//! own linked to the challenge, and are ranked by how many of the
+//! challenge's days they completed on it.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Days, NaiveDate, NaiveDateTime, Utc};
+use sqlx::PgPool;
+
+use crate::models::{completion, sharing};
+use crate::models::task::{Schedule, Task, TaskDetails, TaskKind};
+use crate::models::user::DayBoundary;
+
+/// Longest a challenge can run, in days.
+pub const MAX_DAYS: i64 = 365;
+
+#[derive(sqlx::FromRow)]
+#[allow(dead_code)]
+pub struct Challenge {
+    pub id: i64,
+    pub group_id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub created_by: i64,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ChallengeStatus {
+    Upcoming,
+    Active,
+    Ended,
+}
+
+impl Challenge {
+    pub fn status(&self, today: NaiveDate) -> ChallengeStatus {
+        if today < self.start_date {
+            ChallengeStatus::Upcoming
+        } else if today > self.end_date {
+            ChallengeStatus::Ended
+        } else {
+            ChallengeStatus::Active
+        }
+    }
+
+    pub fn is_ended(&self, today: NaiveDate) -> bool {
+        self.status(today) == ChallengeStatus::Ended
+    }
+
+    /// Days in the challenge, counting both ends.
+    pub fn length_days(&self) -> i64 {
+        (self.end_date - self.start_date).num_days() + 1
+    }
+
+    pub fn dates_label(&self) -> String {
+        format!("{} – {}", self.start_date.format("%b %-d"), self.end_date.format("%b %-d, %Y"))
+    }
+
+    /// Where the challenge stands, e.g. "Day 3 of 30".
+    pub fn progress_label(&self, today: NaiveDate) -> String {
+        match self.status(today) {
+            ChallengeStatus::Upcoming => format!("Starts {}", self.start_date.format("%b %-d")),
+            ChallengeStatus::Active => {
+                let day = (today - self.start_date).num_days() + 1;
+                format!("Day {day} of {}", self.length_days())
+            }
+            ChallengeStatus::Ended => format!("Ended {}", self.end_date.format("%b %-d")),
+        }
+    }
+}
+
+/// Why a challenge couldn't be created.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ChallengeError {
+    MissingName,
+    EndsBeforeStart,
+    TooLong,
+    AlreadyOver,
+}
+
+impl ChallengeError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            ChallengeError::MissingName => "Give the challenge a name",
+            ChallengeError::EndsBeforeStart => "A challenge can't end before it starts",
+            ChallengeError::TooLong => "Challenges can last at most 365 days",
+            ChallengeError::AlreadyOver => "That challenge would already be over",
+        }
+    }
+}
+
+pub fn validate(name: &str, start: NaiveDate, end: NaiveDate, today: NaiveDate) -> Result<(), ChallengeError> {
+    if name.trim().is_empty() {
+        Err(ChallengeError::MissingName)
+    } else if end < start {
+        Err(ChallengeError::EndsBeforeStart)
+    } else if (end - start).num_days() + 1 > MAX_DAYS {
+        Err(ChallengeError::TooLong)
+    } else if end < today {
+        Err(ChallengeError::AlreadyOver)
+    } else {
+        Ok(())
+    }
+}
+
+pub async fn create(
+    pool: &PgPool,
+    group_id: i64,
+    created_by: i64,
+    name: &str,
+    description: Option<&str>,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> sqlx::Result<i64> {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO challenges (group_id, name, description, start_date, end_date, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id
+        "#,
+    )
+    .bind(group_id)
+    .bind(name.trim())
+    .bind(description)
+    .bind(start)
+    .bind(end)
+    .bind(created_by)
+    .fetch_one(pool)
+    .await
+}
+
+/// The group's challenges, latest first.
+pub async fn for_group(pool: &PgPool, group_id: i64) -> sqlx::Result<Vec<Challenge>> {
+    sqlx::query_as("SELECT * FROM challenges WHERE group_id = $1 ORDER BY start_date DESC, id DESC")
+        .bind(group_id)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn find(pool: &PgPool, group_id: i64, id: i64) -> sqlx::Result<Option<Challenge>> {
+    sqlx::query_as("SELECT * FROM challenges WHERE id = $1 AND group_id = $2")
+        .bind(id)
+        .bind(group_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Signs the user up, creating the daily task they'll track the challenge
+/// with, shown only to the challenge's group. False if they had already
+/// joined.
+pub async fn join(pool: &PgPool, challenge: &Challenge, user_id: i64) -> sqlx::Result<bool> {
+    let mut tx = pool.begin().await?;
+    // Joins to one challenge take turns, so a repeated request sees the
+    // first one's row instead of failing on the primary key
+    sqlx::query("SELECT id FROM challenges WHERE id = $1 FOR UPDATE")
+        .bind(challenge.id)
+        .execute(&mut *tx)
+        .await?;
+    let joined: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM challenge_participants WHERE challenge_id = $1 AND user_id = $2)",
+    )
+    .bind(challenge.id)
+    .bind(user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    if joined {
+        return Ok(false);
+    }
+
+    let details = TaskDetails {
+        name: &challenge.name,
+        description: challenge.description.as_deref(),
+        section: None,
+        schedule: Schedule::Daily,
+        target_value: None,
+        unit: None,
+    };
+    let task_id = Task::create(&mut *tx, user_id, TaskKind::Build, &details).await?;
+    sharing::limit_to_group(&mut tx, task_id, challenge.group_id).await?;
+    sqlx::query("INSERT INTO challenge_participants (challenge_id, user_id, task_id) VALUES ($1, $2, $3)")
+        .bind(challenge.id)
+        .bind(user_id)
+        .bind(task_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// How one participant is doing over the challenge's days so far.
+pub struct Standing {
+    pub user_id: i64,
+    pub username: String,
+    pub completed_days: i64,
+    /// Days of the challenge that have started, up to its end.
+    pub elapsed_days: i64,
+    /// Run of completed days reaching the latest one; today counts once done.
+    pub current_streak: i64,
+    pub best_streak: i64,
+}
+
+impl Standing {
+    pub fn rate_percent(&self) -> i64 {
+        if self.elapsed_days == 0 {
+            return 0;
+        }
+        (self.completed_days as f64 / self.elapsed_days as f64 * 100.0).round() as i64
+    }
+
+    fn from_dates(
+        user_id: i64,
+        username: String,
+        done: &HashSet<NaiveDate>,
+        challenge: &Challenge,
+        today: NaiveDate,
+    ) -> Self {
+        let last = today.min(challenge.end_date);
+        let mut standing = Self {
+            user_id,
+            username,
+            completed_days: 0,
+            elapsed_days: 0,
+            current_streak: 0,
+            best_streak: 0,
+        };
+        if last < challenge.start_date {
+            return standing;
+        }
+
+        let mut run = 0;
+        for day in challenge.start_date.iter_days().take_while(|day| *day <= last) {
+            standing.elapsed_days += 1;
+            if done.contains(&day) {
+                standing.completed_days += 1;
+                run += 1;
+                standing.best_streak = standing.best_streak.max(run);
+            } else {
+                run = 0;
+            }
+        }
+
+        // Today isn't missed until it's over
+        let mut day = if last == today && !done.contains(&today) {
+            today.checked_sub_days(Days::new(1))
+        } else {
+            Some(last)
+        };
+        while let Some(current) = day.filter(|d| *d >= challenge.start_date && done.contains(d)) {
+            standing.current_streak += 1;
+            day = current.checked_sub_days(Days::new(1));
+        }
+        standing
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct ParticipantRow {
+    user_id: i64,
+    username: String,
+    task_id: i64,
+    #[sqlx(flatten)]
+    boundary: DayBoundary,
+}
+
+/// Every participant's standing as of `now`, each on their own day, best
+/// first: by days completed, then longest streak.
+pub async fn standings(pool: &PgPool, challenge: &Challenge, now: DateTime<Utc>) -> sqlx::Result<Vec<Standing>> {
+    let participants: Vec<ParticipantRow> = sqlx::query_as(
+        r#"
+        SELECT p.user_id, u.username, p.task_id, u.time_zone, u.day_ends_hour
+        FROM challenge_participants p
+        JOIN users u ON u.id = p.user_id
+        WHERE p.challenge_id = $1
+        ORDER BY u.username
+        "#,
+    )
+    .bind(challenge.id)
+    .fetch_all(pool)
+    .await?;
+
+    let task_ids: Vec<i64> = participants.iter().map(|p| p.task_id).collect();
+    let mut dates = completion::dates_by_task_since(pool, &task_ids, challenge.start_date).await?;
+    let mut standings: Vec<Standing> = participants
+        .into_iter()
+        .map(|p| {
+            let done: HashSet<NaiveDate> = dates.remove(&p.task_id).unwrap_or_default().into_iter().collect();
+            let today = p.boundary.today(now);
+            Standing::from_dates(p.user_id, p.username, &done, challenge, today)
+        })
+        .collect();
+    standings.sort_by(|a, b| {
+        b.completed_days
+            .cmp(&a.completed_days)
+            .then(b.best_streak.cmp(&a.best_streak))
+    });
+    Ok(standings)
+}
+
+/// The wrap-up shown once a challenge has ended.
+pub struct FinalSummary {
+    /// Everyone tied for the most completed days, if anyone completed any.
+    pub winners: Vec<String>,
+    /// Those who didn't miss a day.
+    pub perfect: Vec<String>,
+    pub average_rate: i64,
+}
+
+impl FinalSummary {
+    pub fn from_standings(standings: &[Standing]) -> Option<Self> {
+        let top = standings.first()?.completed_days;
+        let winners = standings
+            .iter()
+            .filter(|s| top > 0 && s.completed_days == top)
+            .map(|s| s.username.clone())
+            .collect();
+        let perfect = standings
+            .iter()
+            .filter(|s| s.elapsed_days > 0 && s.completed_days == s.elapsed_days)
+            .map(|s| s.username.clone())
+            .collect();
+        let average_rate = standings.iter().map(Standing::rate_percent).sum::<i64>() / standings.len() as i64;
+        Some(Self {
+            winners,
+            perfect,
+            average_rate,
+        })
+    }
+
+    pub fn winners_label(&self) -> String {
+        self.winners.join(", ")
+    }
+
+    pub fn perfect_label(&self) -> String {
+        self.perfect.join(", ")
+    }
+}
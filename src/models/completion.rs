@@ -1,20 +1,268 @@
+use std::collections::HashMap;
+
 use chrono::NaiveDate;
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgExecutor, PgPool};
+
+use crate::models::streak_cache;
 
 pub async fn complete_today(pool: &PgPool, task_id: i64, today: NaiveDate) -> sqlx::Result<()> {
-    sqlx::query("INSERT INTO completions (task_id, completed_date) VALUES ($1, $2) ON CONFLICT DO NOTHING")
+    complete_on(pool, task_id, today, today).await
+}
+
+pub async fn uncomplete_today(pool: &PgPool, task_id: i64, today: NaiveDate) -> sqlx::Result<()> {
+    uncomplete_on(pool, task_id, today, today).await
+}
+
+/// Marks `date` as done, filling a quantitative task up to the target for
+/// that day. The streak cache is refreshed as of `today` in the same
+/// transaction.
+pub async fn complete_on(pool: &PgPool, task_id: i64, date: NaiveDate, today: NaiveDate) -> sqlx::Result<()> {
+    let mut tx = pool.begin().await?;
+    streak_cache::lock(&mut tx, task_id).await?;
+    sqlx::query(
+        r#"
+        INSERT INTO completions (task_id, completed_date, value)
+        SELECT id, $2, COALESCE(target_value, 1) FROM tasks WHERE id = $1
+        ON CONFLICT (task_id, completed_date)
+        DO UPDATE SET value = GREATEST(completions.value, COALESCE(completions.target, EXCLUDED.value))
+        "#,
+    )
+    .bind(task_id)
+    .bind(date)
+    .execute(&mut *tx)
+    .await?;
+    streak_cache::refresh(&mut tx, task_id, today).await?;
+    tx.commit().await
+}
+
+pub async fn uncomplete_on(pool: &PgPool, task_id: i64, date: NaiveDate, today: NaiveDate) -> sqlx::Result<()> {
+    let mut tx = pool.begin().await?;
+    streak_cache::lock(&mut tx, task_id).await?;
+    clear_day(&mut tx, task_id, date).await?;
+    streak_cache::refresh(&mut tx, task_id, today).await?;
+    tx.commit().await
+}
+
+/// Takes back whatever was logged on `date`. A note outlives it: the row
+/// stays with nothing logged so the note isn't lost.
+async fn clear_day(conn: &mut PgConnection, task_id: i64, date: NaiveDate) -> sqlx::Result<()> {
+    sqlx::query("UPDATE completions SET value = 0 WHERE task_id = $1 AND completed_date = $2 AND note IS NOT NULL")
         .bind(task_id)
-        .bind(today)
-        .execute(pool)
+        .bind(date)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query("DELETE FROM completions WHERE task_id = $1 AND completed_date = $2 AND note IS NULL")
+        .bind(task_id)
+        .bind(date)
+        .execute(&mut *conn)
         .await?;
     Ok(())
 }
 
-pub async fn uncomplete_today(pool: &PgPool, task_id: i64, today: NaiveDate) -> sqlx::Result<()> {
-    sqlx::query("DELETE FROM completions WHERE task_id = $1 AND completed_date = $2")
+/// Adds `delta` (which may be negative) to the value logged today, clearing
+/// the day once it drops to zero. Returns the new value.
+pub async fn add_value(pool: &PgPool, task_id: i64, today: NaiveDate, delta: i32) -> sqlx::Result<i32> {
+    let mut tx = pool.begin().await?;
+    streak_cache::lock(&mut tx, task_id).await?;
+
+    let value: i32 = sqlx::query_scalar(
+        r#"
+        INSERT INTO completions (task_id, completed_date, value)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (task_id, completed_date)
+        DO UPDATE SET value = completions.value + EXCLUDED.value
+        RETURNING value
+        "#,
+    )
+    .bind(task_id)
+    .bind(today)
+    .bind(delta)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if value <= 0 {
+        clear_day(&mut tx, task_id, today).await?;
+    }
+
+    streak_cache::refresh(&mut tx, task_id, today).await?;
+    tx.commit().await?;
+    Ok(value.max(0))
+}
+
+/// Days between `from` and `to` (inclusive) on which the task was done.
+pub async fn dates_between(
+    pool: &PgPool,
+    task_id: i64,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> sqlx::Result<Vec<NaiveDate>> {
+    sqlx::query_scalar(
+        r#"
+        SELECT c.completed_date
+        FROM completions c
+        JOIN tasks t ON t.id = c.task_id
+        WHERE c.task_id = $1
+          AND c.completed_date BETWEEN $2 AND $3
+          AND c.value >= COALESCE(c.target, t.target_value, 1)
+        ORDER BY c.completed_date
+        "#,
+    )
+    .bind(task_id)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
+
+/// What was logged on a day and the target it was judged against.
+#[derive(Clone, Copy, Debug)]
+pub struct DayValue {
+    pub value: i32,
+    pub target: i32,
+}
+
+impl DayValue {
+    pub fn reached(&self) -> bool {
+        self.value >= self.target
+    }
+}
+
+/// Value logged on each day between `from` and `to` (inclusive), whether or
+/// not it reached the day's target.
+pub async fn values_between(
+    pool: &PgPool,
+    task_id: i64,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> sqlx::Result<HashMap<NaiveDate, DayValue>> {
+    let rows: Vec<(NaiveDate, i32, i32)> = sqlx::query_as(
+        r#"
+        SELECT c.completed_date, c.value, COALESCE(c.target, t.target_value, 1)
+        FROM completions c
+        JOIN tasks t ON t.id = c.task_id
+        WHERE c.task_id = $1 AND c.completed_date BETWEEN $2 AND $3
+        "#,
+    )
+    .bind(task_id)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(date, value, target)| (date, DayValue { value, target }))
+        .collect())
+}
+
+/// Days on which each task was done, i.e. reached its target if it has one.
+pub async fn dates_by_task(
+    executor: impl PgExecutor<'_>,
+    task_ids: &[i64],
+) -> sqlx::Result<HashMap<i64, Vec<NaiveDate>>> {
+    done_dates(executor, task_ids, None).await
+}
+
+/// Like `dates_by_task`, limited to `from` onwards.
+pub async fn dates_by_task_since(
+    executor: impl PgExecutor<'_>,
+    task_ids: &[i64],
+    from: NaiveDate,
+) -> sqlx::Result<HashMap<i64, Vec<NaiveDate>>> {
+    done_dates(executor, task_ids, Some(from)).await
+}
+
+async fn done_dates(
+    executor: impl PgExecutor<'_>,
+    task_ids: &[i64],
+    from: Option<NaiveDate>,
+) -> sqlx::Result<HashMap<i64, Vec<NaiveDate>>> {
+    let rows: Vec<(i64, NaiveDate)> = sqlx::query_as(
+        r#"
+        SELECT c.task_id, c.completed_date
+        FROM completions c
+        JOIN tasks t ON t.id = c.task_id
+        WHERE c.task_id = ANY($1)
+          AND ($2::DATE IS NULL OR c.completed_date >= $2)
+          AND c.value >= COALESCE(c.target, t.target_value, 1)
+        ORDER BY c.completed_date
+        "#,
+    )
+    .bind(task_ids)
+    .bind(from)
+    .fetch_all(executor)
+    .await?;
+
+    let mut dates: HashMap<i64, Vec<NaiveDate>> = HashMap::new();
+    for (task_id, date) in rows {
+        dates.entry(task_id).or_default().push(date);
+    }
+    Ok(dates)
+}
+
+/// Value logged on `date` for each task that has a completion row that day.
+pub async fn values_on(pool: &PgPool, task_ids: &[i64], date: NaiveDate) -> sqlx::Result<HashMap<i64, i32>> {
+    let rows: Vec<(i64, i32)> = sqlx::query_as(
+        "SELECT task_id, value FROM completions WHERE task_id = ANY($1) AND completed_date = $2",
+    )
+    .bind(task_ids)
+    .bind(date)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().collect())
+}
+
+/// Sets or clears the note on the completion logged on `date`. Returns false
+/// if there's no row that day, since a note needs a completion to hang on.
+/// Clearing the note kept from an undone day drops its row.
+pub async fn set_note(pool: &PgPool, task_id: i64, date: NaiveDate, note: Option<&str>) -> sqlx::Result<bool> {
+    let mut tx = pool.begin().await?;
+    let result = sqlx::query("UPDATE completions SET note = $1 WHERE task_id = $2 AND completed_date = $3")
+        .bind(note)
         .bind(task_id)
-        .bind(today)
-        .execute(pool)
+        .bind(date)
+        .execute(&mut *tx)
         .await?;
-    Ok(())
+    sqlx::query("DELETE FROM completions WHERE task_id = $1 AND completed_date = $2 AND value <= 0 AND note IS NULL")
+        .bind(task_id)
+        .bind(date)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Note on each task's completion on `date`, for tasks that have one.
+pub async fn notes_on(pool: &PgPool, task_ids: &[i64], date: NaiveDate) -> sqlx::Result<HashMap<i64, String>> {
+    let rows: Vec<(i64, String)> = sqlx::query_as(
+        r#"
+        SELECT task_id, note FROM completions
+        WHERE task_id = ANY($1) AND completed_date = $2 AND note IS NOT NULL
+        "#,
+    )
+    .bind(task_ids)
+    .bind(date)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().collect())
+}
+
+/// Notes left between `from` and `to` (inclusive), oldest first.
+pub async fn notes_between(
+    pool: &PgPool,
+    task_id: i64,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> sqlx::Result<Vec<(NaiveDate, String)>> {
+    sqlx::query_as(
+        r#"
+        SELECT completed_date, note FROM completions
+        WHERE task_id = $1 AND completed_date BETWEEN $2 AND $3 AND note IS NOT NULL
+        ORDER BY completed_date
+        "#,
+    )
+    .bind(task_id)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
 }
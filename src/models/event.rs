@@ -0,0 +1,208 @@
+//! A log of what happened to members and their tasks, written as it happens
+//! so group pages can show an activity timeline. Task events reach every
+//! group the task is shared with; membership events belong to one group.
+
+use chrono::NaiveDateTime;
+use sqlx::{PgExecutor, PgPool};
+
+/// How many events a timeline page holds.
+pub const PAGE_SIZE: i64 = 20;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EventKind {
+    Completed,
+    Uncompleted,
+    Milestone,
+    StreakBroken,
+    Joined,
+    Left,
+    Removed,
+}
+
+impl EventKind {
+    pub fn from_column(kind: &str) -> Option<Self> {
+        match kind {
+            "completed" => Some(EventKind::Completed),
+            "uncompleted" => Some(EventKind::Uncompleted),
+            "milestone" => Some(EventKind::Milestone),
+            "streak_broken" => Some(EventKind::StreakBroken),
+            "joined" => Some(EventKind::Joined),
+            "left" => Some(EventKind::Left),
+            "removed" => Some(EventKind::Removed),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::Completed => "completed",
+            EventKind::Uncompleted => "uncompleted",
+            EventKind::Milestone => "milestone",
+            EventKind::StreakBroken => "streak_broken",
+            EventKind::Joined => "joined",
+            EventKind::Left => "left",
+            EventKind::Removed => "removed",
+        }
+    }
+}
+
+/// Records something that happened to one of the user's tasks. `streak` is
+/// the length reached for a milestone or lost for a broken streak.
+pub async fn record_task(
+    executor: impl PgExecutor<'_>,
+    kind: EventKind,
+    user_id: i64,
+    task_id: i64,
+    streak: Option<i64>,
+    now: NaiveDateTime,
+) -> sqlx::Result<()> {
+    sqlx::query("INSERT INTO events (kind, user_id, task_id, streak, created_at) VALUES ($1, $2, $3, $4, $5)")
+        .bind(kind.as_str())
+        .bind(user_id)
+        .bind(task_id)
+        .bind(streak.map(|streak| streak as i32))
+        .bind(now)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// Takes a milestone off the timelines once it's revoked: the latest event
+/// for each of `thresholds`, which is the one that announced it.
+pub async fn withdraw_milestones(pool: &PgPool, task_id: i64, thresholds: &[i32]) -> sqlx::Result<()> {
+    sqlx::query(
+        r#"
+        DELETE FROM events
+        WHERE id IN (SELECT DISTINCT ON (streak) id
+                     FROM events
+                     WHERE kind = $1 AND task_id = $2 AND streak = ANY($3)
+                     ORDER BY streak, id DESC)
+        "#,
+    )
+    .bind(EventKind::Milestone.as_str())
+    .bind(task_id)
+    .bind(thresholds)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Records the user joining or leaving a group.
+pub async fn record_group(
+    pool: &PgPool,
+    kind: EventKind,
+    user_id: i64,
+    group_id: i64,
+    now: NaiveDateTime,
+) -> sqlx::Result<()> {
+    sqlx::query("INSERT INTO events (kind, user_id, group_id, created_at) VALUES ($1, $2, $3, $4)")
+        .bind(kind.as_str())
+        .bind(user_id)
+        .bind(group_id)
+        .bind(now)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+#[derive(sqlx::FromRow)]
+pub struct TimelineEvent {
+    pub id: i64,
+    kind: String,
+    pub username: String,
+    pub task_name: Option<String>,
+    schedule_kind: Option<String>,
+    pub streak: Option<i32>,
+    pub created_at: NaiveDateTime,
+}
+
+impl TimelineEvent {
+    pub fn kind(&self) -> Option<EventKind> {
+        EventKind::from_column(&self.kind)
+    }
+
+    fn streak_unit(&self) -> &'static str {
+        if self.schedule_kind.as_deref() == Some("weekly") { "week" } else { "day" }
+    }
+
+    /// What happened, to follow the member's name.
+    pub fn description(&self) -> String {
+        let task = self.task_name.as_deref().unwrap_or_default();
+        let streak = self.streak.unwrap_or_default();
+        let unit = self.streak_unit();
+        match self.kind() {
+            Some(EventKind::Completed) => format!("completed {task}"),
+            Some(EventKind::Uncompleted) => format!("unmarked {task}"),
+            Some(EventKind::Milestone) => {
+                let plural = if streak == 1 { "" } else { "s" };
+                format!("hit {streak} {unit}{plural} on {task}")
+            }
+            Some(EventKind::StreakBroken) => format!("lost a {streak}-{unit} streak on {task}"),
+            Some(EventKind::Joined) => "joined the group".to_string(),
+            Some(EventKind::Left) => "left the group".to_string(),
+            Some(EventKind::Removed) => "was removed from the group".to_string(),
+            None => String::new(),
+        }
+    }
+
+    /// How long ago it happened.
+    pub fn ago(&self, now: NaiveDateTime) -> String {
+        ago(self.created_at, now)
+    }
+}
+
+/// How long ago `at` was, falling back to the date after a week.
+pub fn ago(at: NaiveDateTime, now: NaiveDateTime) -> String {
+    let elapsed = now - at;
+    if elapsed.num_minutes() < 1 {
+        "just now".to_string()
+    } else if elapsed.num_hours() < 1 {
+        format!("{}m ago", elapsed.num_minutes())
+    } else if elapsed.num_days() < 1 {
+        format!("{}h ago", elapsed.num_hours())
+    } else if elapsed.num_days() < 7 {
+        format!("{}d ago", elapsed.num_days())
+    } else {
+        at.format("%b %-d").to_string()
+    }
+}
+
+/// A page of the group's timeline, newest first, starting below the event
+/// `before` when given. The second value is the cursor for the next page,
+/// if there is one.
+pub async fn timeline(
+    pool: &PgPool,
+    group_id: i64,
+    before: Option<i64>,
+) -> sqlx::Result<(Vec<TimelineEvent>, Option<i64>)> {
+    let mut events: Vec<TimelineEvent> = sqlx::query_as(
+        r#"
+        SELECT e.id, e.kind, u.username, t.name AS task_name, t.schedule_kind, e.streak, e.created_at
+        FROM events e
+        JOIN users u ON u.id = e.user_id
+        LEFT JOIN tasks t ON t.id = e.task_id
+        WHERE ($2::BIGINT IS NULL OR e.id < $2)
+          AND (e.group_id = $1
+               OR (e.task_id IS NOT NULL
+                   AND EXISTS (SELECT 1 FROM group_members gm WHERE gm.group_id = $1 AND gm.user_id = e.user_id)
+                   AND (t.visibility = 'groups'
+                        OR (t.visibility = 'selected'
+                            AND EXISTS (SELECT 1 FROM task_group_shares s WHERE s.task_id = t.id AND s.group_id = $1)))))
+        ORDER BY e.id DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(group_id)
+    .bind(before)
+    .bind(PAGE_SIZE + 1)
+    .fetch_all(pool)
+    .await?;
+
+    let next = if events.len() as i64 > PAGE_SIZE {
+        events.truncate(PAGE_SIZE as usize);
+        events.last().map(|event| event.id)
+    } else {
+        None
+    };
+    Ok((events, next))
+}
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use sqlx::{PgExecutor, PgPool};
+
+use crate::models::streak_cache;
+
+/// Most freeze tokens a user can hold at once.
+pub const MAX_FREEZE_TOKENS: i32 = 3;
+
+/// Streak length at which, and every multiple of which, a token is earned.
+pub const FREEZE_EARN_INTERVAL: i64 = 7;
+
+pub async fn tokens(pool: &PgPool, user_id: i64) -> sqlx::Result<i32> {
+    sqlx::query_scalar("SELECT freeze_tokens FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn award_token(pool: &PgPool, user_id: i64) -> sqlx::Result<()> {
+    sqlx::query("UPDATE users SET freeze_tokens = LEAST(freeze_tokens + 1, $2) WHERE id = $1")
+        .bind(user_id)
+        .bind(MAX_FREEZE_TOKENS)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn revoke_token(pool: &PgPool, user_id: i64) -> sqlx::Result<()> {
+    sqlx::query("UPDATE users SET freeze_tokens = GREATEST(freeze_tokens - 1, 0) WHERE id = $1")
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Spends one of the user's tokens to freeze `date`. Returns `false` without
+/// freezing anything when the user has no tokens left.
+pub async fn freeze(
+    pool: &PgPool,
+    user_id: i64,
+    task_id: i64,
+    date: NaiveDate,
+    today: NaiveDate,
+) -> sqlx::Result<bool> {
+    let mut tx = pool.begin().await?;
+    streak_cache::lock(&mut tx, task_id).await?;
+
+    let spent = sqlx::query(
+        "UPDATE users SET freeze_tokens = freeze_tokens - 1 WHERE id = $1 AND freeze_tokens > 0",
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    if spent == 0 {
+        return Ok(false);
+    }
+
+    sqlx::query("INSERT INTO streak_freezes (task_id, frozen_date) VALUES ($1, $2)")
+        .bind(task_id)
+        .bind(date)
+        .execute(&mut *tx)
+        .await?;
+
+    streak_cache::refresh(&mut tx, task_id, today).await?;
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// Removes the freeze on `date` and refunds its token.
+pub async fn unfreeze(
+    pool: &PgPool,
+    user_id: i64,
+    task_id: i64,
+    date: NaiveDate,
+    today: NaiveDate,
+) -> sqlx::Result<()> {
+    let mut tx = pool.begin().await?;
+    streak_cache::lock(&mut tx, task_id).await?;
+
+    let removed = sqlx::query("DELETE FROM streak_freezes WHERE task_id = $1 AND frozen_date = $2")
+        .bind(task_id)
+        .bind(date)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    if removed > 0 {
+        sqlx::query("UPDATE users SET freeze_tokens = LEAST(freeze_tokens + 1, $2) WHERE id = $1")
+            .bind(user_id)
+            .bind(MAX_FREEZE_TOKENS)
+            .execute(&mut *tx)
+            .await?;
+        streak_cache::refresh(&mut tx, task_id, today).await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn dates_by_task(
+    executor: impl PgExecutor<'_>,
+    task_ids: &[i64],
+) -> sqlx::Result<HashMap<i64, Vec<NaiveDate>>> {
+    let rows: Vec<(i64, NaiveDate)> = sqlx::query_as(
+        "SELECT task_id, frozen_date FROM streak_freezes WHERE task_id = ANY($1) ORDER BY frozen_date",
+    )
+    .bind(task_ids)
+    .fetch_all(executor)
+    .await?;
+
+    let mut dates: HashMap<i64, Vec<NaiveDate>> = HashMap::new();
+    for (task_id, date) in rows {
+        dates.entry(task_id).or_default().push(date);
+    }
+    Ok(dates)
+}
@@ -1,6 +1,13 @@
-use chrono::{NaiveDate, NaiveDateTime};
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use rand::Rng;
-use sqlx::PgPool;
+use sqlx::{PgExecutor, PgPool};
+
+use crate::models::{group_habit, milestone};
+use crate::models::reaction::{self, ReactionButton, ReactionSummary};
+use crate::models::task::{Task, TaskWithStreak};
+use crate::models::user::DayBoundary;
 
 #[derive(sqlx::FromRow)]
 #[allow(dead_code)]
@@ -19,20 +26,125 @@ pub struct GroupWithMembership {
     pub name: String,
     pub invite_code: String,
     pub member_count: i64,
+    pub role: String,
+}
+
+impl GroupWithMembership {
+    pub fn role(&self) -> GroupRole {
+        GroupRole::from_column(&self.role)
+    }
+}
+
+/// What a member may do in a group. Stored on `group_members` as `role`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GroupRole {
+    /// The creator; manages admins.
+    Owner,
+    /// Sees the invite code and helps run the group.
+    Admin,
+    Member,
+}
+
+impl GroupRole {
+    pub fn from_column(role: &str) -> Self {
+        match role {
+            "owner" => GroupRole::Owner,
+            "admin" => GroupRole::Admin,
+            _ => GroupRole::Member,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GroupRole::Owner => "owner",
+            GroupRole::Admin => "admin",
+            GroupRole::Member => "member",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GroupRole::Owner => "Owner",
+            GroupRole::Admin => "Admin",
+            GroupRole::Member => "Member",
+        }
+    }
+
+    /// Owners and admins run the group day to day.
+    pub fn can_manage(&self) -> bool {
+        matches!(self, GroupRole::Owner | GroupRole::Admin)
+    }
+
+    pub fn is_owner(&self) -> bool {
+        *self == GroupRole::Owner
+    }
+
+    /// Owners remove anyone else; admins only plain members.
+    pub fn can_remove(&self, target: GroupRole) -> bool {
+        match self {
+            GroupRole::Owner => !target.is_owner(),
+            GroupRole::Admin => target == GroupRole::Member,
+            GroupRole::Member => false,
+        }
+    }
+}
+
+/// What happened when a member left.
+#[derive(PartialEq, Eq, Debug)]
+pub enum LeaveOutcome {
+    Left,
+    /// The owner left and ownership passed to this member.
+    HandedOver(String),
+    /// The last member left, so the group was deleted.
+    Deleted,
 }
 
 #[derive(sqlx::FromRow)]
+pub struct GroupMember {
+    pub user_id: i64,
+    pub username: String,
+    pub role: String,
+}
+
+impl GroupMember {
+    pub fn role(&self) -> GroupRole {
+        GroupRole::from_column(&self.role)
+    }
+}
+
 #[allow(dead_code)]
 pub struct MemberWithStreaks {
     pub user_id: i64,
     pub username: String,
     pub task_id: i64,
     pub task_name: String,
+    pub avoid: bool,
     pub current_streak: i64,
+    pub frozen: bool,
     pub completed_today: bool,
+    pub due_today: bool,
+    pub reactions: Vec<ReactionSummary>,
+    /// The latest milestone the task reached, while its streak still covers it.
+    pub milestone: Option<i32>,
+    pub streak_unit: &'static str,
+}
+
+impl MemberWithStreaks {
+    pub fn reaction_buttons(&self, viewer_id: i64) -> Vec<ReactionButton> {
+        reaction::buttons(&self.reactions, viewer_id)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct MemberTaskRow {
+    username: String,
+    #[sqlx(flatten)]
+    boundary: DayBoundary,
+    #[sqlx(flatten)]
+    task: Task,
 }
 
-fn generate_invite_code() -> String {
+pub(crate) fn generate_invite_code() -> String {
     let mut rng = rand::rng();
     let chars: Vec<char> = "ABCDEFGHJKLMNPQRSTUVWXYZ23456789".chars().collect();
     (0..8).map(|_| chars[rng.random_range(0..chars.len())]).collect()
@@ -50,8 +162,8 @@ impl Group {
         .fetch_one(pool)
         .await?;
 
-        // Creator auto-joins the group
-        sqlx::query("INSERT INTO group_members (group_id, user_id) VALUES ($1, $2)")
+        // Creator auto-joins the group as its owner
+        sqlx::query("INSERT INTO group_members (group_id, user_id, role) VALUES ($1, $2, 'owner')")
             .bind(group_id)
             .bind(created_by)
             .execute(pool)
@@ -60,33 +172,42 @@ impl Group {
         Ok(group_id)
     }
 
-    pub async fn find_by_id(pool: &PgPool, id: i64) -> sqlx::Result<Option<Self>> {
+    pub async fn find_by_id(executor: impl PgExecutor<'_>, id: i64) -> sqlx::Result<Option<Self>> {
         sqlx::query_as("SELECT * FROM groups WHERE id = $1")
             .bind(id)
-            .fetch_optional(pool)
+            .fetch_optional(executor)
             .await
     }
 
-    pub async fn find_by_invite_code(pool: &PgPool, code: &str) -> sqlx::Result<Option<Self>> {
+    pub async fn find_by_invite_code(executor: impl PgExecutor<'_>, code: &str) -> sqlx::Result<Option<Self>> {
         sqlx::query_as("SELECT * FROM groups WHERE invite_code = $1")
             .bind(code)
-            .fetch_optional(pool)
+            .fetch_optional(executor)
             .await
     }
 
-    pub async fn join(pool: &PgPool, group_id: i64, user_id: i64) -> sqlx::Result<()> {
-        sqlx::query("INSERT INTO group_members (group_id, user_id) VALUES ($1, $2) ON CONFLICT DO NOTHING")
+    pub async fn member_count(pool: &PgPool, group_id: i64) -> sqlx::Result<i64> {
+        sqlx::query_scalar("SELECT COUNT(*) FROM group_members WHERE group_id = $1")
             .bind(group_id)
-            .bind(user_id)
-            .execute(pool)
-            .await?;
-        Ok(())
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Adds the user as a plain member. Returns false if they already were one.
+    pub async fn join(executor: impl PgExecutor<'_>, group_id: i64, user_id: i64) -> sqlx::Result<bool> {
+        let result =
+            sqlx::query("INSERT INTO group_members (group_id, user_id) VALUES ($1, $2) ON CONFLICT DO NOTHING")
+                .bind(group_id)
+                .bind(user_id)
+                .execute(executor)
+                .await?;
+        Ok(result.rows_affected() > 0)
     }
 
     pub async fn user_groups(pool: &PgPool, user_id: i64) -> sqlx::Result<Vec<GroupWithMembership>> {
         sqlx::query_as(
             r#"
-            SELECT g.id, g.name, g.invite_code,
+            SELECT g.id, g.name, g.invite_code, gm.role,
                    (SELECT COUNT(*) FROM group_members gm2 WHERE gm2.group_id = g.id) AS member_count
             FROM groups g
             JOIN group_members gm ON gm.group_id = g.id
@@ -99,57 +220,247 @@ impl Group {
         .await
     }
 
-    pub async fn member_streaks(pool: &PgPool, group_id: i64, today: NaiveDate) -> sqlx::Result<Vec<MemberWithStreaks>> {
+    /// The user's role in the group, or `None` if they aren't a member.
+    pub async fn role_of(pool: &PgPool, group_id: i64, user_id: i64) -> sqlx::Result<Option<GroupRole>> {
+        let role: Option<String> =
+            sqlx::query_scalar("SELECT role FROM group_members WHERE group_id = $1 AND user_id = $2")
+                .bind(group_id)
+                .bind(user_id)
+                .fetch_optional(pool)
+                .await?;
+        Ok(role.as_deref().map(GroupRole::from_column))
+    }
+
+    /// Members with their roles, owner first, then admins, then by name.
+    pub async fn members(pool: &PgPool, group_id: i64) -> sqlx::Result<Vec<GroupMember>> {
         sqlx::query_as(
             r#"
-            WITH RECURSIVE streak_cte(task_id, streak_date, streak_count) AS (
-                -- Base case: completed today
-                SELECT c.task_id, c.completed_date, 1
-                FROM completions c
-                JOIN tasks t ON t.id = c.task_id
-                JOIN group_members gm ON gm.user_id = t.user_id AND gm.group_id = $1
-                WHERE c.completed_date = $2
-                UNION ALL
-                -- Base case: completed yesterday but not today
-                SELECT c.task_id, c.completed_date, 1
-                FROM completions c
-                JOIN tasks t ON t.id = c.task_id
-                JOIN group_members gm ON gm.user_id = t.user_id AND gm.group_id = $1
-                WHERE c.completed_date = $2 - INTERVAL '1 day'
-                  AND NOT EXISTS (
-                      SELECT 1 FROM completions c2
-                      WHERE c2.task_id = c.task_id
-                        AND c2.completed_date = $2
-                  )
-                UNION ALL
-                -- Recursive: walk backwards day by day
-                SELECT s.task_id, c.completed_date, s.streak_count + 1
-                FROM streak_cte s
-                JOIN completions c ON c.task_id = s.task_id
-                  AND c.completed_date = s.streak_date - INTERVAL '1 day'
-            )
-            SELECT
-                u.id AS user_id,
-                u.username,
-                t.id AS task_id,
-                t.name AS task_name,
-                COALESCE(MAX(s.streak_count), 0)::BIGINT AS current_streak,
-                EXISTS (
-                    SELECT 1 FROM completions c
-                    WHERE c.task_id = t.id AND c.completed_date = $2
-                ) AS completed_today
+            SELECT u.id AS user_id, u.username, gm.role
+            FROM group_members gm
+            JOIN users u ON u.id = gm.user_id
+            WHERE gm.group_id = $1
+            ORDER BY CASE gm.role WHEN 'owner' THEN 0 WHEN 'admin' THEN 1 ELSE 2 END, u.username
+            "#,
+        )
+        .bind(group_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn member(pool: &PgPool, group_id: i64, user_id: i64) -> sqlx::Result<Option<GroupMember>> {
+        sqlx::query_as(
+            r#"
+            SELECT u.id AS user_id, u.username, gm.role
+            FROM group_members gm
+            JOIN users u ON u.id = gm.user_id
+            WHERE gm.group_id = $1 AND gm.user_id = $2
+            "#,
+        )
+        .bind(group_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Makes a member an admin or back again. The owner's role never changes
+    /// here; returns false if `user_id` isn't a non-owner member.
+    pub async fn set_role(pool: &PgPool, group_id: i64, user_id: i64, role: GroupRole) -> sqlx::Result<bool> {
+        if role.is_owner() {
+            return Ok(false);
+        }
+        let result = sqlx::query(
+            "UPDATE group_members SET role = $1 WHERE group_id = $2 AND user_id = $3 AND role <> 'owner'",
+        )
+        .bind(role.as_str())
+        .bind(group_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Who takes over if the owner leaves: the longest-standing admin, or
+    /// failing that the longest-standing member.
+    pub async fn successor(
+        executor: impl PgExecutor<'_>,
+        group_id: i64,
+        owner_id: i64,
+    ) -> sqlx::Result<Option<GroupMember>> {
+        sqlx::query_as(
+            r#"
+            SELECT u.id AS user_id, u.username, gm.role
+            FROM group_members gm
+            JOIN users u ON u.id = gm.user_id
+            WHERE gm.group_id = $1 AND gm.user_id <> $2
+            ORDER BY (gm.role = 'admin') DESC, gm.joined_at, gm.user_id
+            LIMIT 1
+            "#,
+        )
+        .bind(group_id)
+        .bind(owner_id)
+        .fetch_optional(executor)
+        .await
+    }
+
+    /// Takes the user out of the group and its habits as of their `today`.
+    /// An owner hands the group to their successor first, and the last
+    /// member out deletes it.
+    pub async fn leave(pool: &PgPool, group_id: i64, user_id: i64, today: NaiveDate) -> sqlx::Result<LeaveOutcome> {
+        let mut tx = pool.begin().await?;
+        // Serialise leaves so the group can't be left without an owner
+        sqlx::query("SELECT id FROM groups WHERE id = $1 FOR UPDATE")
+            .bind(group_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let role: Option<String> = sqlx::query_scalar(
+            "DELETE FROM group_members WHERE group_id = $1 AND user_id = $2 RETURNING role",
+        )
+        .bind(group_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        group_habit::leave_group(&mut *tx, group_id, user_id, today).await?;
+
+        let mut outcome = LeaveOutcome::Left;
+        if role.as_deref().map(GroupRole::from_column) == Some(GroupRole::Owner) {
+            match Self::successor(&mut *tx, group_id, user_id).await? {
+                Some(successor) => {
+                    sqlx::query("UPDATE group_members SET role = 'owner' WHERE group_id = $1 AND user_id = $2")
+                        .bind(group_id)
+                        .bind(successor.user_id)
+                        .execute(&mut *tx)
+                        .await?;
+                    outcome = LeaveOutcome::HandedOver(successor.username);
+                }
+                None => {
+                    sqlx::query("DELETE FROM groups WHERE id = $1")
+                        .bind(group_id)
+                        .execute(&mut *tx)
+                        .await?;
+                    outcome = LeaveOutcome::Deleted;
+                }
+            }
+        }
+        tx.commit().await?;
+        Ok(outcome)
+    }
+
+    /// Removes a member other than the owner, taking them out of the group's
+    /// habits as of their `today`. Returns false if `user_id` isn't a
+    /// non-owner member.
+    pub async fn remove_member(pool: &PgPool, group_id: i64, user_id: i64, today: NaiveDate) -> sqlx::Result<bool> {
+        let mut tx = pool.begin().await?;
+        let result =
+            sqlx::query("DELETE FROM group_members WHERE group_id = $1 AND user_id = $2 AND role <> 'owner'")
+                .bind(group_id)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+        group_habit::leave_group(&mut *tx, group_id, user_id, today).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Makes `to` the owner and the previous owner an admin. Returns false
+    /// if `to` isn't another member of the group.
+    pub async fn transfer_ownership(pool: &PgPool, group_id: i64, from: i64, to: i64) -> sqlx::Result<bool> {
+        let mut tx = pool.begin().await?;
+        let promoted = sqlx::query(
+            "UPDATE group_members SET role = 'owner' WHERE group_id = $1 AND user_id = $2 AND role <> 'owner'",
+        )
+        .bind(group_id)
+        .bind(to)
+        .execute(&mut *tx)
+        .await?;
+        if promoted.rows_affected() == 0 {
+            return Ok(false);
+        }
+        sqlx::query("UPDATE group_members SET role = 'admin' WHERE group_id = $1 AND user_id = $2")
+            .bind(group_id)
+            .bind(from)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Deletes the group along with its memberships.
+    pub async fn delete(pool: &PgPool, group_id: i64) -> sqlx::Result<()> {
+        let mut tx = pool.begin().await?;
+        sqlx::query("DELETE FROM group_members WHERE group_id = $1")
+            .bind(group_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM groups WHERE id = $1")
+            .bind(group_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await
+    }
+
+    /// Each member's shared tasks as of `now`, judged by the member's own
+    /// day so someone in another zone isn't shown a day ahead or behind.
+    pub async fn member_streaks(
+        pool: &PgPool,
+        group_id: i64,
+        now: DateTime<Utc>,
+    ) -> sqlx::Result<Vec<MemberWithStreaks>> {
+        let rows: Vec<MemberTaskRow> = sqlx::query_as(
+            r#"
+            SELECT u.username, u.time_zone, u.day_ends_hour, t.*
             FROM group_members gm
             JOIN users u ON u.id = gm.user_id
             JOIN tasks t ON t.user_id = u.id AND t.archived = FALSE
-            LEFT JOIN streak_cte s ON s.task_id = t.id
             WHERE gm.group_id = $1
-            GROUP BY t.id, u.id, u.username, t.name
+              AND (t.visibility = 'groups'
+                   OR (t.visibility = 'selected'
+                       AND EXISTS (SELECT 1 FROM task_group_shares s WHERE s.task_id = t.id AND s.group_id = $1)))
             ORDER BY u.username, t.name
             "#,
         )
         .bind(group_id)
-        .bind(today)
         .fetch_all(pool)
-        .await
+        .await?;
+
+        let mut usernames = Vec::with_capacity(rows.len());
+        let mut by_day: HashMap<NaiveDate, Vec<Task>> = HashMap::new();
+        for row in rows {
+            usernames.push((row.username, row.task.id));
+            by_day.entry(row.boundary.today(now)).or_default().push(row.task);
+        }
+        let ids: Vec<i64> = usernames.iter().map(|(_, id)| *id).collect();
+        let milestones = milestone::latest(pool, &ids).await?;
+        let mut tasks: HashMap<i64, TaskWithStreak> = HashMap::new();
+        for (today, day_tasks) in by_day {
+            for task in TaskWithStreak::from_tasks(pool, day_tasks, today).await? {
+                tasks.insert(task.id, task);
+            }
+        }
+
+        Ok(usernames
+            .into_iter()
+            .filter_map(|(username, id)| tasks.remove(&id).map(|task| (username, task)))
+            .map(|(username, task)| MemberWithStreaks {
+                milestone: milestones
+                    .get(&task.id)
+                    .copied()
+                    .filter(|threshold| i64::from(*threshold) <= task.current_streak),
+                streak_unit: task.streak_unit(),
+                user_id: task.user_id,
+                username,
+                task_id: task.id,
+                avoid: task.is_avoid(),
+                task_name: task.name,
+                current_streak: task.current_streak,
+                frozen: task.frozen,
+                completed_today: task.completed_today,
+                due_today: task.due_today,
+                reactions: task.reactions,
+            })
+            .collect())
     }
 }
@@ -0,0 +1,316 @@
+//! Habits a group keeps together. Members opt in and check in each day; the
+//! group's streak runs while enough of them do. Each day is judged by who was
+//! taking part that day, from the day they opted in until the day they left
+//! the habit or the group, so leaving never rewrites earlier days.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Days, NaiveDate, NaiveDateTime, Utc};
+use sqlx::{PgExecutor, PgPool};
+
+use crate::models::user::DayBoundary;
+
+#[derive(sqlx::FromRow)]
+#[allow(dead_code)]
+pub struct GroupHabit {
+    pub id: i64,
+    pub group_id: i64,
+    pub name: String,
+    /// Share of participants, 1 to 100, who must check in for a day to count.
+    pub required_percent: i16,
+    pub created_by: i64,
+    pub created_at: NaiveDateTime,
+}
+
+/// Whether `done` check-ins out of `expected` participants keep the streak.
+pub fn day_met(done: usize, expected: usize, required_percent: i16) -> bool {
+    expected > 0 && done * 100 >= expected * required_percent as usize
+}
+
+#[derive(sqlx::FromRow)]
+struct ParticipantRow {
+    user_id: i64,
+    username: String,
+    #[sqlx(flatten)]
+    boundary: DayBoundary,
+}
+
+/// One stretch of someone taking part, from `joined_on` up to but not
+/// including `left_on`.
+#[derive(sqlx::FromRow)]
+struct Stint {
+    user_id: i64,
+    joined_on: NaiveDate,
+    left_on: Option<NaiveDate>,
+}
+
+impl Stint {
+    fn covers(&self, day: NaiveDate) -> bool {
+        self.joined_on <= day && self.left_on.is_none_or(|left_on| day < left_on)
+    }
+}
+
+pub struct Participant {
+    pub user_id: i64,
+    pub username: String,
+    pub done_today: bool,
+}
+
+/// A habit with today's check-ins and the group's streak on it.
+pub struct HabitStatus {
+    pub habit: GroupHabit,
+    pub participants: Vec<Participant>,
+    /// Days in a row the group met the habit; today counts once it's met.
+    pub streak: i64,
+}
+
+impl HabitStatus {
+    pub fn done_count(&self) -> usize {
+        self.participants.iter().filter(|p| p.done_today).count()
+    }
+
+    pub fn met_today(&self) -> bool {
+        day_met(self.done_count(), self.participants.len(), self.habit.required_percent)
+    }
+
+    /// Participants who haven't checked in today, comma-separated.
+    pub fn missing_label(&self) -> String {
+        self.participants
+            .iter()
+            .filter(|p| !p.done_today)
+            .map(|p| p.username.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn is_participant(&self, user_id: i64) -> bool {
+        self.participants.iter().any(|p| p.user_id == user_id)
+    }
+
+    pub fn checked_in(&self, user_id: i64) -> bool {
+        self.participants.iter().any(|p| p.user_id == user_id && p.done_today)
+    }
+
+    /// What a day takes, e.g. "Needs 75% of 4".
+    pub fn rule_label(&self) -> String {
+        if self.habit.required_percent >= 100 {
+            "Needs everyone".to_string()
+        } else {
+            format!("Needs {}% of {}", self.habit.required_percent, self.participants.len())
+        }
+    }
+}
+
+/// Creates the habit with its creator already taking part.
+pub async fn create(
+    pool: &PgPool,
+    group_id: i64,
+    created_by: i64,
+    name: &str,
+    required_percent: i16,
+    today: NaiveDate,
+) -> sqlx::Result<i64> {
+    let mut tx = pool.begin().await?;
+    let id: i64 = sqlx::query_scalar(
+        "INSERT INTO group_habits (group_id, name, required_percent, created_by) VALUES ($1, $2, $3, $4) RETURNING id",
+    )
+    .bind(group_id)
+    .bind(name.trim())
+    .bind(required_percent)
+    .bind(created_by)
+    .fetch_one(&mut *tx)
+    .await?;
+    sqlx::query("INSERT INTO group_habit_members (habit_id, user_id, joined_on) VALUES ($1, $2, $3)")
+        .bind(id)
+        .bind(created_by)
+        .bind(today)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(id)
+}
+
+pub async fn find(pool: &PgPool, group_id: i64, id: i64) -> sqlx::Result<Option<GroupHabit>> {
+    sqlx::query_as("SELECT * FROM group_habits WHERE id = $1 AND group_id = $2")
+        .bind(id)
+        .bind(group_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Every habit of the group with its status, oldest first.
+pub async fn for_group(pool: &PgPool, group_id: i64, now: DateTime<Utc>) -> sqlx::Result<Vec<HabitStatus>> {
+    let habits: Vec<GroupHabit> = sqlx::query_as("SELECT * FROM group_habits WHERE group_id = $1 ORDER BY id")
+        .bind(group_id)
+        .fetch_all(pool)
+        .await?;
+    let mut statuses = Vec::with_capacity(habits.len());
+    for habit in habits {
+        statuses.push(status(pool, habit, now).await?);
+    }
+    Ok(statuses)
+}
+
+/// The habit as of `now`. Each participant's check-in for today is read on
+/// their own day, and the streak leaves alone any day one of them is still
+/// living through.
+pub async fn status(pool: &PgPool, habit: GroupHabit, now: DateTime<Utc>) -> sqlx::Result<HabitStatus> {
+    let participants: Vec<ParticipantRow> = sqlx::query_as(
+        r#"
+        SELECT hm.user_id, u.username, u.time_zone, u.day_ends_hour
+        FROM group_habit_members hm
+        JOIN users u ON u.id = hm.user_id
+        JOIN group_members gm ON gm.user_id = hm.user_id AND gm.group_id = $2
+        WHERE hm.habit_id = $1 AND hm.left_on IS NULL
+        ORDER BY u.username
+        "#,
+    )
+    .bind(habit.id)
+    .bind(habit.group_id)
+    .fetch_all(pool)
+    .await?;
+    let stints: Vec<Stint> =
+        sqlx::query_as("SELECT user_id, joined_on, left_on FROM group_habit_members WHERE habit_id = $1")
+            .bind(habit.id)
+            .fetch_all(pool)
+            .await?;
+    let todays: Vec<NaiveDate> = participants.iter().map(|p| p.boundary.today(now)).collect();
+    let latest = todays.iter().max().copied().unwrap_or_else(|| DayBoundary::default().today(now));
+    let earliest = todays.iter().min().copied().unwrap_or(latest);
+
+    let rows: Vec<(i64, NaiveDate)> = sqlx::query_as(
+        "SELECT user_id, checkin_date FROM group_habit_checkins WHERE habit_id = $1 AND checkin_date <= $2",
+    )
+    .bind(habit.id)
+    .bind(latest)
+    .fetch_all(pool)
+    .await?;
+
+    let mut checkins: HashMap<NaiveDate, HashSet<i64>> = HashMap::new();
+    for (user_id, date) in rows {
+        checkins.entry(date).or_default().insert(user_id);
+    }
+    let streak = team_streak(&stints, &checkins, habit.required_percent, earliest, latest);
+
+    Ok(HabitStatus {
+        participants: participants
+            .into_iter()
+            .zip(todays)
+            .map(|(p, today)| Participant {
+                done_today: checkins.get(&today).is_some_and(|users| users.contains(&p.user_id)),
+                user_id: p.user_id,
+                username: p.username,
+            })
+            .collect(),
+        habit,
+        streak,
+    })
+}
+
+/// Counts back from `latest`, the furthest-ahead participant's today, while
+/// each day was met by the participants taking part that day. Days from
+/// `earliest` on aren't over for everyone yet, so they can't break the
+/// streak.
+fn team_streak(
+    stints: &[Stint],
+    checkins: &HashMap<NaiveDate, HashSet<i64>>,
+    required_percent: i16,
+    earliest: NaiveDate,
+    latest: NaiveDate,
+) -> i64 {
+    let met = |day: NaiveDate| {
+        let expected: HashSet<i64> = stints.iter().filter(|s| s.covers(day)).map(|s| s.user_id).collect();
+        let done = checkins
+            .get(&day)
+            .map(|users| expected.iter().filter(|id| users.contains(id)).count())
+            .unwrap_or(0);
+        day_met(done, expected.len(), required_percent)
+    };
+
+    let mut streak = 0;
+    let mut day = Some(latest);
+    while let Some(current) = day {
+        if met(current) {
+            streak += 1;
+        } else if current < earliest {
+            break;
+        }
+        day = current.checked_sub_days(Days::new(1));
+    }
+    streak
+}
+
+/// Opts the user in from `today`. False if they already take part.
+pub async fn join(pool: &PgPool, habit_id: i64, user_id: i64, today: NaiveDate) -> sqlx::Result<bool> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO group_habit_members (habit_id, user_id, joined_on) VALUES ($1, $2, $3)
+        ON CONFLICT (habit_id, user_id) WHERE left_on IS NULL DO NOTHING
+        "#,
+    )
+    .bind(habit_id)
+    .bind(user_id)
+    .bind(today)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Ends the user's part in the habit; from `today` on they aren't expected.
+pub async fn leave(pool: &PgPool, habit_id: i64, user_id: i64, today: NaiveDate) -> sqlx::Result<()> {
+    sqlx::query(
+        "UPDATE group_habit_members SET left_on = $3 WHERE habit_id = $1 AND user_id = $2 AND left_on IS NULL",
+    )
+    .bind(habit_id)
+    .bind(user_id)
+    .bind(today)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Ends the user's part in every habit of a group they left or were
+/// removed from.
+pub async fn leave_group(
+    executor: impl PgExecutor<'_>,
+    group_id: i64,
+    user_id: i64,
+    today: NaiveDate,
+) -> sqlx::Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE group_habit_members hm SET left_on = $3
+        FROM group_habits h
+        WHERE h.id = hm.habit_id AND h.group_id = $1 AND hm.user_id = $2 AND hm.left_on IS NULL
+        "#,
+    )
+    .bind(group_id)
+    .bind(user_id)
+    .bind(today)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Checks the user in for `date`, or takes the check-in back if they already
+/// had. Returns whether they're now checked in.
+pub async fn toggle_checkin(pool: &PgPool, habit_id: i64, user_id: i64, date: NaiveDate) -> sqlx::Result<bool> {
+    let removed = sqlx::query(
+        "DELETE FROM group_habit_checkins WHERE habit_id = $1 AND user_id = $2 AND checkin_date = $3",
+    )
+    .bind(habit_id)
+    .bind(user_id)
+    .bind(date)
+    .execute(pool)
+    .await?;
+    if removed.rows_affected() > 0 {
+        return Ok(false);
+    }
+    sqlx::query("INSERT INTO group_habit_checkins (habit_id, user_id, checkin_date) VALUES ($1, $2, $3)")
+        .bind(habit_id)
+        .bind(user_id)
+        .bind(date)
+        .execute(pool)
+        .await?;
+    Ok(true)
+}
@@ -0,0 +1,206 @@
+//! Ways into a group: its main invite code, which admins can rotate, and
+//! extra invites that may expire, run out of uses or be revoked.
+
+use chrono::{NaiveDateTime, TimeDelta};
+use sqlx::{PgConnection, PgPool};
+
+use crate::models::group::{Group, generate_invite_code};
+
+/// Longest an invite can stay open, in hours.
+pub const MAX_EXPIRY_HOURS: i64 = 30 * 24;
+
+/// Most people a single invite can let in.
+pub const MAX_USES: i32 = 100;
+
+#[derive(sqlx::FromRow)]
+#[allow(dead_code)]
+pub struct Invite {
+    pub id: i64,
+    pub group_id: i64,
+    pub code: String,
+    pub created_by: i64,
+    pub expires_at: Option<NaiveDateTime>,
+    pub max_uses: Option<i32>,
+    pub uses: i32,
+    pub revoked: bool,
+    pub created_at: NaiveDateTime,
+}
+
+impl Invite {
+    pub fn is_expired(&self, now: NaiveDateTime) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    pub fn is_used_up(&self) -> bool {
+        self.max_uses.is_some_and(|max_uses| self.uses >= max_uses)
+    }
+
+    /// Short state for the invite list.
+    pub fn status(&self, now: NaiveDateTime) -> &'static str {
+        if self.revoked {
+            "Revoked"
+        } else if self.is_expired(now) {
+            "Expired"
+        } else if self.is_used_up() {
+            "Used up"
+        } else {
+            "Active"
+        }
+    }
+
+    pub fn uses_label(&self) -> String {
+        match self.max_uses {
+            Some(max_uses) => format!("{} / {max_uses} uses", self.uses),
+            None => format!("{} uses", self.uses),
+        }
+    }
+
+    pub fn expires_label(&self) -> String {
+        match self.expires_at {
+            Some(expires_at) => format!("Expires {} UTC", expires_at.format("%b %-d, %H:%M")),
+            None => "Never expires".to_string(),
+        }
+    }
+}
+
+/// Why a code didn't get someone into a group.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum JoinError {
+    Unknown,
+    Revoked,
+    Expired,
+    UsedUp,
+    AlreadyMember,
+}
+
+impl JoinError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            JoinError::Unknown => "That invite code doesn't match any group",
+            JoinError::Revoked => "That invite has been revoked",
+            JoinError::Expired => "That invite has expired",
+            JoinError::UsedUp => "That invite has already been used as many times as allowed",
+            JoinError::AlreadyMember => "You're already a member of that group",
+        }
+    }
+}
+
+/// The group `code` lets someone into, with the extra invite it belongs to
+/// if it isn't the group's main code.
+async fn resolve(
+    conn: &mut PgConnection,
+    code: &str,
+    now: NaiveDateTime,
+) -> sqlx::Result<Result<(Group, Option<i64>), JoinError>> {
+    if let Some(group) = Group::find_by_invite_code(&mut *conn, code).await? {
+        return Ok(Ok((group, None)));
+    }
+
+    // Locked so concurrent joins can't overrun `max_uses`
+    let invite: Option<Invite> = sqlx::query_as("SELECT * FROM group_invites WHERE code = $1 FOR UPDATE")
+        .bind(code)
+        .fetch_optional(&mut *conn)
+        .await?;
+    let Some(invite) = invite else {
+        return Ok(Err(JoinError::Unknown));
+    };
+    if invite.revoked {
+        return Ok(Err(JoinError::Revoked));
+    }
+    if invite.is_expired(now) {
+        return Ok(Err(JoinError::Expired));
+    }
+    if invite.is_used_up() {
+        return Ok(Err(JoinError::UsedUp));
+    }
+    match Group::find_by_id(&mut *conn, invite.group_id).await? {
+        Some(group) => Ok(Ok((group, Some(invite.id)))),
+        None => Ok(Err(JoinError::Unknown)),
+    }
+}
+
+/// The group `code` would let someone into right now, without joining it.
+pub async fn lookup(pool: &PgPool, code: &str, now: NaiveDateTime) -> sqlx::Result<Result<Group, JoinError>> {
+    let mut conn = pool.acquire().await?;
+    Ok(resolve(&mut conn, code, now).await?.map(|(group, _)| group))
+}
+
+/// Joins the user to the group `code` belongs to, counting a use against an
+/// extra invite. Returns the group, or why the code was turned down.
+pub async fn redeem(
+    pool: &PgPool,
+    code: &str,
+    user_id: i64,
+    now: NaiveDateTime,
+) -> sqlx::Result<Result<Group, JoinError>> {
+    let mut tx = pool.begin().await?;
+    let (group, invite_id) = match resolve(&mut tx, code, now).await? {
+        Ok(found) => found,
+        Err(err) => return Ok(Err(err)),
+    };
+
+    if !Group::join(&mut *tx, group.id, user_id).await? {
+        return Ok(Err(JoinError::AlreadyMember));
+    }
+    if let Some(invite_id) = invite_id {
+        sqlx::query("UPDATE group_invites SET uses = uses + 1 WHERE id = $1")
+            .bind(invite_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(Ok(group))
+}
+
+/// Replaces the group's main code, so the old one stops working.
+pub async fn rotate_code(pool: &PgPool, group_id: i64) -> sqlx::Result<String> {
+    let code = generate_invite_code();
+    sqlx::query("UPDATE groups SET invite_code = $1 WHERE id = $2")
+        .bind(&code)
+        .bind(group_id)
+        .execute(pool)
+        .await?;
+    Ok(code)
+}
+
+pub async fn create(
+    pool: &PgPool,
+    group_id: i64,
+    created_by: i64,
+    expires_in: Option<TimeDelta>,
+    max_uses: Option<i32>,
+    now: NaiveDateTime,
+) -> sqlx::Result<Invite> {
+    sqlx::query_as(
+        r#"
+        INSERT INTO group_invites (group_id, code, created_by, expires_at, max_uses)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING *
+        "#,
+    )
+    .bind(group_id)
+    .bind(generate_invite_code())
+    .bind(created_by)
+    .bind(expires_in.map(|expires_in| now + expires_in))
+    .bind(max_uses)
+    .fetch_one(pool)
+    .await
+}
+
+/// The group's invites that haven't been revoked, newest first.
+pub async fn for_group(pool: &PgPool, group_id: i64) -> sqlx::Result<Vec<Invite>> {
+    sqlx::query_as("SELECT * FROM group_invites WHERE group_id = $1 AND NOT revoked ORDER BY created_at DESC, id DESC")
+        .bind(group_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// Returns false if the group has no such invite.
+pub async fn revoke(pool: &PgPool, group_id: i64, invite_id: i64) -> sqlx::Result<bool> {
+    let result = sqlx::query("UPDATE group_invites SET revoked = TRUE WHERE id = $1 AND group_id = $2")
+        .bind(invite_id)
+        .bind(group_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,98 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use sqlx::PgPool;
+
+/// `(mood, emoji, label)` for each selectable mood, lowest first.
+pub const MOODS: [(i16, &str, &str); 5] = [
+    (1, "😞", "Rough"),
+    (2, "😕", "Meh"),
+    (3, "😐", "Okay"),
+    (4, "🙂", "Good"),
+    (5, "😄", "Great"),
+];
+
+#[derive(sqlx::FromRow)]
+#[allow(dead_code)]
+pub struct JournalEntry {
+    pub id: i64,
+    pub user_id: i64,
+    pub entry_date: NaiveDate,
+    pub mood: Option<i16>,
+    pub body: String,
+    pub updated_at: NaiveDateTime,
+}
+
+impl JournalEntry {
+    pub async fn find(pool: &PgPool, user_id: i64, date: NaiveDate) -> sqlx::Result<Option<Self>> {
+        sqlx::query_as("SELECT * FROM journal_entries WHERE user_id = $1 AND entry_date = $2")
+            .bind(user_id)
+            .bind(date)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Writes the entry for `date`, removing it when both fields are empty.
+    pub async fn save(
+        pool: &PgPool,
+        user_id: i64,
+        date: NaiveDate,
+        mood: Option<i16>,
+        body: &str,
+    ) -> sqlx::Result<()> {
+        if mood.is_none() && body.is_empty() {
+            sqlx::query("DELETE FROM journal_entries WHERE user_id = $1 AND entry_date = $2")
+                .bind(user_id)
+                .bind(date)
+                .execute(pool)
+                .await?;
+            return Ok(());
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO journal_entries (user_id, entry_date, mood, body)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id, entry_date)
+            DO UPDATE SET mood = EXCLUDED.mood, body = EXCLUDED.body, updated_at = NOW()
+            "#,
+        )
+        .bind(user_id)
+        .bind(date)
+        .bind(mood)
+        .bind(body)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Most recent entries before `before`, newest first.
+    pub async fn recent(pool: &PgPool, user_id: i64, before: NaiveDate, limit: i64) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as(
+            r#"
+            SELECT * FROM journal_entries
+            WHERE user_id = $1 AND entry_date < $2
+            ORDER BY entry_date DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(before)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub fn mood_emoji(&self) -> &'static str {
+        MOODS
+            .iter()
+            .find(|(mood, _, _)| Some(*mood) == self.mood)
+            .map_or("", |(_, emoji, _)| emoji)
+    }
+}
+
+/// `(mood, emoji, label, selected)` for the mood picker.
+pub fn mood_options(selected: Option<i16>) -> Vec<(i16, &'static str, &'static str, bool)> {
+    MOODS
+        .iter()
+        .map(|(mood, emoji, label)| (*mood, *emoji, *label, selected == Some(*mood)))
+        .collect()
+}
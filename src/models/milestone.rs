@@ -0,0 +1,90 @@
+//! Streak milestones: the lengths a user wants celebrated, and a record of
+//! each one a task reached and when.
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+/// Thresholds new users start with.
+pub const DEFAULT_THRESHOLDS: [i32; 3] = [7, 30, 100];
+
+/// Most thresholds a user can set.
+pub const MAX_THRESHOLDS: usize = 10;
+
+/// Longest streak a threshold can ask for, about ten years of days.
+pub const MAX_THRESHOLD: i32 = 3650;
+
+/// Reads a comma-separated list such as "7, 30, 100" into sorted, distinct
+/// thresholds.
+pub fn parse_thresholds(input: &str) -> Result<Vec<i32>, &'static str> {
+    let mut thresholds = Vec::new();
+    for part in input.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+        match part.parse::<i32>() {
+            Ok(threshold) if (1..=MAX_THRESHOLD).contains(&threshold) => thresholds.push(threshold),
+            _ => return Err("Milestones must be whole numbers from 1 to 3650"),
+        }
+    }
+    thresholds.sort_unstable();
+    thresholds.dedup();
+    if thresholds.len() > MAX_THRESHOLDS {
+        return Err("Pick at most 10 milestones");
+    }
+    Ok(thresholds)
+}
+
+pub fn thresholds_label(thresholds: &[i32]) -> String {
+    thresholds.iter().map(i32::to_string).collect::<Vec<_>>().join(", ")
+}
+
+/// Thresholds passed on the way from the `before` streak up to `after`.
+pub fn crossed(thresholds: &[i32], before: i64, after: i64) -> Vec<i32> {
+    thresholds
+        .iter()
+        .copied()
+        .filter(|threshold| before < i64::from(*threshold) && i64::from(*threshold) <= after)
+        .collect()
+}
+
+/// Records the task reaching `threshold` on `date`. False if it already had.
+pub async fn record(pool: &PgPool, task_id: i64, threshold: i32, date: NaiveDate) -> sqlx::Result<bool> {
+    let result = sqlx::query(
+        "INSERT INTO milestones (task_id, threshold, reached_on) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+    )
+    .bind(task_id)
+    .bind(threshold)
+    .bind(date)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Drops milestones reached on `date` that the task's streak no longer
+/// covers, after the completion that earned them is undone. Returns the
+/// thresholds taken back.
+pub async fn revoke_above(pool: &PgPool, task_id: i64, streak: i64, date: NaiveDate) -> sqlx::Result<Vec<i32>> {
+    sqlx::query_scalar(
+        "DELETE FROM milestones WHERE task_id = $1 AND reached_on = $2 AND threshold > $3 RETURNING threshold",
+    )
+    .bind(task_id)
+    .bind(date)
+    .bind(streak)
+    .fetch_all(pool)
+    .await
+}
+
+/// The most recent milestone each task reached, by task id.
+pub async fn latest(pool: &PgPool, task_ids: &[i64]) -> sqlx::Result<HashMap<i64, i32>> {
+    let rows: Vec<(i64, i32)> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT ON (task_id) task_id, threshold
+        FROM milestones
+        WHERE task_id = ANY($1)
+        ORDER BY task_id, reached_on DESC, threshold DESC
+        "#,
+    )
+    .bind(task_ids)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().collect())
+}
@@ -1,4 +1,16 @@
 pub mod user;
 pub mod task;
+pub mod streak;
+pub mod streak_cache;
 pub mod completion;
+pub mod freeze;
 pub mod group;
+pub mod invite;
+pub mod reaction;
+pub mod event;
+pub mod milestone;
+pub mod challenge;
+pub mod group_habit;
+pub mod notification;
+pub mod sharing;
+pub mod journal;
@@ -0,0 +1,345 @@
+//! Notices for one user: friends' milestones, cheers on their tasks, new
+//! members in their groups, and reminders when a streak is about to end.
+//! Each stays in the notification centre, unread until the user marks it.
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, TimeDelta, Utc, Weekday};
+use sqlx::PgPool;
+
+use crate::models::event;
+use crate::models::reaction::Reaction;
+use crate::models::task::{Schedule, TaskWithStreak};
+use crate::models::user::DayBoundary;
+
+/// How many notifications the notification centre lists.
+pub const PAGE_SIZE: i64 = 50;
+
+/// How close to the end of their day a user is reminded of streaks they
+/// haven't kept yet.
+pub const AT_RISK_WINDOW: TimeDelta = TimeDelta::hours(4);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NotificationKind {
+    Milestone,
+    Cheer,
+    Joined,
+    StreakAtRisk,
+}
+
+impl NotificationKind {
+    pub fn from_column(kind: &str) -> Option<Self> {
+        match kind {
+            "milestone" => Some(NotificationKind::Milestone),
+            "cheer" => Some(NotificationKind::Cheer),
+            "joined" => Some(NotificationKind::Joined),
+            "streak_at_risk" => Some(NotificationKind::StreakAtRisk),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::Milestone => "milestone",
+            NotificationKind::Cheer => "cheer",
+            NotificationKind::Joined => "joined",
+            NotificationKind::StreakAtRisk => "streak_at_risk",
+        }
+    }
+}
+
+/// Tells everyone who can see the task in a group they share with its owner
+/// that it reached a milestone.
+pub async fn notify_milestone(
+    pool: &PgPool,
+    owner_id: i64,
+    task_id: i64,
+    threshold: i32,
+    now: NaiveDateTime,
+) -> sqlx::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO notifications (user_id, kind, actor_id, task_id, streak, created_at)
+        SELECT DISTINCT gm.user_id, $1, $2, $3, $4, $5
+        FROM group_members owner
+        JOIN group_members gm ON gm.group_id = owner.group_id AND gm.user_id <> owner.user_id
+        JOIN tasks t ON t.id = $3
+        WHERE owner.user_id = $2
+          AND t.archived = FALSE
+          AND (t.visibility = 'groups'
+               OR (t.visibility = 'selected'
+                   AND EXISTS (SELECT 1 FROM task_group_shares s WHERE s.task_id = t.id AND s.group_id = owner.group_id)))
+        "#,
+    )
+    .bind(NotificationKind::Milestone.as_str())
+    .bind(owner_id)
+    .bind(task_id)
+    .bind(threshold)
+    .bind(now)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Takes back the notices of milestones that were revoked, from members who
+/// haven't read them yet.
+pub async fn withdraw_milestones(pool: &PgPool, task_id: i64, thresholds: &[i32]) -> sqlx::Result<()> {
+    sqlx::query(
+        r#"
+        DELETE FROM notifications
+        WHERE kind = $1 AND task_id = $2 AND streak = ANY($3) AND read_at IS NULL
+        "#,
+    )
+    .bind(NotificationKind::Milestone.as_str())
+    .bind(task_id)
+    .bind(thresholds)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Tells the task's owner someone cheered it.
+pub async fn notify_cheer(
+    pool: &PgPool,
+    owner_id: i64,
+    actor_id: i64,
+    task_id: i64,
+    reaction: Reaction,
+    now: NaiveDateTime,
+) -> sqlx::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO notifications (user_id, kind, actor_id, task_id, reaction, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+    )
+    .bind(owner_id)
+    .bind(NotificationKind::Cheer.as_str())
+    .bind(actor_id)
+    .bind(task_id)
+    .bind(reaction.as_str())
+    .bind(now)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Takes back the notice of a cheer that was withdrawn, unless the owner
+/// has already read it.
+pub async fn withdraw_cheer(pool: &PgPool, actor_id: i64, task_id: i64, reaction: Reaction) -> sqlx::Result<()> {
+    sqlx::query(
+        r#"
+        DELETE FROM notifications
+        WHERE kind = $1 AND actor_id = $2 AND task_id = $3 AND reaction = $4 AND read_at IS NULL
+        "#,
+    )
+    .bind(NotificationKind::Cheer.as_str())
+    .bind(actor_id)
+    .bind(task_id)
+    .bind(reaction.as_str())
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Tells the group's other members someone joined.
+pub async fn notify_joined(pool: &PgPool, actor_id: i64, group_id: i64, now: NaiveDateTime) -> sqlx::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO notifications (user_id, kind, actor_id, group_id, created_at)
+        SELECT user_id, $1, $2, $3, $4
+        FROM group_members
+        WHERE group_id = $3 AND user_id <> $2
+        "#,
+    )
+    .bind(NotificationKind::Joined.as_str())
+    .bind(actor_id)
+    .bind(group_id)
+    .bind(now)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Whether the task's streak ends with today unless it's done: a running
+/// streak on a task due today and not done yet. Weekly quotas are only at
+/// risk on Sunday, with one completion to go.
+fn at_risk(task: &TaskWithStreak, today: NaiveDate) -> bool {
+    if task.is_avoid() || task.current_streak == 0 || task.completed_today || !task.due_today {
+        return false;
+    }
+    match task.schedule {
+        Schedule::Weekly(target) => {
+            today.weekday() == Weekday::Sun && task.completed_this_week + 1 == i64::from(target)
+        }
+        _ => true,
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct UserDayRow {
+    id: i64,
+    time_zone: String,
+    day_ends_hour: i16,
+}
+
+/// Reminds users whose day ends within `AT_RISK_WINDOW` of each streak they
+/// are about to lose, once per task and day. Returns how many reminders
+/// were sent.
+pub async fn flag_streaks_at_risk(pool: &PgPool, now: DateTime<Utc>) -> sqlx::Result<usize> {
+    let users: Vec<UserDayRow> = sqlx::query_as("SELECT id, time_zone, day_ends_hour FROM users ORDER BY id")
+        .fetch_all(pool)
+        .await?;
+
+    let mut sent = 0;
+    for user in users {
+        let boundary = DayBoundary {
+            time_zone: user.time_zone,
+            day_ends_hour: user.day_ends_hour,
+        };
+        if boundary.time_left(now) > AT_RISK_WINDOW {
+            continue;
+        }
+        let today = boundary.today(now);
+        for task in TaskWithStreak::for_user(pool, user.id, today).await? {
+            if !at_risk(&task, today) {
+                continue;
+            }
+            let result = sqlx::query(
+                r#"
+                INSERT INTO notifications (user_id, kind, task_id, streak, for_date, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                ON CONFLICT (task_id, for_date) WHERE kind = 'streak_at_risk' DO NOTHING
+                "#,
+            )
+            .bind(user.id)
+            .bind(NotificationKind::StreakAtRisk.as_str())
+            .bind(task.id)
+            .bind(task.current_streak as i32)
+            .bind(today)
+            .bind(now.naive_utc())
+            .execute(pool)
+            .await?;
+            sent += result.rows_affected() as usize;
+        }
+    }
+    Ok(sent)
+}
+
+#[derive(sqlx::FromRow)]
+pub struct Notification {
+    pub id: i64,
+    kind: String,
+    pub actor_name: Option<String>,
+    pub task_name: Option<String>,
+    schedule_kind: Option<String>,
+    pub group_id: Option<i64>,
+    pub group_name: Option<String>,
+    pub streak: Option<i32>,
+    reaction: Option<String>,
+    pub read_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+impl Notification {
+    pub fn kind(&self) -> Option<NotificationKind> {
+        NotificationKind::from_column(&self.kind)
+    }
+
+    pub fn is_unread(&self) -> bool {
+        self.read_at.is_none()
+    }
+
+    fn streak_unit(&self) -> &'static str {
+        if self.schedule_kind.as_deref() == Some("weekly") { "week" } else { "day" }
+    }
+
+    pub fn description(&self) -> String {
+        let actor = self.actor_name.as_deref().unwrap_or("Someone");
+        let task = self.task_name.as_deref().unwrap_or_default();
+        let streak = self.streak.unwrap_or_default();
+        let unit = self.streak_unit();
+        match self.kind() {
+            Some(NotificationKind::Milestone) => format!("{actor} hit a {streak}-{unit} streak on {task}"),
+            Some(NotificationKind::Cheer) => {
+                let emoji = self.reaction.as_deref().and_then(Reaction::from_column).map(|r| r.emoji());
+                format!("{actor} cheered {task} {}", emoji.unwrap_or_default())
+            }
+            Some(NotificationKind::Joined) => {
+                format!("{actor} joined {}", self.group_name.as_deref().unwrap_or("your group"))
+            }
+            Some(NotificationKind::StreakAtRisk) => {
+                format!("Your {streak}-{unit} streak on {task} ends today unless you check in")
+            }
+            None => String::new(),
+        }
+    }
+
+    /// Where the notification leads: the group someone joined, or the
+    /// dashboard for the user's own tasks.
+    pub fn link(&self) -> Option<String> {
+        match self.kind() {
+            Some(NotificationKind::Joined) => self.group_id.map(|id| format!("/groups/{id}")),
+            Some(NotificationKind::Cheer | NotificationKind::StreakAtRisk) => Some("/".to_string()),
+            _ => None,
+        }
+    }
+
+    pub fn ago(&self, now: NaiveDateTime) -> String {
+        event::ago(self.created_at, now)
+    }
+}
+
+const SELECT_NOTIFICATIONS: &str = r#"
+    SELECT n.id, n.kind, a.username AS actor_name, t.name AS task_name, t.schedule_kind,
+           n.group_id, g.name AS group_name, n.streak, n.reaction, n.read_at, n.created_at
+    FROM notifications n
+    LEFT JOIN users a ON a.id = n.actor_id
+    LEFT JOIN tasks t ON t.id = n.task_id
+    LEFT JOIN groups g ON g.id = n.group_id
+"#;
+
+/// The user's latest notifications, newest first.
+pub async fn recent(pool: &PgPool, user_id: i64) -> sqlx::Result<Vec<Notification>> {
+    sqlx::query_as(&format!("{SELECT_NOTIFICATIONS} WHERE n.user_id = $1 ORDER BY n.id DESC LIMIT $2"))
+        .bind(user_id)
+        .bind(PAGE_SIZE)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn find(pool: &PgPool, user_id: i64, id: i64) -> sqlx::Result<Option<Notification>> {
+    sqlx::query_as(&format!("{SELECT_NOTIFICATIONS} WHERE n.id = $1 AND n.user_id = $2"))
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn unread_count(pool: &PgPool, user_id: i64) -> sqlx::Result<i64> {
+    sqlx::query_scalar("SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND read_at IS NULL")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Marks one of the user's notifications read. False if they have no such
+/// notification.
+pub async fn mark_read(pool: &PgPool, user_id: i64, id: i64, now: NaiveDateTime) -> sqlx::Result<bool> {
+    let result = sqlx::query(
+        "UPDATE notifications SET read_at = COALESCE(read_at, $3) WHERE id = $1 AND user_id = $2",
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(now)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn mark_all_read(pool: &PgPool, user_id: i64, now: NaiveDateTime) -> sqlx::Result<()> {
+    sqlx::query("UPDATE notifications SET read_at = $2 WHERE user_id = $1 AND read_at IS NULL")
+        .bind(user_id)
+        .bind(now)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
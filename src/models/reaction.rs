@@ -0,0 +1,198 @@
+//! Cheers group members leave on each other's tasks, one of a fixed set of
+//! emoji per member, task and day.
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Reaction {
+    Fire,
+    Clap,
+    Muscle,
+    Heart,
+    Party,
+}
+
+impl Reaction {
+    pub const ALL: [Reaction; 5] = [
+        Reaction::Fire,
+        Reaction::Clap,
+        Reaction::Muscle,
+        Reaction::Heart,
+        Reaction::Party,
+    ];
+
+    pub fn from_column(reaction: &str) -> Option<Self> {
+        match reaction {
+            "fire" => Some(Reaction::Fire),
+            "clap" => Some(Reaction::Clap),
+            "muscle" => Some(Reaction::Muscle),
+            "heart" => Some(Reaction::Heart),
+            "party" => Some(Reaction::Party),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Reaction::Fire => "fire",
+            Reaction::Clap => "clap",
+            Reaction::Muscle => "muscle",
+            Reaction::Heart => "heart",
+            Reaction::Party => "party",
+        }
+    }
+
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Reaction::Fire => "🔥",
+            Reaction::Clap => "👏",
+            Reaction::Muscle => "💪",
+            Reaction::Heart => "❤️",
+            Reaction::Party => "🎉",
+        }
+    }
+}
+
+/// Everyone who left one reaction on a task for a day.
+#[derive(Clone, Debug)]
+pub struct ReactionSummary {
+    pub reaction: Reaction,
+    /// Usernames, earliest first.
+    pub names: Vec<String>,
+    pub user_ids: Vec<i64>,
+}
+
+impl ReactionSummary {
+    pub fn count(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Who reacted, for a tooltip.
+    pub fn names_label(&self) -> String {
+        self.names.join(", ")
+    }
+
+    pub fn includes(&self, user_id: i64) -> bool {
+        self.user_ids.contains(&user_id)
+    }
+}
+
+/// One button in a task's reaction bar on the group feed.
+pub struct ReactionButton {
+    pub reaction: Reaction,
+    pub count: usize,
+    pub names: String,
+    /// Whether the viewer has left this one.
+    pub mine: bool,
+}
+
+/// A button for every reaction, in the fixed order, filled in from `summaries`.
+pub fn buttons(summaries: &[ReactionSummary], viewer_id: i64) -> Vec<ReactionButton> {
+    Reaction::ALL
+        .iter()
+        .map(|reaction| match summaries.iter().find(|s| s.reaction == *reaction) {
+            Some(summary) => ReactionButton {
+                reaction: *reaction,
+                count: summary.count(),
+                names: summary.names_label(),
+                mine: summary.includes(viewer_id),
+            },
+            None => ReactionButton {
+                reaction: *reaction,
+                count: 0,
+                names: String::new(),
+                mine: false,
+            },
+        })
+        .collect()
+}
+
+#[derive(sqlx::FromRow)]
+struct ReactionRow {
+    task_id: i64,
+    user_id: i64,
+    username: String,
+    reaction: String,
+}
+
+/// Reactions left on each task on `date`, in the fixed order, leaving out
+/// ones nobody used.
+pub async fn on(pool: &PgPool, task_ids: &[i64], date: NaiveDate) -> sqlx::Result<HashMap<i64, Vec<ReactionSummary>>> {
+    let rows: Vec<ReactionRow> = sqlx::query_as(
+        r#"
+        SELECT r.task_id, r.user_id, u.username, r.reaction
+        FROM reactions r
+        JOIN users u ON u.id = r.user_id
+        WHERE r.task_id = ANY($1) AND r.reaction_date = $2
+        ORDER BY r.created_at, u.username
+        "#,
+    )
+    .bind(task_ids)
+    .bind(date)
+    .fetch_all(pool)
+    .await?;
+
+    let mut by_task: HashMap<i64, Vec<ReactionSummary>> = HashMap::new();
+    for row in rows {
+        let Some(reaction) = Reaction::from_column(&row.reaction) else {
+            continue;
+        };
+        let summaries = by_task.entry(row.task_id).or_default();
+        match summaries.iter_mut().find(|s| s.reaction == reaction) {
+            Some(summary) => {
+                summary.names.push(row.username);
+                summary.user_ids.push(row.user_id);
+            }
+            None => summaries.push(ReactionSummary {
+                reaction,
+                names: vec![row.username],
+                user_ids: vec![row.user_id],
+            }),
+        }
+    }
+    for summaries in by_task.values_mut() {
+        summaries.sort_by_key(|s| Reaction::ALL.iter().position(|r| *r == s.reaction));
+    }
+    Ok(by_task)
+}
+
+/// Adds the user's reaction, or takes it back if they'd already left it.
+/// Returns whether it's now there.
+pub async fn toggle(
+    pool: &PgPool,
+    task_id: i64,
+    user_id: i64,
+    date: NaiveDate,
+    reaction: Reaction,
+) -> sqlx::Result<bool> {
+    let removed = sqlx::query(
+        "DELETE FROM reactions WHERE task_id = $1 AND user_id = $2 AND reaction_date = $3 AND reaction = $4",
+    )
+    .bind(task_id)
+    .bind(user_id)
+    .bind(date)
+    .bind(reaction.as_str())
+    .execute(pool)
+    .await?;
+    if removed.rows_affected() > 0 {
+        return Ok(false);
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO reactions (task_id, user_id, reaction_date, reaction)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(task_id)
+    .bind(user_id)
+    .bind(date)
+    .bind(reaction.as_str())
+    .execute(pool)
+    .await?;
+    Ok(true)
+}
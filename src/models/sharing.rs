@@ -0,0 +1,153 @@
+//! Which of its owner's groups can see a task. Group feeds and every other
+//! place one member sees another's tasks only show tasks shared with that
+//! group.
+
+use sqlx::{PgConnection, PgPool};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Visibility {
+    /// Every group the owner is in, including ones they join later.
+    AllGroups,
+    /// Only the groups picked for the task.
+    Selected,
+    Private,
+}
+
+impl Visibility {
+    pub const ALL: [Visibility; 3] = [Visibility::AllGroups, Visibility::Selected, Visibility::Private];
+
+    pub fn from_column(visibility: &str) -> Self {
+        match visibility {
+            "selected" => Visibility::Selected,
+            "private" => Visibility::Private,
+            _ => Visibility::AllGroups,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Visibility::AllGroups => "groups",
+            Visibility::Selected => "selected",
+            Visibility::Private => "private",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Visibility::AllGroups => "All my groups",
+            Visibility::Selected => "Selected groups",
+            Visibility::Private => "Only me",
+        }
+    }
+
+    /// `(value, label, selected)` for the visibility select on the edit form.
+    pub fn options(selected: Visibility) -> Vec<(&'static str, &'static str, bool)> {
+        Visibility::ALL
+            .iter()
+            .map(|visibility| (visibility.as_str(), visibility.label(), *visibility == selected))
+            .collect()
+    }
+
+    /// `(value, label, selected)` for the default picked on the profile page,
+    /// where choosing groups task by task doesn't apply.
+    pub fn default_options(selected: Visibility) -> Vec<(&'static str, &'static str, bool)> {
+        [Visibility::AllGroups, Visibility::Private]
+            .iter()
+            .map(|visibility| (visibility.as_str(), visibility.label(), *visibility == selected))
+            .collect()
+    }
+}
+
+/// The task's visibility and, when it's `Selected`, the groups it's shared with.
+pub async fn for_task(pool: &PgPool, task_id: i64) -> sqlx::Result<(Visibility, Vec<i64>)> {
+    let visibility: String = sqlx::query_scalar("SELECT visibility FROM tasks WHERE id = $1")
+        .bind(task_id)
+        .fetch_one(pool)
+        .await?;
+    let group_ids: Vec<i64> =
+        sqlx::query_scalar("SELECT group_id FROM task_group_shares WHERE task_id = $1 ORDER BY group_id")
+            .bind(task_id)
+            .fetch_all(pool)
+            .await?;
+    Ok((Visibility::from_column(&visibility), group_ids))
+}
+
+/// The task's owner, if members of the group can see it: it isn't archived,
+/// and its owner belongs to the group and has shared it there.
+pub async fn owner_in_group(pool: &PgPool, task_id: i64, group_id: i64) -> sqlx::Result<Option<i64>> {
+    sqlx::query_scalar(
+        r#"
+        SELECT t.user_id FROM tasks t
+        JOIN group_members gm ON gm.user_id = t.user_id AND gm.group_id = $2
+        WHERE t.id = $1 AND t.archived = FALSE
+          AND (t.visibility = 'groups'
+               OR (t.visibility = 'selected'
+                   AND EXISTS (SELECT 1 FROM task_group_shares s WHERE s.task_id = t.id AND s.group_id = $2)))
+        "#,
+    )
+    .bind(task_id)
+    .bind(group_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Sets who can see the task. `group_ids` only matter for `Selected`, and
+/// groups the owner doesn't belong to are ignored.
+pub async fn set(
+    pool: &PgPool,
+    task_id: i64,
+    user_id: i64,
+    visibility: Visibility,
+    group_ids: &[i64],
+) -> sqlx::Result<()> {
+    let mut tx = pool.begin().await?;
+    let updated = sqlx::query("UPDATE tasks SET visibility = $1 WHERE id = $2 AND user_id = $3")
+        .bind(visibility.as_str())
+        .bind(task_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    if updated.rows_affected() == 0 {
+        return Ok(());
+    }
+
+    sqlx::query("DELETE FROM task_group_shares WHERE task_id = $1")
+        .bind(task_id)
+        .execute(&mut *tx)
+        .await?;
+    if visibility == Visibility::Selected {
+        sqlx::query(
+            r#"
+            INSERT INTO task_group_shares (task_id, group_id)
+            SELECT $1, gm.group_id FROM group_members gm
+            WHERE gm.user_id = $2 AND gm.group_id = ANY($3)
+            "#,
+        )
+        .bind(task_id)
+        .bind(user_id)
+        .bind(group_ids)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await
+}
+
+/// Shows the task to `group_id` alone, for tasks that only mean something
+/// there, such as the one tracking a group's challenge.
+pub async fn limit_to_group(conn: &mut PgConnection, task_id: i64, group_id: i64) -> sqlx::Result<()> {
+    sqlx::query("UPDATE tasks SET visibility = $1 WHERE id = $2")
+        .bind(Visibility::Selected.as_str())
+        .bind(task_id)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query("DELETE FROM task_group_shares WHERE task_id = $1")
+        .bind(task_id)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query("INSERT INTO task_group_shares (task_id, group_id) VALUES ($1, $2)")
+        .bind(task_id)
+        .bind(group_id)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,231 @@
+//! Streak engine. Every streak the app shows, on the dashboard, the history
+//! page and in group feeds, comes from `summarize` (or `summarize_clean` for
+//! avoid tasks), so the rules live in one place.
+//!
+//! Rather than walking the calendar one day at a time, completions are sorted
+//! and grouped into islands: two neighbouring completions belong to the same
+//! run when every due day between them was frozen. Only the gaps are walked,
+//! and a gap stops at its first plain miss, so cost follows the number of
+//! completions rather than the age of the task.
+
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{Datelike, Days, NaiveDate};
+
+use crate::models::task::Schedule;
+
+/// The streak as of a given day.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Streak {
+    pub length: i64,
+    /// Whether the streak only survives because a freeze bridged a missed day.
+    pub frozen: bool,
+}
+
+/// One unbroken streak, in days or weeks depending on the schedule.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct StreakRun {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub length: i64,
+}
+
+/// A task's whole streak history as of one day.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct StreakSummary {
+    pub current: Streak,
+    /// Longest run ever, including the current one.
+    pub best: i64,
+    /// Every run, oldest first; the last one may still be running.
+    pub runs: Vec<StreakRun>,
+}
+
+impl StreakSummary {
+    fn new(runs: Vec<StreakRun>, current: Streak) -> Self {
+        let best = runs.iter().map(|run| run.length).max().unwrap_or(0);
+        Self { current, best, runs }
+    }
+}
+
+pub(crate) fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Days::new(date.weekday().num_days_from_monday() as u64)
+}
+
+pub(crate) fn completions_in_week(completed: &HashSet<NaiveDate>, start: NaiveDate, until: NaiveDate) -> i64 {
+    start
+        .iter_days()
+        .take(7)
+        .take_while(|d| *d <= until)
+        .filter(|d| completed.contains(d))
+        .count() as i64
+}
+
+/// Streaks as of `today`: consecutive due days completed for daily and
+/// weekday schedules, consecutive weeks that met the target for weekly ones.
+/// A day or week still in progress never breaks the streak, and a frozen day
+/// (or a week containing one) is bridged without adding to it. Completions
+/// after `today` are ignored.
+pub fn summarize(
+    schedule: Schedule,
+    completed: &HashSet<NaiveDate>,
+    frozen: &HashSet<NaiveDate>,
+    today: NaiveDate,
+) -> StreakSummary {
+    match schedule {
+        Schedule::Weekly(target) => weekly(target as usize, completed, frozen, today),
+        _ => daily(schedule, completed, frozen, today),
+    }
+}
+
+/// Whether the due days strictly between `after` and `before` can be
+/// bridged: `None` if one was simply missed, otherwise whether it took a
+/// freeze.
+fn bridge(schedule: Schedule, frozen: &HashSet<NaiveDate>, after: NaiveDate, before: NaiveDate) -> Option<bool> {
+    let mut used_freeze = false;
+    for day in after.iter_days().skip(1).take_while(|d| *d < before) {
+        if !schedule.is_due_on(day) {
+            continue;
+        }
+        if !frozen.contains(&day) {
+            return None;
+        }
+        used_freeze = true;
+    }
+    Some(used_freeze)
+}
+
+/// Like `bridge`, for the whole weeks strictly between two week starts.
+fn bridge_weeks(frozen_weeks: &HashSet<NaiveDate>, after: NaiveDate, before: NaiveDate) -> Option<bool> {
+    let mut week = after + Days::new(7);
+    let mut used_freeze = false;
+    while week < before {
+        if !frozen_weeks.contains(&week) {
+            return None;
+        }
+        used_freeze = true;
+        week = week + Days::new(7);
+    }
+    Some(used_freeze)
+}
+
+fn daily(
+    schedule: Schedule,
+    completed: &HashSet<NaiveDate>,
+    frozen: &HashSet<NaiveDate>,
+    today: NaiveDate,
+) -> StreakSummary {
+    let mut dates: Vec<NaiveDate> = completed
+        .iter()
+        .copied()
+        .filter(|d| *d <= today && schedule.is_due_on(*d))
+        .collect();
+    dates.sort_unstable();
+
+    let mut runs: Vec<StreakRun> = Vec::new();
+    let mut run_frozen = false;
+    for date in dates {
+        let bridged = runs.last().and_then(|run| bridge(schedule, frozen, run.end, date));
+        match (runs.last_mut(), bridged) {
+            (Some(run), Some(used_freeze)) => {
+                run.end = date;
+                run.length += 1;
+                run_frozen |= used_freeze;
+            }
+            _ => {
+                runs.push(StreakRun { start: date, end: date, length: 1 });
+                run_frozen = false;
+            }
+        }
+    }
+
+    // Today is still in progress, so only the days before it can break the run
+    let current = match runs.last().map(|run| (run, bridge(schedule, frozen, run.end, today))) {
+        Some((run, Some(used_freeze))) => Streak {
+            length: run.length,
+            frozen: run_frozen || used_freeze,
+        },
+        _ => Streak::default(),
+    };
+    StreakSummary::new(runs, current)
+}
+
+fn weekly(
+    target: usize,
+    completed: &HashSet<NaiveDate>,
+    frozen: &HashSet<NaiveDate>,
+    today: NaiveDate,
+) -> StreakSummary {
+    let mut weeks: BTreeMap<NaiveDate, Vec<NaiveDate>> = BTreeMap::new();
+    for date in completed.iter().filter(|d| **d <= today) {
+        weeks.entry(week_start(*date)).or_default().push(*date);
+    }
+    let frozen_weeks: HashSet<NaiveDate> = frozen.iter().map(|d| week_start(*d)).collect();
+    let this_week = week_start(today);
+
+    let mut runs: Vec<StreakRun> = Vec::new();
+    let mut open: Option<(StreakRun, bool)> = None;
+    for (week, mut days) in weeks {
+        if days.len() < target {
+            continue;
+        }
+        days.sort_unstable();
+        let (first, last) = (days[0], days[days.len() - 1]);
+
+        let bridged = open
+            .as_ref()
+            .and_then(|(run, _)| bridge_weeks(&frozen_weeks, week_start(run.end), week));
+        match (open.as_mut(), bridged) {
+            (Some((run, run_frozen)), Some(used_freeze)) => {
+                run.end = last;
+                run.length += 1;
+                *run_frozen |= used_freeze;
+            }
+            _ => {
+                runs.extend(open.take().map(|(run, _)| run));
+                open = Some((StreakRun { start: first, end: last, length: 1 }, false));
+            }
+        }
+    }
+
+    // This week may still reach its target, so it can't break the run yet
+    let mut current = Streak::default();
+    if let Some((run, run_frozen)) = open {
+        if let Some(used_freeze) = bridge_weeks(&frozen_weeks, week_start(run.end), this_week) {
+            current = Streak { length: run.length, frozen: run_frozen || used_freeze };
+        }
+        runs.push(run);
+    }
+    StreakSummary::new(runs, current)
+}
+
+/// Clean stretches of an avoid task between slips, oldest first. A run counts
+/// the days after the slip (or creation) that starts it; the last run always
+/// ends today and is the current streak, so slipping today leaves none.
+pub fn summarize_clean(slips: &HashSet<NaiveDate>, created: NaiveDate, today: NaiveDate) -> StreakSummary {
+    let mut slips: Vec<NaiveDate> = slips.iter().filter(|d| **d <= today).copied().collect();
+    slips.sort_unstable();
+
+    let mut runs = Vec::new();
+    let mut since = slips.first().map_or(created, |first| created.min(*first));
+    for slip in slips {
+        if slip > since + Days::new(1) {
+            runs.push(StreakRun {
+                start: since + Days::new(1),
+                end: slip - Days::new(1),
+                length: (slip - since).num_days() - 1,
+            });
+        }
+        since = since.max(slip);
+    }
+
+    let mut current = Streak::default();
+    if today > since {
+        current.length = (today - since).num_days();
+        runs.push(StreakRun {
+            start: since + Days::new(1),
+            end: today,
+            length: current.length,
+        });
+    }
+    StreakSummary::new(runs, current)
+}
@@ -0,0 +1,276 @@
+//! Streak counters cached per task in `task_streaks`, so dashboards and group
+//! feeds don't replay every task's full history on each request. A row is
+//! rewritten, one writer at a time, in the same transaction as any change to
+//! the task's completions or freezes, rolled forward to the owner's new day
+//! by `roll_over`, and can be checked against the raw history with `rebuild`.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{PgConnection, PgExecutor, PgPool};
+
+use crate::models::event::{self, EventKind};
+use crate::models::streak::StreakSummary;
+use crate::models::task::Task;
+use crate::models::user::DayBoundary;
+use crate::models::{completion, freeze};
+
+#[derive(sqlx::FromRow, Clone, PartialEq, Eq, Debug)]
+pub struct CachedStreak {
+    pub task_id: i64,
+    pub current_streak: i32,
+    pub best_streak: i32,
+    pub frozen: bool,
+    pub total_completions: i32,
+    pub last_completed: Option<NaiveDate>,
+    /// The owner's date the row was computed for.
+    pub as_of: NaiveDate,
+}
+
+impl CachedStreak {
+    pub fn from_summary(
+        task_id: i64,
+        summary: &StreakSummary,
+        completed: &HashSet<NaiveDate>,
+        today: NaiveDate,
+    ) -> Self {
+        Self {
+            task_id,
+            current_streak: summary.current.length as i32,
+            best_streak: summary.best as i32,
+            frozen: summary.current.frozen,
+            total_completions: completed.len() as i32,
+            last_completed: completed.iter().filter(|d| **d <= today).max().copied(),
+            as_of: today,
+        }
+    }
+}
+
+/// Cached rows for `task_ids` computed for `today`. Stale and missing rows
+/// are left out, so callers fall back to the full history for those.
+pub async fn fresh_for(
+    pool: &PgPool,
+    task_ids: &[i64],
+    today: NaiveDate,
+) -> sqlx::Result<HashMap<i64, CachedStreak>> {
+    let rows: Vec<CachedStreak> = sqlx::query_as(
+        r#"
+        SELECT task_id, current_streak, best_streak, frozen, total_completions, last_completed, as_of
+        FROM task_streaks
+        WHERE task_id = ANY($1) AND as_of = $2
+        "#,
+    )
+    .bind(task_ids)
+    .bind(today)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().map(|row| (row.task_id, row)).collect())
+}
+
+pub async fn store(executor: impl PgExecutor<'_>, row: &CachedStreak) -> sqlx::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO task_streaks
+            (task_id, current_streak, best_streak, frozen, total_completions, last_completed, as_of)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (task_id) DO UPDATE SET
+            current_streak = EXCLUDED.current_streak,
+            best_streak = EXCLUDED.best_streak,
+            frozen = EXCLUDED.frozen,
+            total_completions = EXCLUDED.total_completions,
+            last_completed = EXCLUDED.last_completed,
+            as_of = EXCLUDED.as_of,
+            updated_at = NOW()
+        "#,
+    )
+    .bind(row.task_id)
+    .bind(row.current_streak)
+    .bind(row.best_streak)
+    .bind(row.frozen)
+    .bind(row.total_completions)
+    .bind(row.last_completed)
+    .bind(row.as_of)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Drops a task's cached row, for changes such as a new schedule that alter
+/// how its whole history is read.
+pub async fn invalidate(executor: impl PgExecutor<'_>, task_id: i64) -> sqlx::Result<()> {
+    sqlx::query("DELETE FROM task_streaks WHERE task_id = $1")
+        .bind(task_id)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// Recomputes a task's streak from its full history as of `today`.
+pub async fn compute(conn: &mut PgConnection, task_id: i64, today: NaiveDate) -> sqlx::Result<Option<CachedStreak>> {
+    let task: Option<Task> = sqlx::query_as("SELECT * FROM tasks WHERE id = $1")
+        .bind(task_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+    let Some(task) = task else {
+        return Ok(None);
+    };
+
+    let completed: HashSet<NaiveDate> = completion::dates_by_task(&mut *conn, &[task_id])
+        .await?
+        .remove(&task_id)
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    let frozen: HashSet<NaiveDate> = freeze::dates_by_task(&mut *conn, &[task_id])
+        .await?
+        .remove(&task_id)
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+
+    let summary = task.streak_summary(&completed, &frozen, today);
+    Ok(Some(CachedStreak::from_summary(task_id, &summary, &completed, today)))
+}
+
+/// Locks the task's row until the transaction ends, so writers to one task
+/// take turns and each recomputes its row with the others' changes
+/// committed. Take it before touching the task's completions or freezes:
+/// their foreign keys already share-lock the row, and upgrading that lock
+/// later deadlocks against another writer doing the same.
+pub async fn lock(conn: &mut PgConnection, task_id: i64) -> sqlx::Result<()> {
+    sqlx::query("SELECT id FROM tasks WHERE id = $1 FOR UPDATE")
+        .bind(task_id)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// Recomputes and stores a task's row. Call inside the transaction that
+/// changed its completions or freezes so the two never disagree. The task is
+/// locked here too, for callers such as `roll_over` that change nothing else.
+pub async fn refresh(conn: &mut PgConnection, task_id: i64, today: NaiveDate) -> sqlx::Result<()> {
+    lock(conn, task_id).await?;
+    if let Some(row) = compute(conn, task_id, today).await? {
+        store(&mut *conn, &row).await?;
+    }
+    Ok(())
+}
+
+#[derive(sqlx::FromRow)]
+struct TaskOwnerRow {
+    id: i64,
+    time_zone: String,
+    day_ends_hour: i16,
+    as_of: Option<NaiveDate>,
+}
+
+/// Every task (active ones only, unless `include_archived`) with its owner's
+/// date at `now` and the date its cached row was computed for.
+async fn tasks_with_owner_today(
+    pool: &PgPool,
+    now: DateTime<Utc>,
+    include_archived: bool,
+) -> sqlx::Result<Vec<(i64, NaiveDate, Option<NaiveDate>)>> {
+    let rows: Vec<TaskOwnerRow> = sqlx::query_as(
+        r#"
+        SELECT t.id, u.time_zone, u.day_ends_hour, s.as_of
+        FROM tasks t
+        JOIN users u ON u.id = t.user_id
+        LEFT JOIN task_streaks s ON s.task_id = t.id
+        WHERE $1 OR t.archived = FALSE
+        ORDER BY t.id
+        "#,
+    )
+    .bind(include_archived)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let boundary = DayBoundary {
+                time_zone: row.time_zone,
+                day_ends_hour: row.day_ends_hour,
+            };
+            (row.id, boundary.today(now), row.as_of)
+        })
+        .collect())
+}
+
+/// Brings every active task whose owner has started a new day since its row
+/// was computed up to date, logging a broken streak for each running one a
+/// missed day ended. Returns how many rows were refreshed.
+pub async fn roll_over(pool: &PgPool, now: DateTime<Utc>) -> sqlx::Result<usize> {
+    let mut refreshed = 0;
+    for (task_id, today, as_of) in tasks_with_owner_today(pool, now, false).await? {
+        if as_of.is_some_and(|as_of| as_of >= today) {
+            continue;
+        }
+        let mut tx = pool.begin().await?;
+        lock(&mut tx, task_id).await?;
+        let (user_id, before): (i64, Option<i32>) = sqlx::query_as(
+            r#"
+            SELECT t.user_id, s.current_streak
+            FROM tasks t
+            LEFT JOIN task_streaks s ON s.task_id = t.id
+            WHERE t.id = $1
+            "#,
+        )
+        .bind(task_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if let Some(row) = compute(&mut tx, task_id, today).await? {
+            store(&mut *tx, &row).await?;
+            if before.is_some_and(|before| before > 0) && row.current_streak == 0 {
+                let streak = before.map(i64::from);
+                event::record_task(&mut *tx, EventKind::StreakBroken, user_id, task_id, streak, now.naive_utc())
+                    .await?;
+            }
+        }
+        tx.commit().await?;
+        refreshed += 1;
+    }
+    Ok(refreshed)
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RebuildReport {
+    pub checked: usize,
+    /// Rows that disagreed with the history for the day they were computed.
+    pub mismatched: usize,
+    /// Tasks that had no row at all.
+    pub missing: usize,
+}
+
+/// Checks every task's row against its raw completions and freezes, then
+/// rewrites it from scratch as of the owner's current day.
+pub async fn rebuild(pool: &PgPool, now: DateTime<Utc>) -> sqlx::Result<RebuildReport> {
+    let mut report = RebuildReport::default();
+    for (task_id, today, _) in tasks_with_owner_today(pool, now, true).await? {
+        let mut tx = pool.begin().await?;
+        let cached: Option<CachedStreak> = sqlx::query_as(
+            r#"
+            SELECT task_id, current_streak, best_streak, frozen, total_completions, last_completed, as_of
+            FROM task_streaks
+            WHERE task_id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(task_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        match cached {
+            Some(cached) => {
+                if compute(&mut tx, task_id, cached.as_of).await?.as_ref() != Some(&cached) {
+                    report.mismatched += 1;
+                }
+            }
+            None => report.missing += 1,
+        }
+        refresh(&mut tx, task_id, today).await?;
+        tx.commit().await?;
+        report.checked += 1;
+    }
+    Ok(report)
+}
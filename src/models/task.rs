@@ -1,5 +1,12 @@
-use chrono::{NaiveDate, NaiveDateTime};
-use sqlx::PgPool;
+use std::collections::HashSet;
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use sqlx::{PgExecutor, PgPool};
+
+use crate::models::reaction::{self, ReactionSummary};
+use crate::models::streak::{self, Streak, StreakRun, StreakSummary, completions_in_week, week_start};
+use crate::models::streak_cache::{self, CachedStreak};
+use crate::models::{completion, freeze};
 
 #[derive(sqlx::FromRow)]
 #[allow(dead_code)]
@@ -10,9 +17,16 @@ pub struct Task {
     pub description: Option<String>,
     pub created_at: NaiveDateTime,
     pub archived: bool,
+    pub kind: String,
+    pub schedule_kind: String,
+    pub schedule_days: i16,
+    pub weekly_target: i16,
+    pub target_value: Option<i32>,
+    pub unit: Option<String>,
+    pub position: i32,
+    pub section: Option<String>,
 }
 
-#[derive(sqlx::FromRow)]
 #[allow(dead_code)]
 pub struct TaskWithStreak {
     pub id: i64,
@@ -21,38 +35,327 @@ pub struct TaskWithStreak {
     pub description: Option<String>,
     pub created_at: NaiveDateTime,
     pub archived: bool,
+    pub kind: TaskKind,
+    pub section: Option<Section>,
+    pub schedule: Schedule,
+    pub target_value: Option<i32>,
+    pub unit: Option<String>,
+    /// Amount logged today toward `target_value`.
+    pub today_value: i32,
+    /// Note on today's completion (or slip, for avoid tasks).
+    pub today_note: Option<String>,
+    /// Whether anything was logged today, which a note needs.
+    pub logged_today: bool,
     pub current_streak: i64,
+    pub frozen: bool,
     pub completed_today: bool,
+    pub due_today: bool,
+    pub completed_this_week: i64,
+    /// Longest streak ever, including the current one.
+    pub best_streak: i64,
+    /// Days the task was done (or, for avoid tasks, slipped on).
+    pub total_completions: i64,
+    /// Every streak, oldest first; the last one may still be running. Only
+    /// loaded by the `*_history` constructors.
+    pub runs: Vec<StreakRun>,
+    /// Cheers left on the task today by other group members.
+    pub reactions: Vec<ReactionSummary>,
+}
+
+/// Whether a task is a habit to build or one to avoid.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TaskKind {
+    Build,
+    /// Completions record slips; the streak counts clean days since the last one.
+    Avoid,
+}
+
+impl TaskKind {
+    pub fn from_column(kind: &str) -> Self {
+        match kind {
+            "avoid" => TaskKind::Avoid,
+            _ => TaskKind::Build,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskKind::Build => "build",
+            TaskKind::Avoid => "avoid",
+        }
+    }
+}
+
+/// Time of day a task is grouped under on the dashboard.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Section {
+    Morning,
+    Afternoon,
+    Evening,
+}
+
+impl Section {
+    pub const ALL: [Section; 3] = [Section::Morning, Section::Afternoon, Section::Evening];
+
+    pub fn from_column(section: &str) -> Option<Self> {
+        match section {
+            "morning" => Some(Section::Morning),
+            "afternoon" => Some(Section::Afternoon),
+            "evening" => Some(Section::Evening),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Section::Morning => "morning",
+            Section::Afternoon => "afternoon",
+            Section::Evening => "evening",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Section::Morning => "Morning",
+            Section::Afternoon => "Afternoon",
+            Section::Evening => "Evening",
+        }
+    }
+
+    /// `(value, label, selected)` for the section select in the task forms,
+    /// starting with "Anytime" for no section.
+    pub fn options(selected: Option<Section>) -> Vec<(&'static str, &'static str, bool)> {
+        std::iter::once(("", "Anytime", selected.is_none()))
+            .chain(
+                Section::ALL
+                    .iter()
+                    .map(|section| (section.as_str(), section.label(), selected == Some(*section))),
+            )
+            .collect()
+    }
+}
+
+/// Editable task attributes, shared by `Task::create` and `Task::update`.
+pub struct TaskDetails<'a> {
+    pub name: &'a str,
+    pub description: Option<&'a str>,
+    pub section: Option<Section>,
+    pub schedule: Schedule,
+    pub target_value: Option<i32>,
+    pub unit: Option<&'a str>,
+}
+
+const WEEKDAYS: [(&str, &str); 7] = [
+    ("mon", "Mon"),
+    ("tue", "Tue"),
+    ("wed", "Wed"),
+    ("thu", "Thu"),
+    ("fri", "Fri"),
+    ("sat", "Sat"),
+    ("sun", "Sun"),
+];
+
+/// When a task is due. Stored on `tasks` as `schedule_kind`, `schedule_days`
+/// and `weekly_target`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Schedule {
+    Daily,
+    /// Due on fixed weekdays; bit 0 is Monday, bit 6 is Sunday.
+    Weekdays(u8),
+    /// Due a number of times per Monday-based week, on any days.
+    Weekly(u8),
+}
+
+impl Schedule {
+    pub fn from_columns(kind: &str, days: i16, weekly_target: i16) -> Self {
+        match kind {
+            "weekdays" if days & 0x7f != 0 => Schedule::Weekdays((days & 0x7f) as u8),
+            "weekly" => Schedule::Weekly(weekly_target.clamp(1, 7) as u8),
+            _ => Schedule::Daily,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Schedule::Daily => "daily",
+            Schedule::Weekdays(_) => "weekdays",
+            Schedule::Weekly(_) => "weekly",
+        }
+    }
+
+    pub fn days_mask(&self) -> i16 {
+        match self {
+            Schedule::Weekdays(mask) => *mask as i16,
+            _ => 0x7f,
+        }
+    }
+
+    pub fn weekly_target(&self) -> i16 {
+        match self {
+            Schedule::Weekly(target) => *target as i16,
+            _ => 1,
+        }
+    }
+
+    pub fn is_daily(&self) -> bool {
+        matches!(self, Schedule::Daily)
+    }
+
+    pub fn is_weekly(&self) -> bool {
+        matches!(self, Schedule::Weekly(_))
+    }
+
+    /// `(field name, label, checked)` for each weekday checkbox in the task forms.
+    pub fn day_options(&self) -> Vec<(&'static str, &'static str, bool)> {
+        let mask = match self {
+            Schedule::Weekdays(mask) => *mask,
+            _ => 0,
+        };
+        WEEKDAYS
+            .iter()
+            .enumerate()
+            .map(|(i, (field, label))| (*field, *label, mask & (1 << i) != 0))
+            .collect()
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Schedule::Daily => "Every day".to_string(),
+            Schedule::Weekdays(0x1f) => "Weekdays".to_string(),
+            Schedule::Weekdays(0x60) => "Weekends".to_string(),
+            Schedule::Weekdays(_) => self
+                .day_options()
+                .into_iter()
+                .filter(|(_, _, checked)| *checked)
+                .map(|(_, label, _)| label)
+                .collect::<Vec<_>>()
+                .join(", "),
+            Schedule::Weekly(target) => format!("{target}× per week"),
+        }
+    }
+
+    /// Whether `date` is one of the fixed days this schedule is due on. Weekly
+    /// quotas can be met on any day.
+    pub fn is_due_on(&self, date: NaiveDate) -> bool {
+        match self {
+            Schedule::Weekdays(mask) => mask & (1 << date.weekday().num_days_from_monday()) != 0,
+            _ => true,
+        }
+    }
 }
 
 impl Task {
+    pub fn kind(&self) -> TaskKind {
+        TaskKind::from_column(&self.kind)
+    }
+
+    pub fn schedule(&self) -> Schedule {
+        Schedule::from_columns(&self.schedule_kind, self.schedule_days, self.weekly_target)
+    }
+
+    /// Streaks from the task's full history: clean runs between slips for
+    /// avoid tasks, completed runs for everything else.
+    pub fn streak_summary(
+        &self,
+        completed: &HashSet<NaiveDate>,
+        frozen: &HashSet<NaiveDate>,
+        today: NaiveDate,
+    ) -> StreakSummary {
+        match self.kind() {
+            TaskKind::Avoid => streak::summarize_clean(completed, self.created_at.date(), today),
+            TaskKind::Build => streak::summarize(self.schedule(), completed, frozen, today),
+        }
+    }
+
+    pub fn section(&self) -> Option<Section> {
+        self.section.as_deref().and_then(Section::from_column)
+    }
+
     pub async fn create(
-        pool: &PgPool,
+        executor: impl PgExecutor<'_>,
         user_id: i64,
-        name: &str,
-        description: Option<&str>,
+        kind: TaskKind,
+        details: &TaskDetails<'_>,
     ) -> sqlx::Result<i64> {
         let id: i64 = sqlx::query_scalar(
-            "INSERT INTO tasks (user_id, name, description) VALUES ($1, $2, $3) RETURNING id",
+            r#"
+            INSERT INTO tasks (user_id, kind, name, description, schedule_kind, schedule_days, weekly_target,
+                               target_value, unit, section, position, visibility)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10,
+                    (SELECT COALESCE(MIN(position), 0) - 1 FROM tasks WHERE user_id = $1),
+                    (SELECT default_task_visibility FROM users WHERE id = $1))
+            RETURNING id
+            "#,
         )
         .bind(user_id)
-        .bind(name)
-        .bind(description)
-        .fetch_one(pool)
+        .bind(kind.as_str())
+        .bind(details.name)
+        .bind(details.description)
+        .bind(details.schedule.kind())
+        .bind(details.schedule.days_mask())
+        .bind(details.schedule.weekly_target())
+        .bind(details.target_value)
+        .bind(details.unit)
+        .bind(details.section.map(|s| s.as_str()))
+        .fetch_one(executor)
         .await?;
         Ok(id)
     }
 
+    /// Saves the task's details. A new target applies from `today` on:
+    /// earlier days keep the target they were logged under.
     pub async fn update(
         pool: &PgPool,
         id: i64,
         user_id: i64,
-        name: &str,
-        description: Option<&str>,
+        details: &TaskDetails<'_>,
+        today: NaiveDate,
     ) -> sqlx::Result<()> {
-        sqlx::query("UPDATE tasks SET name = $1, description = $2 WHERE id = $3 AND user_id = $4")
-            .bind(name)
-            .bind(description)
+        let mut tx = pool.begin().await?;
+        sqlx::query(
+            r#"
+            UPDATE completions c SET target = COALESCE(t.target_value, 1)
+            FROM tasks t
+            WHERE t.id = c.task_id AND t.id = $1 AND t.user_id = $2
+              AND t.target_value IS DISTINCT FROM $3
+              AND c.target IS NULL AND c.completed_date < $4
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(details.target_value)
+        .bind(today)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            r#"
+            UPDATE tasks
+            SET name = $1, description = $2, schedule_kind = $3, schedule_days = $4, weekly_target = $5,
+                target_value = $6, unit = $7, section = $8
+            WHERE id = $9 AND user_id = $10
+            "#,
+        )
+        .bind(details.name)
+        .bind(details.description)
+        .bind(details.schedule.kind())
+        .bind(details.schedule.days_mask())
+        .bind(details.schedule.weekly_target())
+        .bind(details.target_value)
+        .bind(details.unit)
+        .bind(details.section.map(|s| s.as_str()))
+        .bind(id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+        // A new schedule reads the whole history differently, and a new
+        // target changes today
+        streak_cache::invalidate(&mut *tx, id).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn archive(pool: &PgPool, id: i64, user_id: i64) -> sqlx::Result<()> {
+        sqlx::query("UPDATE tasks SET archived = TRUE WHERE id = $1 AND user_id = $2")
             .bind(id)
             .bind(user_id)
             .execute(pool)
@@ -60,116 +363,289 @@ impl Task {
         Ok(())
     }
 
-    pub async fn archive(pool: &PgPool, id: i64, user_id: i64) -> sqlx::Result<()> {
-        sqlx::query("UPDATE tasks SET archived = TRUE WHERE id = $1 AND user_id = $2")
+    /// Saves a new dashboard order: each task takes its index as position
+    /// and moves to the given section. Ids of other users' tasks are ignored.
+    pub async fn reorder(pool: &PgPool, user_id: i64, order: &[(i64, Option<Section>)]) -> sqlx::Result<()> {
+        let mut tx = pool.begin().await?;
+        for (position, (id, section)) in order.iter().enumerate() {
+            sqlx::query("UPDATE tasks SET position = $1, section = $2 WHERE id = $3 AND user_id = $4")
+                .bind(position as i32)
+                .bind(section.map(|s| s.as_str()))
+                .bind(id)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn restore(pool: &PgPool, id: i64, user_id: i64) -> sqlx::Result<()> {
+        sqlx::query("UPDATE tasks SET archived = FALSE WHERE id = $1 AND user_id = $2")
             .bind(id)
             .bind(user_id)
             .execute(pool)
             .await?;
         Ok(())
     }
+
+    /// Permanently deletes an archived task along with its history. Returns
+    /// false if the task was not archived, so live tasks are never deleted.
+    pub async fn delete(pool: &PgPool, id: i64, user_id: i64) -> sqlx::Result<bool> {
+        let result = sqlx::query("DELETE FROM tasks WHERE id = $1 AND user_id = $2 AND archived = TRUE")
+            .bind(id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
 }
 
 impl TaskWithStreak {
+    /// Builds the task from its full history of completion (or slip) dates
+    /// and freezes.
+    pub fn from_task(
+        task: Task,
+        completed_dates: Vec<NaiveDate>,
+        frozen_dates: Vec<NaiveDate>,
+        today_value: i32,
+        today: NaiveDate,
+    ) -> Self {
+        let completed: HashSet<NaiveDate> = completed_dates.into_iter().collect();
+        let frozen: HashSet<NaiveDate> = frozen_dates.into_iter().collect();
+        let summary = task.streak_summary(&completed, &frozen, today);
+        let total_completions = completed.len() as i64;
+        Self::from_summary(task, summary, total_completions, &completed, today_value, today)
+    }
+
+    /// Builds the task from a cached streak; `recent` only needs the dates
+    /// from the start of this week.
+    fn from_cached(
+        task: Task,
+        cached: &CachedStreak,
+        recent: &HashSet<NaiveDate>,
+        today_value: i32,
+        today: NaiveDate,
+    ) -> Self {
+        let summary = StreakSummary {
+            current: Streak {
+                length: cached.current_streak.into(),
+                frozen: cached.frozen,
+            },
+            best: cached.best_streak.into(),
+            runs: Vec::new(),
+        };
+        Self::from_summary(task, summary, cached.total_completions.into(), recent, today_value, today)
+    }
+
+    fn from_summary(
+        task: Task,
+        summary: StreakSummary,
+        total_completions: i64,
+        completed: &HashSet<NaiveDate>,
+        today_value: i32,
+        today: NaiveDate,
+    ) -> Self {
+        let kind = task.kind();
+        let section = task.section();
+        let schedule = task.schedule();
+
+        if kind == TaskKind::Avoid {
+            let clean_today = !completed.contains(&today);
+            return Self {
+                id: task.id,
+                user_id: task.user_id,
+                name: task.name,
+                description: task.description,
+                created_at: task.created_at,
+                archived: task.archived,
+                kind,
+                section,
+                schedule: Schedule::Daily,
+                target_value: None,
+                unit: None,
+                today_value: 0,
+                today_note: None,
+                logged_today: !clean_today,
+                current_streak: summary.current.length,
+                frozen: false,
+                completed_today: clean_today,
+                due_today: true,
+                completed_this_week: 0,
+                best_streak: summary.best,
+                total_completions,
+                runs: summary.runs,
+                reactions: Vec::new(),
+            };
+        }
+
+        let completed_today = completed.contains(&today);
+        let completed_this_week = completions_in_week(completed, week_start(today), today);
+        let due_today = match schedule {
+            Schedule::Weekly(target) => {
+                completed_today || completed_this_week < target as i64
+            }
+            _ => schedule.is_due_on(today),
+        };
+
+        Self {
+            id: task.id,
+            user_id: task.user_id,
+            name: task.name,
+            description: task.description,
+            created_at: task.created_at,
+            archived: task.archived,
+            kind,
+            section,
+            schedule,
+            target_value: task.target_value,
+            unit: task.unit,
+            today_value,
+            today_note: None,
+            logged_today: completed_today || today_value > 0,
+            current_streak: summary.current.length,
+            frozen: summary.current.frozen,
+            completed_today,
+            due_today,
+            completed_this_week,
+            best_streak: summary.best,
+            total_completions,
+            runs: summary.runs,
+            reactions: Vec::new(),
+        }
+    }
+
+    /// Builds tasks from the streak cache, reading only this week's
+    /// completions. Tasks without a row for `today` fall back to their full
+    /// history. `runs` is left empty; see `with_history`.
+    pub(crate) async fn from_tasks(pool: &PgPool, tasks: Vec<Task>, today: NaiveDate) -> sqlx::Result<Vec<Self>> {
+        let ids: Vec<i64> = tasks.iter().map(|t| t.id).collect();
+        let cached = streak_cache::fresh_for(pool, &ids, today).await?;
+        let (hits, misses): (Vec<i64>, Vec<i64>) = ids.iter().partition(|id| cached.contains_key(id));
+
+        let mut recent = completion::dates_by_task_since(pool, &hits, week_start(today)).await?;
+        let mut dates = completion::dates_by_task(pool, &misses).await?;
+        let mut freezes = freeze::dates_by_task(pool, &misses).await?;
+        let values = completion::values_on(pool, &ids, today).await?;
+        let mut notes = completion::notes_on(pool, &ids, today).await?;
+        let mut reactions = reaction::on(pool, &ids, today).await?;
+        Ok(tasks
+            .into_iter()
+            .map(|task| {
+                let today_value = values.get(&task.id).copied().unwrap_or(0);
+                let note = notes.remove(&task.id);
+                let task_reactions = reactions.remove(&task.id).unwrap_or_default();
+                let mut task = match cached.get(&task.id) {
+                    Some(row) => {
+                        let recent: HashSet<NaiveDate> =
+                            recent.remove(&task.id).unwrap_or_default().into_iter().collect();
+                        Self::from_cached(task, row, &recent, today_value, today)
+                    }
+                    None => {
+                        let completed = dates.remove(&task.id).unwrap_or_default();
+                        let frozen = freezes.remove(&task.id).unwrap_or_default();
+                        let mut task = Self::from_task(task, completed, frozen, today_value, today);
+                        task.runs.clear();
+                        task
+                    }
+                };
+                task.today_note = note;
+                task.reactions = task_reactions;
+                task
+            })
+            .collect())
+    }
+
+    /// Like `from_tasks`, but replays each task's full history so every past
+    /// run is filled in.
+    pub(crate) async fn with_history(pool: &PgPool, tasks: Vec<Task>, today: NaiveDate) -> sqlx::Result<Vec<Self>> {
+        let ids: Vec<i64> = tasks.iter().map(|t| t.id).collect();
+        let mut dates = completion::dates_by_task(pool, &ids).await?;
+        let mut freezes = freeze::dates_by_task(pool, &ids).await?;
+        let values = completion::values_on(pool, &ids, today).await?;
+        let mut notes = completion::notes_on(pool, &ids, today).await?;
+        let mut reactions = reaction::on(pool, &ids, today).await?;
+        Ok(tasks
+            .into_iter()
+            .map(|task| {
+                let completed = dates.remove(&task.id).unwrap_or_default();
+                let frozen = freezes.remove(&task.id).unwrap_or_default();
+                let today_value = values.get(&task.id).copied().unwrap_or(0);
+                let note = notes.remove(&task.id);
+                let task_reactions = reactions.remove(&task.id).unwrap_or_default();
+                let mut task = Self::from_task(task, completed, frozen, today_value, today);
+                task.today_note = note;
+                task.reactions = task_reactions;
+                task
+            })
+            .collect())
+    }
+
     pub async fn for_user(pool: &PgPool, user_id: i64, today: NaiveDate) -> sqlx::Result<Vec<Self>> {
-        sqlx::query_as(
-            r#"
-            WITH RECURSIVE streak_cte(task_id, streak_date, streak_count) AS (
-                -- Base case: check if completed today
-                SELECT c.task_id, c.completed_date, 1
-                FROM completions c
-                JOIN tasks t ON t.id = c.task_id
-                WHERE t.user_id = $1
-                  AND c.completed_date = $2
-                UNION ALL
-                -- If not completed today, check yesterday as base
-                SELECT c.task_id, c.completed_date, 1
-                FROM completions c
-                JOIN tasks t ON t.id = c.task_id
-                WHERE t.user_id = $1
-                  AND c.completed_date = $2 - INTERVAL '1 day'
-                  AND NOT EXISTS (
-                      SELECT 1 FROM completions c2
-                      WHERE c2.task_id = c.task_id
-                        AND c2.completed_date = $2
-                  )
-                UNION ALL
-                -- Recursive: walk backwards day by day
-                SELECT s.task_id, c.completed_date, s.streak_count + 1
-                FROM streak_cte s
-                JOIN completions c ON c.task_id = s.task_id
-                  AND c.completed_date = s.streak_date - INTERVAL '1 day'
-            )
-            SELECT
-                t.id,
-                t.user_id,
-                t.name,
-                t.description,
-                t.created_at,
-                t.archived,
-                COALESCE(MAX(s.streak_count), 0)::BIGINT AS current_streak,
-                EXISTS (
-                    SELECT 1 FROM completions c
-                    WHERE c.task_id = t.id AND c.completed_date = $2
-                ) AS completed_today
-            FROM tasks t
-            LEFT JOIN streak_cte s ON s.task_id = t.id
-            WHERE t.user_id = $1 AND t.archived = FALSE
-            GROUP BY t.id
-            ORDER BY t.created_at DESC
-            "#,
+        let tasks: Vec<Task> = sqlx::query_as(
+            "SELECT * FROM tasks WHERE user_id = $1 AND archived = FALSE ORDER BY position, created_at DESC",
         )
         .bind(user_id)
-        .bind(today)
         .fetch_all(pool)
-        .await
+        .await?;
+        Self::from_tasks(pool, tasks, today).await
     }
 
-    pub async fn find_by_id(pool: &PgPool, task_id: i64, today: NaiveDate) -> sqlx::Result<Option<Self>> {
-        sqlx::query_as(
-            r#"
-            WITH RECURSIVE streak_cte(task_id, streak_date, streak_count) AS (
-                SELECT c.task_id, c.completed_date, 1
-                FROM completions c
-                WHERE c.task_id = $1
-                  AND c.completed_date = $2
-                UNION ALL
-                SELECT c.task_id, c.completed_date, 1
-                FROM completions c
-                WHERE c.task_id = $1
-                  AND c.completed_date = $2 - INTERVAL '1 day'
-                  AND NOT EXISTS (
-                      SELECT 1 FROM completions c2
-                      WHERE c2.task_id = c.task_id
-                        AND c2.completed_date = $2
-                  )
-                UNION ALL
-                SELECT s.task_id, c.completed_date, s.streak_count + 1
-                FROM streak_cte s
-                JOIN completions c ON c.task_id = s.task_id
-                  AND c.completed_date = s.streak_date - INTERVAL '1 day'
-            )
-            SELECT
-                t.id,
-                t.user_id,
-                t.name,
-                t.description,
-                t.created_at,
-                t.archived,
-                COALESCE(MAX(s.streak_count), 0)::BIGINT AS current_streak,
-                EXISTS (
-                    SELECT 1 FROM completions c
-                    WHERE c.task_id = t.id AND c.completed_date = $2
-                ) AS completed_today
-            FROM tasks t
-            LEFT JOIN streak_cte s ON s.task_id = t.id
-            WHERE t.id = $1
-            GROUP BY t.id
-            "#,
+    pub async fn archived_for_user(pool: &PgPool, user_id: i64, today: NaiveDate) -> sqlx::Result<Vec<Self>> {
+        let tasks: Vec<Task> = sqlx::query_as(
+            "SELECT * FROM tasks WHERE user_id = $1 AND archived = TRUE ORDER BY created_at DESC",
         )
-        .bind(task_id)
-        .bind(today)
-        .fetch_optional(pool)
-        .await
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+        Self::with_history(pool, tasks, today).await
+    }
+
+    pub async fn find_by_id(pool: &PgPool, task_id: i64, today: NaiveDate) -> sqlx::Result<Option<Self>> {
+        let task: Option<Task> = sqlx::query_as("SELECT * FROM tasks WHERE id = $1")
+            .bind(task_id)
+            .fetch_optional(pool)
+            .await?;
+        let Some(task) = task else {
+            return Ok(None);
+        };
+        Ok(Self::from_tasks(pool, vec![task], today).await?.pop())
+    }
+
+    /// Like `find_by_id`, with every past run filled in.
+    pub async fn find_with_history(pool: &PgPool, task_id: i64, today: NaiveDate) -> sqlx::Result<Option<Self>> {
+        let task: Option<Task> = sqlx::query_as("SELECT * FROM tasks WHERE id = $1")
+            .bind(task_id)
+            .fetch_optional(pool)
+            .await?;
+        let Some(task) = task else {
+            return Ok(None);
+        };
+        Ok(Self::with_history(pool, vec![task], today).await?.pop())
+    }
+
+    pub fn is_avoid(&self) -> bool {
+        self.kind == TaskKind::Avoid
+    }
+
+    pub fn section_options(&self) -> Vec<(&'static str, &'static str, bool)> {
+        Section::options(self.section)
+    }
+
+    /// Share of today's target reached, from 0.0 to 1.0.
+    pub fn today_fraction(&self) -> f64 {
+        match self.target_value {
+            _ if self.completed_today => 1.0,
+            Some(target) if target > 0 => (self.today_value as f64 / target as f64).min(1.0),
+            _ => 0.0,
+        }
+    }
+
+    pub fn today_percent(&self) -> i64 {
+        (self.today_fraction() * 100.0).round() as i64
+    }
+
+    pub fn streak_unit(&self) -> &'static str {
+        if self.schedule.is_weekly() { "week" } else { "day" }
     }
 }
@@ -1,6 +1,14 @@
-use chrono::NaiveDateTime;
+use chrono::{DateTime, Days, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Utc};
+use chrono_tz::Tz;
 use sqlx::PgPool;
 
+use crate::auth::{DEFAULT_TIME_ZONE, parse_time_zone};
+use crate::models::sharing::Visibility;
+
+/// Latest hour a day may be stretched to; later than this and "yesterday"
+/// would swallow most of the morning.
+pub const MAX_DAY_ENDS_HOUR: i16 = 6;
+
 #[derive(sqlx::FromRow)]
 #[allow(dead_code)]
 pub struct User {
@@ -9,6 +17,53 @@ pub struct User {
     pub email: String,
     pub password_hash: String,
     pub created_at: NaiveDateTime,
+    /// IANA zone name, e.g. "Europe/Madrid".
+    pub time_zone: String,
+    /// Local hour at which the user's day ends; 0 is midnight.
+    pub day_ends_hour: i16,
+    /// Visibility new tasks start with, 'groups' or 'private'.
+    pub default_task_visibility: String,
+    /// Streak lengths to celebrate, in ascending order.
+    pub milestone_thresholds: Vec<i32>,
+}
+
+/// Where and when a user's day turns over.
+#[derive(sqlx::FromRow)]
+pub struct DayBoundary {
+    pub time_zone: String,
+    pub day_ends_hour: i16,
+}
+
+impl Default for DayBoundary {
+    fn default() -> Self {
+        Self {
+            time_zone: DEFAULT_TIME_ZONE.name().to_string(),
+            day_ends_hour: 0,
+        }
+    }
+}
+
+impl DayBoundary {
+    pub fn zone(&self) -> Tz {
+        parse_time_zone(&self.time_zone).unwrap_or(DEFAULT_TIME_ZONE)
+    }
+
+    pub fn rollover(&self) -> TimeDelta {
+        TimeDelta::hours(self.day_ends_hour.into())
+    }
+
+    /// The user's date at `now`; the hours before rollover still belong to
+    /// the previous day.
+    pub fn today(&self, now: DateTime<Utc>) -> NaiveDate {
+        (now - self.rollover()).with_timezone(&self.zone()).date_naive()
+    }
+
+    /// How long until the user's day ends, by the local clock.
+    pub fn time_left(&self, now: DateTime<Utc>) -> TimeDelta {
+        let local = (now - self.rollover()).with_timezone(&self.zone()).naive_local();
+        let next_day = local.date() + Days::new(1);
+        next_day.and_time(NaiveTime::MIN) - local
+    }
 }
 
 impl User {
@@ -31,16 +86,67 @@ impl User {
         username: &str,
         email: &str,
         password_hash: &str,
+        time_zone: &str,
     ) -> sqlx::Result<i64> {
         let id: i64 = sqlx::query_scalar(
-            "INSERT INTO users (username, email, password_hash) VALUES ($1, $2, $3) RETURNING id",
+            "INSERT INTO users (username, email, password_hash, time_zone) VALUES ($1, $2, $3, $4) RETURNING id",
         )
         .bind(username)
         .bind(email)
         .bind(password_hash)
+        .bind(time_zone)
         .fetch_one(pool)
         .await?;
         Ok(id)
     }
 
+    pub async fn day_boundary(pool: &PgPool, id: i64) -> sqlx::Result<Option<DayBoundary>> {
+        sqlx::query_as("SELECT time_zone, day_ends_hour FROM users WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    pub async fn set_time_zone(pool: &PgPool, id: i64, time_zone: &str) -> sqlx::Result<()> {
+        sqlx::query("UPDATE users SET time_zone = $1 WHERE id = $2")
+            .bind(time_zone)
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_default_task_visibility(pool: &PgPool, id: i64, visibility: Visibility) -> sqlx::Result<()> {
+        sqlx::query("UPDATE users SET default_task_visibility = $1 WHERE id = $2")
+            .bind(visibility.as_str())
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn milestone_thresholds(pool: &PgPool, id: i64) -> sqlx::Result<Vec<i32>> {
+        sqlx::query_scalar("SELECT milestone_thresholds FROM users WHERE id = $1")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+    }
+
+    pub async fn set_milestone_thresholds(pool: &PgPool, id: i64, thresholds: &[i32]) -> sqlx::Result<()> {
+        sqlx::query("UPDATE users SET milestone_thresholds = $1 WHERE id = $2")
+            .bind(thresholds)
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_day_ends_hour(pool: &PgPool, id: i64, hour: i16) -> sqlx::Result<()> {
+        sqlx::query("UPDATE users SET day_ends_hour = $1 WHERE id = $2")
+            .bind(hour)
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
 }
@@ -9,10 +9,15 @@ use serde::Deserialize;
 use tower_sessions::Session;
 
 use crate::AppState;
-use crate::auth::{hash_password, verify_password, login_session, logout_session};
+use crate::auth::{
+    DEFAULT_TIME_ZONE, hash_password, login_session, logout_session, parse_time_zone, take_return_to,
+    verify_password,
+};
 use crate::models::user::User;
 use crate::templates::auth::{LoginTemplate, RegisterTemplate};
 
+use super::groups::join_pending_invite;
+
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/login", get(login_page).post(login_submit))
@@ -55,7 +60,7 @@ async fn login_submit(
         .await
         .map_err(|_| LoginTemplate::with_error("Session error"))?;
 
-    Ok(Redirect::to("/"))
+    Ok(after_login(&state, &session, user.id).await)
 }
 
 async fn register_page() -> RegisterTemplate {
@@ -71,6 +76,8 @@ struct RegisterForm {
     username: String,
     email: String,
     password: String,
+    /// Filled in from the browser; falls back to the default zone.
+    time_zone: Option<String>,
 }
 
 async fn register_submit(
@@ -88,7 +95,13 @@ async fn register_submit(
     let password_hash = hash_password(&form.password)
         .map_err(|_| RegisterTemplate::with_error("Internal error"))?;
 
-    let user_id = User::create(&state.db, &form.username, &form.email, &password_hash)
+    let time_zone = form
+        .time_zone
+        .as_deref()
+        .and_then(parse_time_zone)
+        .unwrap_or(DEFAULT_TIME_ZONE);
+
+    let user_id = User::create(&state.db, &form.username, &form.email, &password_hash, time_zone.name())
         .await
         .map_err(|_| RegisterTemplate::with_error("Username or email already taken"))?;
 
@@ -96,7 +109,17 @@ async fn register_submit(
         .await
         .map_err(|_| RegisterTemplate::with_error("Session error"))?;
 
-    Ok(Redirect::to("/"))
+    Ok(after_login(&state, &session, user_id).await)
+}
+
+/// Where a freshly signed-in user goes: into the group of an invite they
+/// opened, back to the page that asked them to log in, or the dashboard.
+async fn after_login(state: &AppState, session: &Session, user_id: i64) -> Redirect {
+    if let Some(redirect) = join_pending_invite(state, session, user_id).await {
+        return redirect;
+    }
+    let target = take_return_to(session).await.unwrap_or_else(|| "/".to_string());
+    Redirect::to(&target)
 }
 
 async fn logout(session: Session) -> Redirect {
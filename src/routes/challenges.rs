@@ -0,0 +1,136 @@
+use axum::{
+    Router,
+    extract::{Path, State},
+    response::{IntoResponse, Redirect, Response},
+    routing::{get, post},
+    Form,
+    http::StatusCode,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use sqlx::PgPool;
+use tower_sessions::Session;
+
+use crate::AppState;
+use crate::auth::{AuthUser, LocalDate};
+use crate::flash;
+use crate::models::challenge::{self, Challenge, FinalSummary};
+use crate::templates::challenges::ChallengeTemplate;
+
+use super::groups::{check_can_manage, find_member_group, group_redirect};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/groups/{id}/challenges", post(create_challenge))
+        .route("/groups/{id}/challenges/{challenge_id}", get(show_challenge))
+        .route("/groups/{id}/challenges/{challenge_id}/join", post(join_challenge))
+}
+
+fn challenge_redirect(id: i64, challenge_id: i64) -> Response {
+    Redirect::to(&format!("/groups/{id}/challenges/{challenge_id}")).into_response()
+}
+
+/// A challenge in a group the user belongs to: 404 if there's no such
+/// challenge there, 403 if they aren't a member.
+async fn find_challenge(db: &PgPool, id: i64, challenge_id: i64, user_id: i64) -> Result<Challenge, StatusCode> {
+    find_member_group(db, id, user_id).await?;
+    match challenge::find(db, id, challenge_id).await {
+        Ok(Some(challenge)) => Ok(challenge),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+#[derive(Deserialize)]
+struct ChallengeForm {
+    name: String,
+    #[serde(default)]
+    description: String,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+}
+
+async fn create_challenge(
+    State(state): State<AppState>,
+    user: AuthUser,
+    session: Session,
+    LocalDate(today): LocalDate,
+    Path(id): Path<i64>,
+    Form(form): Form<ChallengeForm>,
+) -> Response {
+    if let Err(status) = check_can_manage(&state.db, id, user.id).await {
+        return status.into_response();
+    }
+    if let Err(err) = challenge::validate(&form.name, form.start_date, form.end_date, today) {
+        flash::set(&session, err.message(), true).await;
+        return group_redirect(id);
+    }
+
+    let description = Some(form.description.trim()).filter(|d| !d.is_empty());
+    match challenge::create(&state.db, id, user.id, &form.name, description, form.start_date, form.end_date).await {
+        Ok(challenge_id) => challenge_redirect(id, challenge_id),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+async fn show_challenge(
+    State(state): State<AppState>,
+    user: AuthUser,
+    session: Session,
+    LocalDate(today): LocalDate,
+    Path((id, challenge_id)): Path<(i64, i64)>,
+) -> Response {
+    let challenge = match find_challenge(&state.db, id, challenge_id, user.id).await {
+        Ok(challenge) => challenge,
+        Err(status) => return status.into_response(),
+    };
+    let standings = match challenge::standings(&state.db, &challenge, state.clock.now()).await {
+        Ok(standings) => standings,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+    let summary = if challenge.is_ended(today) {
+        FinalSummary::from_standings(&standings)
+    } else {
+        None
+    };
+    let flash = flash::take(&session).await;
+
+    ChallengeTemplate {
+        joined: standings.iter().any(|s| s.user_id == user.id),
+        challenge,
+        standings,
+        summary,
+        today,
+        user_id: user.id,
+        flash_is_error: flash.as_ref().is_some_and(|(_, is_error)| *is_error),
+        flash_message: flash.map(|(message, _)| message),
+    }
+    .into_response()
+}
+
+async fn join_challenge(
+    State(state): State<AppState>,
+    user: AuthUser,
+    session: Session,
+    LocalDate(today): LocalDate,
+    Path((id, challenge_id)): Path<(i64, i64)>,
+) -> Response {
+    let challenge = match find_challenge(&state.db, id, challenge_id, user.id).await {
+        Ok(challenge) => challenge,
+        Err(status) => return status.into_response(),
+    };
+    if challenge.is_ended(today) {
+        flash::set(&session, "This challenge has ended", true).await;
+        return challenge_redirect(id, challenge_id);
+    }
+
+    match challenge::join(&state.db, &challenge, user.id).await {
+        Ok(true) => {
+            let message = format!("You joined {}; it's on your dashboard as a daily task", challenge.name);
+            flash::set(&session, message, false).await;
+            challenge_redirect(id, challenge_id)
+        }
+        Ok(false) => challenge_redirect(id, challenge_id),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
@@ -3,13 +3,16 @@ use axum::{
     extract::State,
     routing::get,
 };
+use tower_sessions::Session;
 
 use crate::AppState;
 use crate::auth::{AuthUser, LocalDate};
+use crate::flash;
 use crate::models::task::TaskWithStreak;
 use crate::models::user::User;
 use crate::models::group::Group;
-use crate::templates::dashboard::DashboardTemplate;
+use crate::templates::dashboard::{DashboardTemplate, Progress};
+use crate::templates::tasks::TaskSection;
 
 pub fn router() -> Router<AppState> {
     Router::new().route("/", get(dashboard))
@@ -18,6 +21,7 @@ pub fn router() -> Router<AppState> {
 async fn dashboard(
     State(state): State<AppState>,
     user: AuthUser,
+    session: Session,
     LocalDate(today): LocalDate,
 ) -> DashboardTemplate {
     let db_user = User::find_by_id(&state.db, user.id).await.ok().flatten();
@@ -25,20 +29,15 @@ async fn dashboard(
     let tasks = TaskWithStreak::for_user(&state.db, user.id, today).await.unwrap_or_default();
     let groups = Group::user_groups(&state.db, user.id).await.unwrap_or_default();
 
-    let total_count = tasks.len() as i64;
-    let completed_count = tasks.iter().filter(|t| t.completed_today).count() as i64;
-    let active_streak_count = tasks.iter().filter(|t| t.current_streak > 0).count() as i64;
-    let longest_streak = tasks.iter().map(|t| t.current_streak).max().unwrap_or(0);
+    let progress = Progress::from_tasks(&tasks);
+    let flash = flash::take(&session).await;
 
     DashboardTemplate {
         username,
-        tasks,
+        sections: TaskSection::group(tasks),
         groups,
-        flash_message: None,
-        flash_is_error: false,
-        completed_count,
-        total_count,
-        active_streak_count,
-        longest_streak,
+        flash_is_error: flash.as_ref().is_some_and(|(_, is_error)| *is_error),
+        flash_message: flash.map(|(message, _)| message),
+        progress,
     }
 }
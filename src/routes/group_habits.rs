@@ -0,0 +1,141 @@
+use axum::{
+    Router,
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+    routing::post,
+    Form,
+    http::StatusCode,
+};
+use serde::Deserialize;
+use sqlx::PgPool;
+use tower_sessions::Session;
+
+use crate::AppState;
+use crate::auth::{AuthUser, LocalDate};
+use crate::flash;
+use crate::models::group_habit::{self, GroupHabit};
+use crate::templates::groups::GroupHabitPartial;
+
+use super::groups::{check_can_manage, find_member_group, group_redirect};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/groups/{id}/habits", post(create_habit))
+        .route("/groups/{id}/habits/{habit_id}/join", post(join_habit))
+        .route("/groups/{id}/habits/{habit_id}/leave", post(leave_habit))
+        .route("/groups/{id}/habits/{habit_id}/check-in", post(toggle_checkin))
+}
+
+/// A habit of a group the user belongs to: 404 if the group has no such
+/// habit, 403 if they aren't a member.
+async fn find_habit(db: &PgPool, id: i64, habit_id: i64, user_id: i64) -> Result<GroupHabit, StatusCode> {
+    find_member_group(db, id, user_id).await?;
+    match group_habit::find(db, id, habit_id).await {
+        Ok(Some(habit)) => Ok(habit),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+#[derive(Deserialize)]
+struct HabitForm {
+    name: String,
+    /// Share of participants needed each day; empty for everyone.
+    #[serde(default)]
+    required_percent: String,
+}
+
+async fn create_habit(
+    State(state): State<AppState>,
+    user: AuthUser,
+    session: Session,
+    LocalDate(today): LocalDate,
+    Path(id): Path<i64>,
+    Form(form): Form<HabitForm>,
+) -> Response {
+    if let Err(status) = check_can_manage(&state.db, id, user.id).await {
+        return status.into_response();
+    }
+    if form.name.trim().is_empty() {
+        flash::set(&session, "Give the habit a name", true).await;
+        return group_redirect(id);
+    }
+    let required_percent = match form.required_percent.trim() {
+        "" => 100,
+        value => match value.parse::<i16>() {
+            Ok(percent) if (1..=100).contains(&percent) => percent,
+            _ => {
+                flash::set(&session, "The share needed each day must be from 1 to 100%", true).await;
+                return group_redirect(id);
+            }
+        },
+    };
+
+    match group_habit::create(&state.db, id, user.id, &form.name, required_percent, today).await {
+        Ok(_) => group_redirect(id),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+async fn join_habit(
+    State(state): State<AppState>,
+    user: AuthUser,
+    LocalDate(today): LocalDate,
+    Path((id, habit_id)): Path<(i64, i64)>,
+) -> Response {
+    if let Err(status) = find_habit(&state.db, id, habit_id, user.id).await {
+        return status.into_response();
+    }
+    match group_habit::join(&state.db, habit_id, user.id, today).await {
+        Ok(_) => group_redirect(id),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+async fn leave_habit(
+    State(state): State<AppState>,
+    user: AuthUser,
+    LocalDate(today): LocalDate,
+    Path((id, habit_id)): Path<(i64, i64)>,
+) -> Response {
+    if let Err(status) = find_habit(&state.db, id, habit_id, user.id).await {
+        return status.into_response();
+    }
+    match group_habit::leave(&state.db, habit_id, user.id, today).await {
+        Ok(()) => group_redirect(id),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+/// Checks the user in for today, or takes it back, answering with the
+/// habit's refreshed card.
+async fn toggle_checkin(
+    State(state): State<AppState>,
+    user: AuthUser,
+    LocalDate(today): LocalDate,
+    Path((id, habit_id)): Path<(i64, i64)>,
+) -> Response {
+    let habit = match find_habit(&state.db, id, habit_id, user.id).await {
+        Ok(habit) => habit,
+        Err(status) => return status.into_response(),
+    };
+    let status = match group_habit::status(&state.db, habit, state.clock.now()).await {
+        Ok(status) => status,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+    if !status.is_participant(user.id) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+
+    if group_habit::toggle_checkin(&state.db, habit_id, user.id, today).await.is_err() {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    match group_habit::status(&state.db, status.habit, state.clock.now()).await {
+        Ok(habit) => GroupHabitPartial {
+            habit,
+            user_id: user.id,
+        }
+        .into_response(),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
@@ -1,17 +1,34 @@
 use axum::{
     Router,
-    extract::{State, Path},
+    extract::{Path, Query, State},
     response::{IntoResponse, Redirect, Response},
     routing::{get, post},
     Form,
     http::StatusCode,
 };
+use chrono::TimeDelta;
 use serde::Deserialize;
+use sqlx::PgPool;
+use tower_sessions::Session;
 
 use crate::AppState;
-use crate::auth::{AuthUser, LocalDate};
-use crate::models::group::{Group, MemberWithStreaks};
-use crate::templates::groups::{GroupFeedTemplate, CreateGroupFormPartial, JoinGroupFormPartial};
+use crate::auth::{AuthUser, LocalDate, session_user_id};
+use crate::models::challenge;
+use crate::models::group_habit;
+use crate::models::event::{self, EventKind};
+use crate::models::notification;
+use crate::models::reaction::{self, Reaction};
+use crate::models::sharing;
+use crate::models::user::User;
+use crate::flash;
+use crate::models::group::{Group, GroupMember, GroupRole, LeaveOutcome, MemberWithStreaks};
+use crate::models::invite::{self, JoinError};
+use crate::templates::groups::{
+    ActivityPartial, ConfirmPartial, GroupFeedTemplate, CreateGroupFormPartial, JoinGroupFormPartial, JoinTemplate, ReactionsPartial,
+};
+
+/// Invite code a signed-out visitor opened, joined once they log in or sign up.
+const PENDING_INVITE_KEY: &str = "pending_invite";
 
 pub fn router() -> Router<AppState> {
     Router::new()
@@ -20,6 +37,35 @@ pub fn router() -> Router<AppState> {
         .route("/groups/join", post(join_group))
         .route("/groups/join-form", get(join_form))
         .route("/groups/{id}", get(group_feed))
+        .route("/groups/{id}/activity", get(group_activity))
+        .route("/groups/{id}/members/{user_id}/role", post(set_member_role))
+        .route("/groups/{id}/leave-confirm", get(confirm_leave))
+        .route("/groups/{id}/leave", post(leave_group))
+        .route("/groups/{id}/delete-confirm", get(confirm_delete))
+        .route("/groups/{id}/delete", post(delete_group))
+        .route("/groups/{id}/members/{user_id}/remove-confirm", get(confirm_remove))
+        .route("/groups/{id}/members/{user_id}/remove", post(remove_member))
+        .route("/groups/{id}/members/{user_id}/transfer-confirm", get(confirm_transfer))
+        .route("/groups/{id}/members/{user_id}/transfer", post(transfer_ownership))
+        .route("/groups/{id}/invite-code", post(rotate_invite_code))
+        .route("/groups/{id}/invites", post(create_invite))
+        .route("/groups/{id}/invites/{invite_id}/revoke", post(revoke_invite))
+        .route("/join/{code}", get(join_page).post(join_by_link))
+        .route("/groups/{id}/tasks/{task_id}/reactions", post(toggle_reaction))
+}
+
+/// Looks up a group the user belongs to, with their role in it: 404 if there
+/// is no such group, 403 if they aren't a member.
+pub(super) async fn find_member_group(db: &PgPool, id: i64, user_id: i64) -> Result<(Group, GroupRole), StatusCode> {
+    let group = match Group::find_by_id(db, id).await {
+        Ok(Some(group)) => group,
+        _ => return Err(StatusCode::NOT_FOUND),
+    };
+    match Group::role_of(db, id, user_id).await {
+        Ok(Some(role)) => Ok((group, role)),
+        Ok(None) => Err(StatusCode::FORBIDDEN),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
 }
 
 async fn create_form(_user: AuthUser) -> CreateGroupFormPartial {
@@ -52,48 +98,564 @@ struct JoinGroupForm {
 async fn join_group(
     State(state): State<AppState>,
     user: AuthUser,
+    session: Session,
     Form(form): Form<JoinGroupForm>,
 ) -> Redirect {
     let code = form.invite_code.trim().to_uppercase();
-    if let Ok(Some(group)) = Group::find_by_invite_code(&state.db, &code).await {
-        let _ = Group::join(&state.db, group.id, user.id).await;
+    match redeem(&state, &code, user.id).await {
+        Ok(Ok(group)) => flash::set(&session, format!("You joined {}", group.name), false).await,
+        Ok(Err(err)) => flash::set(&session, err.message(), true).await,
+        Err(_) => flash::set(&session, "Couldn't join the group, please try again", true).await,
     }
     Redirect::to("/")
 }
 
 async fn group_feed(
     State(state): State<AppState>,
-    _user: AuthUser,
+    user: AuthUser,
+    session: Session,
     LocalDate(today): LocalDate,
     Path(id): Path<i64>,
 ) -> Response {
-    let group = match Group::find_by_id(&state.db, id).await {
-        Ok(Some(g)) => g,
-        _ => return StatusCode::NOT_FOUND.into_response(),
+    let (group, role) = match find_member_group(&state.db, id, user.id).await {
+        Ok(found) => found,
+        Err(status) => return status.into_response(),
     };
-    let streaks = Group::member_streaks(&state.db, id, today).await.unwrap_or_default();
+    let streaks = Group::member_streaks(&state.db, id, state.clock.now()).await.unwrap_or_default();
     let members_grouped = group_streaks_by_member(streaks);
+    let members = Group::members(&state.db, id).await.unwrap_or_default();
+    let invites = if role.can_manage() {
+        invite::for_group(&state.db, id).await.unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    let challenges = challenge::for_group(&state.db, id).await.unwrap_or_default();
+    let habits = group_habit::for_group(&state.db, id, state.clock.now()).await.unwrap_or_default();
+    let (events, next_before) = event::timeline(&state.db, id, None).await.unwrap_or_default();
+    let flash = flash::take(&session).await;
 
     GroupFeedTemplate {
         group,
+        role,
+        members,
+        invites,
+        now: state.clock.now().naive_utc(),
+        user_id: user.id,
         members_grouped,
-        flash_message: None,
-        flash_is_error: false,
+        challenges,
+        habits,
+        today,
+        events,
+        next_before,
+        flash_is_error: flash.as_ref().is_some_and(|(_, is_error)| *is_error),
+        flash_message: flash.map(|(message, _)| message),
     }
     .into_response()
 }
 
+#[derive(Deserialize)]
+struct ActivityQuery {
+    before: Option<i64>,
+}
+
+/// Older events for the timeline's "load more".
+async fn group_activity(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<i64>,
+    Query(query): Query<ActivityQuery>,
+) -> Response {
+    if let Err(status) = find_member_group(&state.db, id, user.id).await {
+        return status.into_response();
+    }
+    match event::timeline(&state.db, id, query.before).await {
+        Ok((events, next_before)) => ActivityPartial {
+            group_id: id,
+            events,
+            next_before,
+            now: state.clock.now().naive_utc(),
+        }
+        .into_response(),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
 fn group_streaks_by_member(streaks: Vec<MemberWithStreaks>) -> Vec<(String, Vec<MemberWithStreaks>)> {
     let mut grouped: Vec<(String, Vec<MemberWithStreaks>)> = Vec::new();
     for streak in streaks {
-        if let Some(last) = grouped.last_mut() {
-            if last.0 == streak.username {
-                last.1.push(streak);
-                continue;
-            }
+        if let Some(last) = grouped.last_mut()
+            && last.0 == streak.username
+        {
+            last.1.push(streak);
+            continue;
         }
         let username = streak.username.clone();
         grouped.push((username, vec![streak]));
     }
     grouped
 }
+
+#[derive(Deserialize)]
+struct RoleForm {
+    role: String,
+}
+
+/// Only the owner hands out or takes back admin rights.
+async fn set_member_role(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((id, member_id)): Path<(i64, i64)>,
+    Form(form): Form<RoleForm>,
+) -> Response {
+    let role = match find_member_group(&state.db, id, user.id).await {
+        Ok((_, role)) => role,
+        Err(status) => return status.into_response(),
+    };
+    if !role.is_owner() {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+
+    let new_role = match form.role.as_str() {
+        "admin" => GroupRole::Admin,
+        "member" => GroupRole::Member,
+        _ => return StatusCode::BAD_REQUEST.into_response(),
+    };
+    match Group::set_role(&state.db, id, member_id, new_role).await {
+        Ok(true) => group_redirect(id),
+        Ok(false) => StatusCode::BAD_REQUEST.into_response(),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+pub(super) fn group_redirect(id: i64) -> Response {
+    Redirect::to(&format!("/groups/{id}")).into_response()
+}
+
+async fn confirm_leave(State(state): State<AppState>, user: AuthUser, Path(id): Path<i64>) -> Response {
+    let (group, role) = match find_member_group(&state.db, id, user.id).await {
+        Ok(found) => found,
+        Err(status) => return status.into_response(),
+    };
+
+    let mut message = format!("Leave {}?", group.name);
+    if role.is_owner() {
+        match Group::successor(&state.db, id, user.id).await {
+            Ok(Some(successor)) => {
+                message.push_str(&format!(" Ownership will pass to {}.", successor.username));
+            }
+            Ok(None) => message.push_str(" You're the only member, so the group will be deleted."),
+            Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        }
+    }
+    ConfirmPartial {
+        message,
+        action: format!("/groups/{id}/leave"),
+        confirm_label: "Leave",
+    }
+    .into_response()
+}
+
+async fn leave_group(
+    State(state): State<AppState>,
+    user: AuthUser,
+    LocalDate(today): LocalDate,
+    Path(id): Path<i64>,
+) -> Response {
+    if let Err(status) = find_member_group(&state.db, id, user.id).await {
+        return status.into_response();
+    }
+    match Group::leave(&state.db, id, user.id, today).await {
+        Ok(LeaveOutcome::Deleted) => Redirect::to("/").into_response(),
+        Ok(_) => {
+            let now = state.clock.now().naive_utc();
+            let _ = event::record_group(&state.db, EventKind::Left, user.id, id, now).await;
+            Redirect::to("/").into_response()
+        }
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+async fn confirm_delete(State(state): State<AppState>, user: AuthUser, Path(id): Path<i64>) -> Response {
+    match find_member_group(&state.db, id, user.id).await {
+        Ok((group, role)) if role.is_owner() => ConfirmPartial {
+            message: format!("Delete {} for everyone? This cannot be undone.", group.name),
+            action: format!("/groups/{id}/delete"),
+            confirm_label: "Delete group",
+        }
+        .into_response(),
+        Ok(_) => StatusCode::FORBIDDEN.into_response(),
+        Err(status) => status.into_response(),
+    }
+}
+
+async fn delete_group(State(state): State<AppState>, user: AuthUser, Path(id): Path<i64>) -> Response {
+    match find_member_group(&state.db, id, user.id).await {
+        Ok((_, role)) if role.is_owner() => {}
+        Ok(_) => return StatusCode::FORBIDDEN.into_response(),
+        Err(status) => return status.into_response(),
+    }
+    match Group::delete(&state.db, id).await {
+        Ok(()) => Redirect::to("/").into_response(),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+/// Another member of the group the user is acting on: 400 for themselves,
+/// 404 if there's no such member.
+async fn find_other_member(db: &PgPool, id: i64, user_id: i64, member_id: i64) -> Result<GroupMember, StatusCode> {
+    if member_id == user_id {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    match Group::member(db, id, member_id).await {
+        Ok(Some(member)) => Ok(member),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// The group and the member the user wants to remove; 403 unless their role
+/// outranks the member's.
+async fn check_can_remove(
+    db: &PgPool,
+    id: i64,
+    user_id: i64,
+    member_id: i64,
+) -> Result<(Group, GroupMember), StatusCode> {
+    let (group, role) = find_member_group(db, id, user_id).await?;
+    let member = find_other_member(db, id, user_id, member_id).await?;
+    if !role.can_remove(member.role()) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok((group, member))
+}
+
+async fn confirm_remove(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((id, member_id)): Path<(i64, i64)>,
+) -> Response {
+    let (group, member) = match check_can_remove(&state.db, id, user.id, member_id).await {
+        Ok(found) => found,
+        Err(status) => return status.into_response(),
+    };
+    ConfirmPartial {
+        message: format!("Remove {} from {}?", member.username, group.name),
+        action: format!("/groups/{id}/members/{member_id}/remove"),
+        confirm_label: "Remove",
+    }
+    .into_response()
+}
+
+async fn remove_member(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((id, member_id)): Path<(i64, i64)>,
+) -> Response {
+    if let Err(status) = check_can_remove(&state.db, id, user.id, member_id).await {
+        return status.into_response();
+    }
+    // Their habits stop expecting them as of their own today
+    let today = match User::day_boundary(&state.db, member_id).await {
+        Ok(boundary) => boundary.unwrap_or_default().today(state.clock.now()),
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+    match Group::remove_member(&state.db, id, member_id, today).await {
+        Ok(true) => {
+            let now = state.clock.now().naive_utc();
+            let _ = event::record_group(&state.db, EventKind::Removed, member_id, id, now).await;
+            group_redirect(id)
+        }
+        Ok(false) => StatusCode::NOT_FOUND.into_response(),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+/// Only the owner hands the group over, and only to another member.
+async fn check_can_transfer(
+    db: &PgPool,
+    id: i64,
+    user_id: i64,
+    member_id: i64,
+) -> Result<(Group, GroupMember), StatusCode> {
+    let (group, role) = find_member_group(db, id, user_id).await?;
+    if !role.is_owner() {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    let member = find_other_member(db, id, user_id, member_id).await?;
+    Ok((group, member))
+}
+
+async fn confirm_transfer(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((id, member_id)): Path<(i64, i64)>,
+) -> Response {
+    let (group, member) = match check_can_transfer(&state.db, id, user.id, member_id).await {
+        Ok(found) => found,
+        Err(status) => return status.into_response(),
+    };
+    ConfirmPartial {
+        message: format!("Make {} the owner of {}? You'll stay on as an admin.", member.username, group.name),
+        action: format!("/groups/{id}/members/{member_id}/transfer"),
+        confirm_label: "Transfer ownership",
+    }
+    .into_response()
+}
+
+async fn transfer_ownership(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((id, member_id)): Path<(i64, i64)>,
+) -> Response {
+    if let Err(status) = check_can_transfer(&state.db, id, user.id, member_id).await {
+        return status.into_response();
+    }
+    match Group::transfer_ownership(&state.db, id, user.id, member_id).await {
+        Ok(true) => group_redirect(id),
+        Ok(false) => StatusCode::NOT_FOUND.into_response(),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+/// Owners and admins look after the group's invites.
+pub(super) async fn check_can_manage(db: &PgPool, id: i64, user_id: i64) -> Result<(), StatusCode> {
+    let (_, role) = find_member_group(db, id, user_id).await?;
+    if !role.can_manage() {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(())
+}
+
+async fn rotate_invite_code(
+    State(state): State<AppState>,
+    user: AuthUser,
+    session: Session,
+    Path(id): Path<i64>,
+) -> Response {
+    if let Err(status) = check_can_manage(&state.db, id, user.id).await {
+        return status.into_response();
+    }
+    match invite::rotate_code(&state.db, id).await {
+        Ok(_) => {
+            flash::set(&session, "Invite code regenerated; the old one no longer works", false).await;
+            group_redirect(id)
+        }
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+struct InviteForm {
+    /// Hours until the invite expires; empty for never.
+    #[serde(default)]
+    expires_in_hours: String,
+    /// Empty for unlimited.
+    #[serde(default)]
+    max_uses: String,
+}
+
+/// Parses an optional positive number no larger than `max`; empty means none.
+fn parse_limit<T: std::str::FromStr + PartialOrd + Default>(value: &str, max: T) -> Result<Option<T>, ()> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    match value.parse::<T>() {
+        Ok(n) if n > T::default() && n <= max => Ok(Some(n)),
+        _ => Err(()),
+    }
+}
+
+async fn create_invite(
+    State(state): State<AppState>,
+    user: AuthUser,
+    session: Session,
+    Path(id): Path<i64>,
+    Form(form): Form<InviteForm>,
+) -> Response {
+    if let Err(status) = check_can_manage(&state.db, id, user.id).await {
+        return status.into_response();
+    }
+    let Ok(expires_in_hours) = parse_limit(&form.expires_in_hours, invite::MAX_EXPIRY_HOURS) else {
+        flash::set(&session, "Invites can last at most 30 days", true).await;
+        return group_redirect(id);
+    };
+    let Ok(max_uses) = parse_limit(&form.max_uses, invite::MAX_USES) else {
+        flash::set(&session, "Invites allow between 1 and 100 uses", true).await;
+        return group_redirect(id);
+    };
+
+    let now = state.clock.now().naive_utc();
+    let expires_in = expires_in_hours.map(TimeDelta::hours);
+    match invite::create(&state.db, id, user.id, expires_in, max_uses, now).await {
+        Ok(created) => {
+            flash::set(&session, format!("Invite {} created", created.code), false).await;
+            group_redirect(id)
+        }
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+async fn revoke_invite(
+    State(state): State<AppState>,
+    user: AuthUser,
+    session: Session,
+    Path((id, invite_id)): Path<(i64, i64)>,
+) -> Response {
+    if let Err(status) = check_can_manage(&state.db, id, user.id).await {
+        return status.into_response();
+    }
+    match invite::revoke(&state.db, id, invite_id).await {
+        Ok(true) => {
+            flash::set(&session, "Invite revoked", false).await;
+            group_redirect(id)
+        }
+        Ok(false) => StatusCode::NOT_FOUND.into_response(),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+/// Landing page for a shared invite link. Anyone can see which group it's
+/// for; a signed-out visitor has the invite held in their session until they
+/// log in or sign up.
+async fn join_page(State(state): State<AppState>, session: Session, Path(code): Path<String>) -> Response {
+    let code = code.trim().to_uppercase();
+    let now = state.clock.now().naive_utc();
+    let user_id = session_user_id(&session).await;
+
+    let mut page = JoinTemplate {
+        code: code.clone(),
+        group_name: String::new(),
+        member_count: 0,
+        error: None,
+        logged_in: user_id.is_some(),
+        member_group_id: None,
+        flash_message: None,
+        flash_is_error: false,
+    };
+    let group = match invite::lookup(&state.db, &code, now).await {
+        Ok(Ok(group)) => group,
+        Ok(Err(err)) => {
+            page.error = Some(err.message());
+            return (StatusCode::NOT_FOUND, page).into_response();
+        }
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+
+    page.member_count = Group::member_count(&state.db, group.id).await.unwrap_or_default();
+    match user_id {
+        Some(user_id) => {
+            if let Ok(Some(_)) = Group::role_of(&state.db, group.id, user_id).await {
+                page.member_group_id = Some(group.id);
+            }
+        }
+        None => {
+            let _ = session.insert(PENDING_INVITE_KEY, &code).await;
+        }
+    }
+    page.group_name = group.name;
+    page.into_response()
+}
+
+async fn join_by_link(
+    State(state): State<AppState>,
+    user: AuthUser,
+    session: Session,
+    Path(code): Path<String>,
+) -> Redirect {
+    redeem_and_redirect(&state, &session, &code, user.id).await
+}
+
+/// Joins the group the code is for, announcing it on the group's timeline and
+/// to its other members.
+async fn redeem(state: &AppState, code: &str, user_id: i64) -> sqlx::Result<Result<Group, JoinError>> {
+    let now = state.clock.now().naive_utc();
+    let joined = invite::redeem(&state.db, code, user_id, now).await?;
+    if let Ok(group) = &joined {
+        let _ = event::record_group(&state.db, EventKind::Joined, user_id, group.id, now).await;
+        let _ = notification::notify_joined(&state.db, user_id, group.id, now).await;
+    }
+    Ok(joined)
+}
+
+/// Joins the group and lands on its page, or returns to the dashboard with
+/// the reason it didn't work.
+async fn redeem_and_redirect(state: &AppState, session: &Session, code: &str, user_id: i64) -> Redirect {
+    let code = code.trim().to_uppercase();
+    match redeem(state, &code, user_id).await {
+        Ok(Ok(group)) => {
+            flash::set(session, format!("You joined {}", group.name), false).await;
+            Redirect::to(&format!("/groups/{}", group.id))
+        }
+        Ok(Err(err)) => {
+            flash::set(session, err.message(), true).await;
+            Redirect::to("/")
+        }
+        Err(_) => {
+            flash::set(session, "Couldn't join the group, please try again", true).await;
+            Redirect::to("/")
+        }
+    }
+}
+
+/// Joins the invite a visitor opened before logging in or signing up, if
+/// there is one.
+pub(super) async fn join_pending_invite(state: &AppState, session: &Session, user_id: i64) -> Option<Redirect> {
+    let code: String = session.remove(PENDING_INVITE_KEY).await.ok()??;
+    Some(redeem_and_redirect(state, session, &code, user_id).await)
+}
+
+#[derive(Deserialize)]
+struct ReactionForm {
+    reaction: String,
+}
+
+/// Adds or takes back a cheer on another member's task for the owner's
+/// today, which is the day their dashboard shows cheers for, answering with
+/// the task's refreshed reaction bar.
+async fn toggle_reaction(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((id, task_id)): Path<(i64, i64)>,
+    Form(form): Form<ReactionForm>,
+) -> Response {
+    if let Err(status) = find_member_group(&state.db, id, user.id).await {
+        return status.into_response();
+    }
+    let Some(picked) = Reaction::from_column(&form.reaction) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    // Members cheer each other, not themselves
+    let owner_id = match sharing::owner_in_group(&state.db, task_id, id).await {
+        Ok(Some(owner_id)) if owner_id == user.id => return StatusCode::FORBIDDEN.into_response(),
+        Ok(Some(owner_id)) => owner_id,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+    let today = match User::day_boundary(&state.db, owner_id).await {
+        Ok(boundary) => boundary.unwrap_or_default().today(state.clock.now()),
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+
+    match reaction::toggle(&state.db, task_id, user.id, today, picked).await {
+        Ok(true) => {
+            let now = state.clock.now().naive_utc();
+            let _ = notification::notify_cheer(&state.db, owner_id, user.id, task_id, picked, now).await;
+        }
+        Ok(false) => {
+            let _ = notification::withdraw_cheer(&state.db, user.id, task_id, picked).await;
+        }
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+    let summaries = reaction::on(&state.db, &[task_id], today)
+        .await
+        .ok()
+        .and_then(|mut by_task| by_task.remove(&task_id))
+        .unwrap_or_default();
+    ReactionsPartial {
+        group_id: id,
+        task_id,
+        buttons: reaction::buttons(&summaries, user.id),
+    }
+    .into_response()
+}
@@ -0,0 +1,195 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::{
+    Router,
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use chrono::{Datelike, Days, Months, NaiveDate};
+use serde::Deserialize;
+
+use crate::AppState;
+use crate::auth::{AuthUser, LocalDate};
+use crate::models::completion::{self, DayValue};
+use crate::models::freeze;
+use crate::models::task::TaskWithStreak;
+use crate::templates::tasks::{
+    CalendarDay, HeatmapDay, HeatmapWeek, HistoryCalendar, HistoryCalendarPartial, HistoryNote,
+    TaskHistoryTemplate,
+};
+
+use super::tasks::owned_by;
+
+/// Weeks shown in the heatmap, ending with the current week.
+const HEATMAP_WEEKS: u64 = 53;
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/tasks/{id}/history", get(task_history))
+}
+
+#[derive(Deserialize)]
+struct HistoryQuery {
+    /// Month to show in the calendar, as YYYY-MM.
+    month: Option<String>,
+}
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+fn monday_of(date: NaiveDate) -> NaiveDate {
+    date - Days::new(date.weekday().num_days_from_monday() as u64)
+}
+
+/// The first of the month given as YYYY-MM. Months too close to the start
+/// of the calendar to draw are rejected: their grid begins on the Monday
+/// before the 1st and links back a month.
+fn parse_month(month: &str) -> Option<NaiveDate> {
+    let first = NaiveDate::parse_from_str(&format!("{month}-01"), "%Y-%m-%d").ok()?;
+    first.checked_sub_months(Months::new(1))?.checked_sub_days(Days::new(6))?;
+    Some(first)
+}
+
+fn build_calendar(
+    task: &TaskWithStreak,
+    month: NaiveDate,
+    today: NaiveDate,
+    values: &HashMap<NaiveDate, DayValue>,
+    frozen: &HashSet<NaiveDate>,
+    notes: Vec<(NaiveDate, String)>,
+) -> HistoryCalendar {
+    let next = month + Months::new(1);
+    let last = next - Days::new(1);
+    let end = monday_of(last) + Days::new(6);
+
+    let days: Vec<CalendarDay> = monday_of(month)
+        .iter_days()
+        .take_while(|date| *date <= end)
+        .map(|date| {
+            let logged = values.get(&date);
+            let completed = logged.is_some_and(DayValue::reached);
+            CalendarDay {
+                day: date.day(),
+                in_month: date.month() == month.month(),
+                is_today: date == today,
+                is_future: date > today,
+                completed,
+                partial: !completed && logged.is_some_and(|day| day.value > 0),
+                frozen: frozen.contains(&date),
+                due: task.schedule.is_due_on(date),
+                has_note: notes.iter().any(|(day, _)| *day == date),
+            }
+        })
+        .collect();
+
+    let completed_count = days.iter().filter(|d| d.in_month && d.completed).count();
+
+    HistoryCalendar {
+        task_id: task.id,
+        avoid: task.is_avoid(),
+        label: month.format("%B %Y").to_string(),
+        weeks: days.chunks(7).map(|week| week.to_vec()).collect(),
+        completed_count,
+        notes: notes
+            .into_iter()
+            .map(|(date, note)| HistoryNote { label: date.format("%a %b %-d").to_string(), note })
+            .collect(),
+        prev_month: (month - Months::new(1)).format("%Y-%m").to_string(),
+        next_month: (next <= today).then(|| next.format("%Y-%m").to_string()),
+    }
+}
+
+fn build_heatmap(
+    start: NaiveDate,
+    today: NaiveDate,
+    values: &HashMap<NaiveDate, DayValue>,
+) -> Vec<HeatmapWeek> {
+    (0..HEATMAP_WEEKS)
+        .map(|week| {
+            let monday = start + Days::new(week * 7);
+            let days: Vec<HeatmapDay> = monday
+                .iter_days()
+                .take(7)
+                .map(|date| {
+                    let level = match values.get(&date) {
+                        Some(day) if day.reached() => 4,
+                        // Partial progress on a target shades from 1 to 3
+                        Some(day) if day.value > 0 => (1 + day.value * 3 / day.target.max(1)).clamp(1, 3) as u8,
+                        _ => 0,
+                    };
+                    HeatmapDay { date, level, is_future: date > today }
+                })
+                .collect();
+            let month_label = days
+                .iter()
+                .find(|d| d.date.day() == 1)
+                .or_else(|| days.first().filter(|_| week == 0))
+                .map(|d| d.date.format("%b").to_string());
+            HeatmapWeek { month_label, days }
+        })
+        .collect()
+}
+
+async fn task_history(
+    State(state): State<AppState>,
+    user: AuthUser,
+    LocalDate(today): LocalDate,
+    Path(id): Path<i64>,
+    Query(query): Query<HistoryQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let task = match owned_by(TaskWithStreak::find_with_history(&state.db, id, today).await, user.id) {
+        Ok(task) => task,
+        Err(status) => return status.into_response(),
+    };
+
+    let this_month = first_of_month(today);
+    let month = query
+        .month
+        .as_deref()
+        .and_then(parse_month)
+        .filter(|month| *month <= this_month)
+        .unwrap_or(this_month);
+    let heatmap_start = monday_of(today) - Days::new((HEATMAP_WEEKS - 1) * 7);
+
+    let values = completion::values_between(&state.db, id, month.min(heatmap_start), today)
+        .await
+        .unwrap_or_default();
+    let frozen: HashSet<NaiveDate> = freeze::dates_by_task(&state.db, &[id])
+        .await
+        .ok()
+        .and_then(|mut dates| dates.remove(&id))
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+
+    let month_end = (month + Months::new(1)) - Days::new(1);
+    let notes = completion::notes_between(&state.db, id, month, month_end)
+        .await
+        .unwrap_or_default();
+
+    let calendar = build_calendar(&task, month, today, &values, &frozen, notes);
+
+    // Month navigation swaps just the calendar
+    if headers.contains_key("HX-Request") {
+        return HistoryCalendarPartial { calendar }.into_response();
+    }
+
+    let heatmap = build_heatmap(heatmap_start, today, &values);
+    let year_count = values
+        .iter()
+        .filter(|(date, day)| **date >= heatmap_start && day.reached())
+        .count();
+
+    TaskHistoryTemplate {
+        task,
+        calendar,
+        heatmap,
+        year_count,
+        flash_message: None,
+        flash_is_error: false,
+    }
+    .into_response()
+}
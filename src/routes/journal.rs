@@ -0,0 +1,84 @@
+use axum::{
+    Router,
+    extract::State,
+    response::{IntoResponse, Response},
+    routing::get,
+    Form,
+    http::StatusCode,
+};
+use serde::Deserialize;
+
+use crate::AppState;
+use crate::auth::{AuthUser, LocalDate};
+use crate::models::journal::{self, JournalEntry};
+use crate::templates::journal::{JournalFormPartial, JournalTemplate};
+
+use super::tasks::toast_headers;
+
+/// Past entries listed under today's.
+const RECENT_ENTRIES: i64 = 30;
+
+/// Longest journal entry kept, in characters.
+const MAX_BODY_CHARS: usize = 5000;
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/journal", get(journal_page).post(save_entry))
+}
+
+async fn journal_page(
+    State(state): State<AppState>,
+    user: AuthUser,
+    LocalDate(today): LocalDate,
+) -> JournalTemplate {
+    let entry = JournalEntry::find(&state.db, user.id, today).await.ok().flatten();
+    let entries = JournalEntry::recent(&state.db, user.id, today, RECENT_ENTRIES)
+        .await
+        .unwrap_or_default();
+
+    JournalTemplate {
+        mood_options: journal::mood_options(entry.as_ref().and_then(|e| e.mood)),
+        body: entry.map(|e| e.body).unwrap_or_default(),
+        entries,
+        flash_message: None,
+        flash_is_error: false,
+    }
+}
+
+#[derive(Deserialize)]
+struct JournalForm {
+    mood: Option<String>,
+    body: String,
+}
+
+impl JournalForm {
+    fn mood(&self) -> Option<i16> {
+        self.mood
+            .as_deref()
+            .and_then(|s| s.parse::<i16>().ok())
+            .filter(|mood| (1..=5).contains(mood))
+    }
+
+    fn body(&self) -> String {
+        self.body.trim().chars().take(MAX_BODY_CHARS).collect()
+    }
+}
+
+async fn save_entry(
+    State(state): State<AppState>,
+    user: AuthUser,
+    LocalDate(today): LocalDate,
+    Form(form): Form<JournalForm>,
+) -> Response {
+    let mood = form.mood();
+    let body = form.body();
+    if JournalEntry::save(&state.db, user.id, today, mood, &body).await.is_err() {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+
+    let headers = toast_headers("Journal saved", "success");
+    let partial = JournalFormPartial {
+        mood_options: journal::mood_options(mood),
+        body,
+    };
+    (headers, partial).into_response()
+}
@@ -1,8 +1,13 @@
 mod auth;
 mod dashboard;
 mod tasks;
+mod history;
 mod groups;
+mod challenges;
+mod group_habits;
+mod notifications;
 mod profile;
+mod journal;
 
 use axum::Router;
 use crate::AppState;
@@ -12,6 +17,11 @@ pub fn build_router() -> Router<AppState> {
         .merge(auth::router())
         .merge(dashboard::router())
         .merge(tasks::router())
+        .merge(history::router())
         .merge(groups::router())
+        .merge(challenges::router())
+        .merge(group_habits::router())
+        .merge(notifications::router())
         .merge(profile::router())
+        .merge(journal::router())
 }
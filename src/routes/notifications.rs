@@ -0,0 +1,72 @@
+use axum::{
+    Router,
+    extract::{Path, State},
+    response::{IntoResponse, Redirect, Response},
+    routing::{get, post},
+    http::StatusCode,
+};
+use tower_sessions::Session;
+
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::flash;
+use crate::models::notification;
+use crate::templates::notifications::{BellPartial, NotificationPartial, NotificationsTemplate};
+
+/// Tells the bell in the nav to reload its unread count.
+const CHANGED_TRIGGER: (&str, &str) = ("HX-Trigger", "notifications-changed");
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/notifications", get(notifications_page))
+        .route("/notifications/bell", get(bell))
+        .route("/notifications/read-all", post(mark_all_read))
+        .route("/notifications/{id}/read", post(mark_read))
+}
+
+async fn notifications_page(State(state): State<AppState>, user: AuthUser, session: Session) -> Response {
+    let notifications = match notification::recent(&state.db, user.id).await {
+        Ok(notifications) => notifications,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+    let unread = notification::unread_count(&state.db, user.id).await.unwrap_or(0);
+    let flash = flash::take(&session).await;
+
+    NotificationsTemplate {
+        notifications,
+        unread,
+        now: state.clock.now().naive_utc(),
+        flash_is_error: flash.as_ref().is_some_and(|(_, is_error)| *is_error),
+        flash_message: flash.map(|(message, _)| message),
+    }
+    .into_response()
+}
+
+async fn bell(State(state): State<AppState>, user: AuthUser) -> BellPartial {
+    BellPartial {
+        unread: notification::unread_count(&state.db, user.id).await.unwrap_or(0),
+    }
+}
+
+/// Marks one notification read, answering with it redrawn.
+async fn mark_read(State(state): State<AppState>, user: AuthUser, Path(id): Path<i64>) -> Response {
+    let now = state.clock.now().naive_utc();
+    match notification::mark_read(&state.db, user.id, id, now).await {
+        Ok(true) => {}
+        Ok(false) => return StatusCode::NOT_FOUND.into_response(),
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+    match notification::find(&state.db, user.id, id).await {
+        Ok(Some(notification)) => ([CHANGED_TRIGGER], NotificationPartial { notification, now }).into_response(),
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+async fn mark_all_read(State(state): State<AppState>, user: AuthUser) -> Response {
+    let now = state.clock.now().naive_utc();
+    match notification::mark_all_read(&state.db, user.id, now).await {
+        Ok(()) => Redirect::to("/notifications").into_response(),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
@@ -1,31 +1,147 @@
 use axum::{
     Router,
     extract::State,
-    routing::get,
+    routing::{get, post},
+    Form,
 };
+use serde::Deserialize;
 
 use crate::AppState;
-use crate::auth::AuthUser;
-use crate::models::user::User;
+use crate::auth::{AuthUser, parse_time_zone};
+use crate::models::milestone::{self, DEFAULT_THRESHOLDS};
+use crate::models::sharing::Visibility;
+use crate::models::user::{MAX_DAY_ENDS_HOUR, User};
 use crate::templates::profile::ProfileTemplate;
 
 pub fn router() -> Router<AppState> {
-    Router::new().route("/profile", get(profile))
+    Router::new()
+        .route("/profile", get(profile))
+        .route("/profile/time-zone", post(update_time_zone))
+        .route("/profile/day-ends", post(update_day_ends))
+        .route("/profile/task-visibility", post(update_task_visibility))
+        .route("/profile/milestones", post(update_milestones))
 }
 
-async fn profile(
-    State(state): State<AppState>,
-    user: AuthUser,
-) -> ProfileTemplate {
-    let db_user = User::find_by_id(&state.db, user.id).await.ok().flatten();
-    let (username, email) = db_user
-        .map(|u| (u.username, u.email))
+fn day_ends_label(hour: i16) -> String {
+    match hour {
+        0 => "Midnight".to_string(),
+        hour => format!("{hour}:00 AM"),
+    }
+}
+
+async fn render_profile(state: &AppState, user_id: i64, flash: Option<(&str, bool)>) -> ProfileTemplate {
+    let db_user = User::find_by_id(&state.db, user_id).await.ok().flatten();
+    let (username, email, time_zone, day_ends_hour, task_visibility, thresholds) = db_user
+        .map(|u| {
+            (u.username, u.email, u.time_zone, u.day_ends_hour, u.default_task_visibility, u.milestone_thresholds)
+        })
         .unwrap_or_default();
 
     ProfileTemplate {
         username,
         email,
-        flash_message: None,
-        flash_is_error: false,
+        time_zone,
+        time_zones: chrono_tz::TZ_VARIANTS.iter().map(|tz| tz.name()).collect(),
+        day_ends_options: (0..=MAX_DAY_ENDS_HOUR)
+            .map(|hour| (hour, day_ends_label(hour), hour == day_ends_hour))
+            .collect(),
+        task_visibility_options: Visibility::default_options(Visibility::from_column(&task_visibility)),
+        milestones: milestone::thresholds_label(&thresholds),
+        default_milestones: milestone::thresholds_label(&DEFAULT_THRESHOLDS),
+        flash_message: flash.map(|(message, _)| message.to_string()),
+        flash_is_error: flash.is_some_and(|(_, is_error)| is_error),
     }
 }
+
+async fn profile(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> ProfileTemplate {
+    render_profile(&state, user.id, None).await
+}
+
+#[derive(Deserialize)]
+struct TimeZoneForm {
+    time_zone: String,
+}
+
+async fn update_time_zone(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Form(form): Form<TimeZoneForm>,
+) -> ProfileTemplate {
+    let Some(tz) = parse_time_zone(&form.time_zone) else {
+        return render_profile(&state, user.id, Some(("Unknown time zone", true))).await;
+    };
+
+    let flash = match User::set_time_zone(&state.db, user.id, tz.name()).await {
+        Ok(()) => ("Time zone updated", false),
+        Err(_) => ("Could not update time zone", true),
+    };
+    render_profile(&state, user.id, Some(flash)).await
+}
+
+#[derive(Deserialize)]
+struct DayEndsForm {
+    day_ends_hour: i16,
+}
+
+async fn update_day_ends(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Form(form): Form<DayEndsForm>,
+) -> ProfileTemplate {
+    if !(0..=MAX_DAY_ENDS_HOUR).contains(&form.day_ends_hour) {
+        return render_profile(&state, user.id, Some(("Day must end by 6:00 AM", true))).await;
+    }
+
+    let flash = match User::set_day_ends_hour(&state.db, user.id, form.day_ends_hour).await {
+        Ok(()) => ("Day rollover updated", false),
+        Err(_) => ("Could not update day rollover", true),
+    };
+    render_profile(&state, user.id, Some(flash)).await
+}
+
+#[derive(Deserialize)]
+struct TaskVisibilityForm {
+    visibility: String,
+}
+
+async fn update_task_visibility(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Form(form): Form<TaskVisibilityForm>,
+) -> ProfileTemplate {
+    let visibility = Visibility::from_column(&form.visibility);
+    if visibility == Visibility::Selected {
+        return render_profile(&state, user.id, Some(("Pick groups on each task instead", true))).await;
+    }
+
+    let flash = match User::set_default_task_visibility(&state.db, user.id, visibility).await {
+        Ok(()) => ("Task visibility updated", false),
+        Err(_) => ("Could not update task visibility", true),
+    };
+    render_profile(&state, user.id, Some(flash)).await
+}
+
+#[derive(Deserialize)]
+struct MilestonesForm {
+    milestones: String,
+}
+
+async fn update_milestones(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Form(form): Form<MilestonesForm>,
+) -> ProfileTemplate {
+    let thresholds = match milestone::parse_thresholds(&form.milestones) {
+        Ok(thresholds) => thresholds,
+        Err(message) => return render_profile(&state, user.id, Some((message, true))).await,
+    };
+
+    let flash = match User::set_milestone_thresholds(&state.db, user.id, &thresholds).await {
+        Ok(()) => ("Milestones updated", false),
+        Err(_) => ("Could not update milestones", true),
+    };
+    render_profile(&state, user.id, Some(flash)).await
+}
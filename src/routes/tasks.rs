@@ -7,34 +7,84 @@ use axum::{
     Form,
     http::{StatusCode, HeaderMap, HeaderValue},
 };
-use chrono::NaiveDate;
+use std::collections::HashMap;
+
+use chrono::{Days, NaiveDate};
 use serde::Deserialize;
 use sqlx::PgPool;
 
 use crate::AppState;
 use crate::auth::{AuthUser, LocalDate};
-use crate::models::task::{Task, TaskWithStreak};
+use crate::models::task::{Schedule, Section, Task, TaskDetails, TaskKind, TaskWithStreak};
 use crate::models::completion;
-use crate::templates::dashboard::ProgressOobPartial;
-use crate::templates::tasks::{TaskCardPartial, TaskFormPartial, TaskEditPartial};
+use crate::models::event::{self, EventKind};
+use crate::models::freeze::{self, FREEZE_EARN_INTERVAL};
+use crate::models::group::Group;
+use crate::models::milestone;
+use crate::models::notification;
+use crate::models::sharing::{self, Visibility};
+use crate::models::user::User;
+use crate::templates::dashboard::{Progress, ProgressOobPartial};
+use crate::templates::tasks::{
+    ArchivedTasksTemplate, BackfillDay, TaskBackfillPartial, TaskCardPartial, TaskEditPartial, TaskFormPartial,
+    TaskListPartial, TaskNotePartial, TaskSection,
+};
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/tasks", post(create_task))
         .route("/tasks/form", get(task_form))
+        .route("/tasks/archived", get(archived_tasks))
+        .route("/tasks/reorder", post(reorder_tasks))
         .route("/tasks/{id}/toggle", post(toggle_task))
+        .route("/tasks/{id}/increment", post(increment_task))
+        .route("/tasks/{id}/decrement", post(decrement_task))
         .route("/tasks/{id}/edit", get(edit_form).post(update_task))
         .route("/tasks/{id}/card", get(task_card))
         .route("/tasks/{id}/archive", post(archive_task))
+        .route("/tasks/{id}/restore", post(restore_task))
+        .route("/tasks/{id}/delete", post(delete_task))
+        .route("/tasks/{id}/backfill", get(backfill_panel).post(backfill_toggle))
+        .route("/tasks/{id}/freeze", post(freeze_toggle))
+        .route("/tasks/{id}/note", get(note_form).post(save_note))
 }
 
-fn render_progress_oob(tasks: &[TaskWithStreak]) -> String {
-    let total_count = tasks.len() as i64;
-    let completed_count = tasks.iter().filter(|t| t.completed_today).count() as i64;
-    let active_streak_count = tasks.iter().filter(|t| t.current_streak > 0).count() as i64;
-    let longest_streak = tasks.iter().map(|t| t.current_streak).max().unwrap_or(0);
+pub(super) fn toast_headers(message: &str, kind: &str) -> HeaderMap {
+    let trigger_json = format!(
+        "{{\"toast\":{{\"message\":\"{}\",\"type\":\"{}\"}}}}",
+        message.replace('"', "\\\""),
+        kind
+    );
+    let mut headers = HeaderMap::new();
+    headers.insert("HX-Trigger", HeaderValue::from_str(&trigger_json).unwrap_or_else(|_| HeaderValue::from_static("{}")));
+    headers
+}
+
+/// Loads a task for its owner. Anyone else gets 403, a missing task 404.
+pub(super) async fn find_owned_task(
+    db: &PgPool,
+    id: i64,
+    user_id: i64,
+    today: NaiveDate,
+) -> Result<TaskWithStreak, StatusCode> {
+    owned_by(TaskWithStreak::find_by_id(db, id, today).await, user_id)
+}
 
-    ProgressOobPartial { completed_count, total_count, active_streak_count, longest_streak }
+/// Checks a looked-up task belongs to `user_id`: 403 for someone else's
+/// task, 404 when there is none.
+pub(super) fn owned_by(
+    found: sqlx::Result<Option<TaskWithStreak>>,
+    user_id: i64,
+) -> Result<TaskWithStreak, StatusCode> {
+    match found {
+        Ok(Some(task)) if task.user_id == user_id => Ok(task),
+        Ok(Some(_)) => Err(StatusCode::FORBIDDEN),
+        _ => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+fn render_progress_oob(tasks: &[TaskWithStreak]) -> String {
+    ProgressOobPartial { progress: Progress::from_tasks(tasks) }
         .render()
         .unwrap_or_default()
 }
@@ -44,34 +94,221 @@ async fn fetch_progress_oob(db: &PgPool, user_id: i64, today: NaiveDate) -> Stri
     render_progress_oob(&tasks)
 }
 
+/// Each multiple of FREEZE_EARN_INTERVAL reached by completing today earns a
+/// freeze token; undoing the completion that earned it takes it back.
+async fn settle_freeze_tokens(db: &PgPool, user_id: i64, before: &TaskWithStreak, after: &TaskWithStreak) {
+    if before.is_avoid() {
+        return;
+    }
+    let milestone = |streak: i64| streak > 0 && streak % FREEZE_EARN_INTERVAL == 0;
+    if before.completed_today && !after.completed_today && milestone(before.current_streak) {
+        let _ = freeze::revoke_token(db, user_id).await;
+    } else if !before.completed_today && after.completed_today && milestone(after.current_streak) {
+        let _ = freeze::award_token(db, user_id).await;
+    }
+}
+
+/// Logs what a change meant for the group timelines: today's completion
+/// flipping, or a running streak cut short by a slip, an unmarked past day or
+/// a removed freeze. Streaks lost to a missed day are logged by
+/// `streak_cache::roll_over` once the day is over.
+async fn record_events(state: &AppState, user_id: i64, before: &TaskWithStreak, after: &TaskWithStreak) {
+    let now = state.clock.now().naive_utc();
+    let completed = !before.completed_today && after.completed_today;
+    let undone = before.completed_today && !after.completed_today;
+
+    let mut events = Vec::new();
+    if !before.is_avoid() && completed {
+        events.push((EventKind::Completed, None));
+    } else if !before.is_avoid() && undone {
+        events.push((EventKind::Uncompleted, None));
+    } else if after.current_streak < before.current_streak {
+        events.push((EventKind::StreakBroken, Some(before.current_streak)));
+    }
+    for (kind, streak) in events {
+        let _ = event::record_task(&state.db, kind, user_id, before.id, streak, now).await;
+    }
+}
+
+/// Records each of the user's milestones the streak passed on its way up and
+/// lets their groups know, returning the highest to celebrate. When the
+/// streak drops, milestones reached today that it no longer covers are taken
+/// back, along with their timeline events and unread notifications.
+async fn settle_milestones(
+    state: &AppState,
+    user_id: i64,
+    before: &TaskWithStreak,
+    after: &TaskWithStreak,
+    today: NaiveDate,
+) -> Option<i32> {
+    if before.is_avoid() {
+        return None;
+    }
+    if after.current_streak < before.current_streak {
+        let revoked = milestone::revoke_above(&state.db, before.id, after.current_streak, today)
+            .await
+            .unwrap_or_default();
+        if !revoked.is_empty() {
+            let _ = event::withdraw_milestones(&state.db, before.id, &revoked).await;
+            let _ = notification::withdraw_milestones(&state.db, before.id, &revoked).await;
+        }
+        return None;
+    }
+
+    let thresholds = User::milestone_thresholds(&state.db, user_id).await.ok()?;
+    let now = state.clock.now().naive_utc();
+    let mut reached = None;
+    for threshold in milestone::crossed(&thresholds, before.current_streak, after.current_streak) {
+        if let Ok(true) = milestone::record(&state.db, before.id, threshold, today).await {
+            let streak = Some(i64::from(threshold));
+            let _ = event::record_task(&state.db, EventKind::Milestone, user_id, before.id, streak, now).await;
+            let _ = notification::notify_milestone(&state.db, user_id, before.id, threshold, now).await;
+            reached = Some(threshold);
+        }
+    }
+    reached
+}
+
+fn milestone_message(task: &TaskWithStreak, threshold: i32) -> String {
+    format!("'{}' hit a {threshold}-{} streak!", task.name, task.streak_unit())
+}
+
+/// The whole task list, for changes that can move a card between sections,
+/// with the progress stats swapped out of band.
+async fn render_task_list(db: &PgPool, user_id: i64, today: NaiveDate) -> String {
+    let tasks = TaskWithStreak::for_user(db, user_id, today).await.unwrap_or_default();
+    let progress = render_progress_oob(&tasks);
+    let list = TaskListPartial { sections: TaskSection::group(tasks) }
+        .render()
+        .unwrap_or_default();
+    format!("{list}{progress}")
+}
+
 async fn task_form(_user: AuthUser) -> TaskFormPartial {
-    TaskFormPartial
+    TaskFormPartial {
+        schedule: Schedule::Daily,
+        section_options: Section::options(None),
+    }
 }
 
+/// Schedule fields shared by the create and edit forms. Weekday checkboxes
+/// are only present in the submission when checked.
 #[derive(Deserialize)]
-struct CreateTaskForm {
+struct ScheduleForm {
+    schedule: Option<String>,
+    weekly_target: Option<String>,
+    mon: Option<String>,
+    tue: Option<String>,
+    wed: Option<String>,
+    thu: Option<String>,
+    fri: Option<String>,
+    sat: Option<String>,
+    sun: Option<String>,
+}
+
+impl ScheduleForm {
+    fn to_schedule(&self) -> Schedule {
+        let days = [&self.mon, &self.tue, &self.wed, &self.thu, &self.fri, &self.sat, &self.sun];
+        let mask = days
+            .iter()
+            .enumerate()
+            .filter(|(_, checked)| checked.is_some())
+            .fold(0u8, |mask, (i, _)| mask | (1 << i));
+
+        match self.schedule.as_deref() {
+            Some("weekdays") if mask != 0 => Schedule::Weekdays(mask),
+            Some("weekly") => {
+                let target = self
+                    .weekly_target
+                    .as_deref()
+                    .and_then(|s| s.trim().parse::<u8>().ok())
+                    .unwrap_or(1);
+                Schedule::Weekly(target.clamp(1, 7))
+            }
+            _ => Schedule::Daily,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TaskForm {
+    kind: Option<String>,
     name: String,
     description: Option<String>,
+    section: Option<String>,
+    target_value: Option<String>,
+    unit: Option<String>,
+    /// Only sent by the edit form; new tasks take the owner's default.
+    visibility: Option<String>,
+    #[serde(flatten)]
+    schedule: ScheduleForm,
+    /// Everything else, which includes a `share_{group_id}` box for each
+    /// group the task is picked for.
+    #[serde(flatten)]
+    extra: HashMap<String, String>,
+}
+
+impl TaskForm {
+    fn visibility(&self) -> Option<Visibility> {
+        self.visibility.as_deref().map(Visibility::from_column)
+    }
+
+    fn shared_group_ids(&self) -> Vec<i64> {
+        self.extra
+            .keys()
+            .filter_map(|key| key.strip_prefix("share_")?.parse().ok())
+            .collect()
+    }
+
+    fn kind(&self) -> TaskKind {
+        TaskKind::from_column(self.kind.as_deref().unwrap_or_default())
+    }
+
+    fn section(&self) -> Option<Section> {
+        self.section.as_deref().and_then(Section::from_column)
+    }
+
+    /// Avoid tasks are always daily and have no target, whatever was submitted.
+    fn details(&self) -> TaskDetails<'_> {
+        if self.kind() == TaskKind::Avoid {
+            return TaskDetails {
+                name: &self.name,
+                description: self.description.as_deref().filter(|s| !s.is_empty()),
+                section: self.section(),
+                schedule: Schedule::Daily,
+                target_value: None,
+                unit: None,
+            };
+        }
+
+        let target_value = self
+            .target_value
+            .as_deref()
+            .and_then(|s| s.trim().parse::<i32>().ok())
+            .filter(|v| *v > 0);
+        TaskDetails {
+            name: &self.name,
+            description: self.description.as_deref().filter(|s| !s.is_empty()),
+            section: self.section(),
+            schedule: self.schedule.to_schedule(),
+            target_value,
+            unit: self
+                .unit
+                .as_deref()
+                .map(str::trim)
+                .filter(|s| !s.is_empty() && target_value.is_some()),
+        }
+    }
 }
 
 async fn create_task(
     State(state): State<AppState>,
     user: AuthUser,
     LocalDate(today): LocalDate,
-    Form(form): Form<CreateTaskForm>,
+    Form(form): Form<TaskForm>,
 ) -> Response {
-    let desc = form.description.as_deref().filter(|s| !s.is_empty());
-    match Task::create(&state.db, user.id, &form.name, desc).await {
-        Ok(task_id) => {
-            match TaskWithStreak::find_by_id(&state.db, task_id, today).await {
-                Ok(Some(task)) => {
-                    let card = TaskCardPartial { task }.render().unwrap_or_default();
-                    let progress = fetch_progress_oob(&state.db, user.id, today).await;
-                    axum::response::Html(format!("{card}{progress}")).into_response()
-                }
-                _ => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
-            }
-        }
+    match Task::create(&state.db, user.id, form.kind(), &form.details()).await {
+        Ok(_) => axum::response::Html(render_task_list(&state.db, user.id, today).await).into_response(),
         Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
     }
 }
@@ -82,12 +319,15 @@ async fn toggle_task(
     LocalDate(today): LocalDate,
     Path(id): Path<i64>,
 ) -> Response {
-    let current = match TaskWithStreak::find_by_id(&state.db, id, today).await {
-        Ok(Some(task)) if task.user_id == user.id => task,
-        Ok(Some(_)) => return StatusCode::FORBIDDEN.into_response(),
-        _ => return StatusCode::NOT_FOUND.into_response(),
+    let current = match find_owned_task(&state.db, id, user.id, today).await {
+        Ok(task) => task,
+        Err(status) => return status.into_response(),
     };
 
+    if current.is_avoid() {
+        return toggle_slip(state, user, today, current).await;
+    }
+
     let was_completed = current.completed_today;
 
     if was_completed {
@@ -98,22 +338,21 @@ async fn toggle_task(
 
     match TaskWithStreak::find_by_id(&state.db, id, today).await {
         Ok(Some(task)) => {
-            let message = if was_completed {
-                format!("Task '{}' uncompleted", task.name)
+            settle_freeze_tokens(&state.db, user.id, &current, &task).await;
+            record_events(&state, user.id, &current, &task).await;
+            let reached = settle_milestones(&state, user.id, &current, &task, today).await;
+
+            let headers = if let Some(threshold) = reached {
+                toast_headers(&milestone_message(&task, threshold), "milestone")
+            } else if was_completed {
+                toast_headers(&format!("Task '{}' uncompleted", task.name), "info")
             } else {
-                format!("Task '{}' completed", task.name)
+                toast_headers(&format!("Task '{}' completed", task.name), "success")
             };
 
             let card = TaskCardPartial { task }.render().unwrap_or_default();
             let progress = fetch_progress_oob(&state.db, user.id, today).await;
             let body = format!("{card}{progress}");
-            let trigger_json = format!(
-                "{{\"toast\":{{\"message\":\"{}\",\"type\":\"{}\"}}}}",
-                message.replace('"', "\\\""),
-                if was_completed { "info" } else { "success" }
-            );
-            let mut headers = HeaderMap::new();
-            headers.insert("HX-Trigger", HeaderValue::from_str(&trigger_json).unwrap_or_else(|_| HeaderValue::from_static("{}")));
 
             (headers, axum::response::Html(body)).into_response()
         }
@@ -121,18 +360,132 @@ async fn toggle_task(
     }
 }
 
-async fn edit_form(
+/// Logs or removes today's slip on an avoid task. A clean day has no
+/// completion row, so logging a slip inserts one.
+async fn toggle_slip(state: AppState, user: AuthUser, today: NaiveDate, current: TaskWithStreak) -> Response {
+    let was_clean = current.completed_today;
+
+    if was_clean {
+        let _ = completion::complete_today(&state.db, current.id, today).await;
+    } else {
+        let _ = completion::uncomplete_today(&state.db, current.id, today).await;
+    }
+
+    match TaskWithStreak::find_by_id(&state.db, current.id, today).await {
+        Ok(Some(task)) => {
+            record_events(&state, user.id, &current, &task).await;
+            let message = if was_clean {
+                format!("Slip logged for '{}'", task.name)
+            } else {
+                format!("Slip removed from '{}'", task.name)
+            };
+
+            let card = TaskCardPartial { task }.render().unwrap_or_default();
+            let progress = fetch_progress_oob(&state.db, user.id, today).await;
+            let headers = toast_headers(&message, "info");
+
+            (headers, axum::response::Html(format!("{card}{progress}"))).into_response()
+        }
+        _ => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+struct AmountForm {
+    amount: Option<String>,
+}
+
+impl AmountForm {
+    fn amount(&self) -> i32 {
+        self.amount
+            .as_deref()
+            .and_then(|s| s.trim().parse::<i32>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(1)
+    }
+}
+
+async fn increment_task(
+    State(state): State<AppState>,
+    user: AuthUser,
+    LocalDate(today): LocalDate,
+    Path(id): Path<i64>,
+    Form(form): Form<AmountForm>,
+) -> Response {
+    adjust_task_value(state, user, today, id, form.amount()).await
+}
+
+async fn decrement_task(
     State(state): State<AppState>,
     user: AuthUser,
     LocalDate(today): LocalDate,
     Path(id): Path<i64>,
+    Form(form): Form<AmountForm>,
 ) -> Response {
+    adjust_task_value(state, user, today, id, -form.amount()).await
+}
+
+async fn adjust_task_value(state: AppState, user: AuthUser, today: NaiveDate, id: i64, delta: i32) -> Response {
+    let current = match find_owned_task(&state.db, id, user.id, today).await {
+        Ok(task) => task,
+        Err(status) => return status.into_response(),
+    };
+    if current.target_value.is_none() {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+
+    if completion::add_value(&state.db, id, today, delta).await.is_err() {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+
     match TaskWithStreak::find_by_id(&state.db, id, today).await {
-        Ok(Some(task)) if task.user_id == user.id => {
-            TaskEditPartial { task }.into_response()
+        Ok(Some(task)) => {
+            settle_freeze_tokens(&state.db, user.id, &current, &task).await;
+            record_events(&state, user.id, &current, &task).await;
+            let reached = settle_milestones(&state, user.id, &current, &task, today).await;
+
+            let mut headers = HeaderMap::new();
+            if let Some(threshold) = reached {
+                headers = toast_headers(&milestone_message(&task, threshold), "milestone");
+            } else if task.completed_today && !current.completed_today {
+                headers = toast_headers(&format!("Task '{}' completed", task.name), "success");
+            } else if current.completed_today && !task.completed_today {
+                headers = toast_headers(&format!("Task '{}' is below its target", task.name), "info");
+            }
+
+            let card = TaskCardPartial { task }.render().unwrap_or_default();
+            let progress = fetch_progress_oob(&state.db, user.id, today).await;
+            (headers, axum::response::Html(format!("{card}{progress}"))).into_response()
         }
-        _ => StatusCode::NOT_FOUND.into_response(),
+        _ => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+async fn edit_form(
+    State(state): State<AppState>,
+    user: AuthUser,
+    LocalDate(today): LocalDate,
+    Path(id): Path<i64>,
+) -> Response {
+    let task = match TaskWithStreak::find_by_id(&state.db, id, today).await {
+        Ok(Some(task)) if task.user_id == user.id => task,
+        _ => return StatusCode::NOT_FOUND.into_response(),
+    };
+    let (visibility, shared) = sharing::for_task(&state.db, id)
+        .await
+        .unwrap_or((Visibility::AllGroups, Vec::new()));
+    let groups = Group::user_groups(&state.db, user.id).await.unwrap_or_default();
+
+    TaskEditPartial {
+        task,
+        visibility,
+        visibility_options: Visibility::options(visibility),
+        group_options: groups
+            .into_iter()
+            .map(|group| (group.id, group.name, shared.contains(&group.id)))
+            .collect(),
     }
+    .into_response()
 }
 
 async fn task_card(
@@ -149,26 +502,33 @@ async fn task_card(
     }
 }
 
-#[derive(Deserialize)]
-struct UpdateTaskForm {
-    name: String,
-    description: Option<String>,
-}
-
 async fn update_task(
     State(state): State<AppState>,
     user: AuthUser,
     LocalDate(today): LocalDate,
     Path(id): Path<i64>,
-    Form(form): Form<UpdateTaskForm>,
+    Form(form): Form<TaskForm>,
 ) -> Response {
-    let desc = form.description.as_deref().filter(|s| !s.is_empty());
-    let _ = Task::update(&state.db, id, user.id, &form.name, desc).await;
+    let before = match find_owned_task(&state.db, id, user.id, today).await {
+        Ok(task) => task,
+        Err(status) => return status.into_response(),
+    };
+    let _ = Task::update(&state.db, id, user.id, &form.details(), today).await;
+    if let Some(visibility) = form.visibility() {
+        let _ = sharing::set(&state.db, id, user.id, visibility, &form.shared_group_ids()).await;
+    }
+
+    // A card moving to another section needs the whole list re-rendered
+    if form.section() != before.section {
+        let mut headers = HeaderMap::new();
+        headers.insert("HX-Retarget", HeaderValue::from_static("#task-list"));
+        headers.insert("HX-Reswap", HeaderValue::from_static("outerHTML"));
+        let list = render_task_list(&state.db, user.id, today).await;
+        return (headers, axum::response::Html(list)).into_response();
+    }
 
     match TaskWithStreak::find_by_id(&state.db, id, today).await {
-        Ok(Some(task)) if task.user_id == user.id => {
-            TaskCardPartial { task }.into_response()
-        }
+        Ok(Some(task)) => TaskCardPartial { task }.into_response(),
         _ => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
     }
 }
@@ -183,3 +543,335 @@ async fn archive_task(
     let progress = fetch_progress_oob(&state.db, user.id, today).await;
     axum::response::Html(progress).into_response()
 }
+
+#[derive(Deserialize)]
+struct ReorderForm {
+    /// Comma-separated `id:section` pairs in display order; the section is
+    /// empty for tasks outside any section.
+    order: String,
+}
+
+impl ReorderForm {
+    fn entries(&self) -> Option<Vec<(i64, Option<Section>)>> {
+        self.order
+            .split(',')
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (id, section) = entry.split_once(':').unwrap_or((entry, ""));
+                Some((id.trim().parse().ok()?, Section::from_column(section.trim())))
+            })
+            .collect()
+    }
+}
+
+async fn reorder_tasks(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Form(form): Form<ReorderForm>,
+) -> Response {
+    let Some(order) = form.entries() else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    match Task::reorder(&state.db, user.id, &order).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+async fn archived_tasks(
+    State(state): State<AppState>,
+    user: AuthUser,
+    LocalDate(today): LocalDate,
+) -> ArchivedTasksTemplate {
+    let tasks = TaskWithStreak::archived_for_user(&state.db, user.id, today)
+        .await
+        .unwrap_or_default();
+
+    ArchivedTasksTemplate {
+        tasks,
+        flash_message: None,
+        flash_is_error: false,
+    }
+}
+
+async fn restore_task(
+    State(state): State<AppState>,
+    user: AuthUser,
+    LocalDate(today): LocalDate,
+    Path(id): Path<i64>,
+) -> Response {
+    let task = match find_owned_task(&state.db, id, user.id, today).await {
+        Ok(task) => task,
+        Err(status) => return status.into_response(),
+    };
+
+    if Task::restore(&state.db, id, user.id).await.is_err() {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+
+    let headers = toast_headers(&format!("Task '{}' restored", task.name), "success");
+    (headers, axum::response::Html(String::new())).into_response()
+}
+
+async fn delete_task(
+    State(state): State<AppState>,
+    user: AuthUser,
+    LocalDate(today): LocalDate,
+    Path(id): Path<i64>,
+) -> Response {
+    let task = match find_owned_task(&state.db, id, user.id, today).await {
+        Ok(task) => task,
+        Err(status) => return status.into_response(),
+    };
+
+    match Task::delete(&state.db, id, user.id).await {
+        Ok(true) => {
+            let headers = toast_headers(&format!("Task '{}' deleted", task.name), "info");
+            (headers, axum::response::Html(String::new())).into_response()
+        }
+        // Only archived tasks can be deleted
+        Ok(false) => StatusCode::BAD_REQUEST.into_response(),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+/// Longest note kept on a completion, in characters.
+const MAX_NOTE_CHARS: usize = 500;
+
+async fn note_form(
+    State(state): State<AppState>,
+    user: AuthUser,
+    LocalDate(today): LocalDate,
+    Path(id): Path<i64>,
+) -> Response {
+    match find_owned_task(&state.db, id, user.id, today).await {
+        Ok(task) if task.logged_today || task.today_note.is_some() => TaskNotePartial { task }.into_response(),
+        Ok(_) => StatusCode::BAD_REQUEST.into_response(),
+        Err(status) => status.into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+struct NoteForm {
+    note: String,
+}
+
+impl NoteForm {
+    fn note(&self) -> Option<String> {
+        let note = self.note.trim();
+        (!note.is_empty()).then(|| note.chars().take(MAX_NOTE_CHARS).collect())
+    }
+}
+
+async fn save_note(
+    State(state): State<AppState>,
+    user: AuthUser,
+    LocalDate(today): LocalDate,
+    Path(id): Path<i64>,
+    Form(form): Form<NoteForm>,
+) -> Response {
+    if let Err(status) = find_owned_task(&state.db, id, user.id, today).await {
+        return status.into_response();
+    }
+
+    match completion::set_note(&state.db, id, today, form.note().as_deref()).await {
+        Ok(true) => {}
+        // Nothing logged today to attach the note to
+        Ok(false) => return StatusCode::BAD_REQUEST.into_response(),
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+
+    match TaskWithStreak::find_by_id(&state.db, id, today).await {
+        Ok(Some(task)) => {
+            let headers = toast_headers("Note saved", "success");
+            (headers, TaskCardPartial { task }).into_response()
+        }
+        _ => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+async fn build_backfill_partial(state: &AppState, task: TaskWithStreak, today: NaiveDate) -> TaskBackfillPartial {
+    let days = if state.backfill_days > 0 {
+        let earliest = today - Days::new(state.backfill_days as u64);
+        let yesterday = today - Days::new(1);
+        let completed = completion::dates_between(&state.db, task.id, earliest, yesterday)
+            .await
+            .unwrap_or_default();
+        let frozen = freeze::dates_by_task(&state.db, &[task.id])
+            .await
+            .ok()
+            .and_then(|mut dates| dates.remove(&task.id))
+            .unwrap_or_default();
+        earliest
+            .iter_days()
+            .take_while(|date| *date < today)
+            .map(|date| BackfillDay {
+                date,
+                label: if date == yesterday {
+                    "Yesterday".to_string()
+                } else {
+                    date.format("%a %-d").to_string()
+                },
+                completed: completed.contains(&date),
+                frozen: frozen.contains(&date),
+                due: task.schedule.is_due_on(date),
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let freeze_tokens = freeze::tokens(&state.db, task.user_id).await.unwrap_or_default();
+
+    TaskBackfillPartial { task, days, freeze_tokens }
+}
+
+/// Whether `date` may be changed from the backfill panel: before today and no
+/// further back than the configured window.
+fn in_backfill_window(state: &AppState, date: NaiveDate, today: NaiveDate) -> bool {
+    let earliest = today - Days::new(state.backfill_days.max(0) as u64);
+    date < today && date >= earliest
+}
+
+async fn backfill_panel(
+    State(state): State<AppState>,
+    user: AuthUser,
+    LocalDate(today): LocalDate,
+    Path(id): Path<i64>,
+) -> Response {
+    match TaskWithStreak::find_by_id(&state.db, id, today).await {
+        Ok(Some(task)) if task.user_id == user.id => {
+            build_backfill_partial(&state, task, today).await.into_response()
+        }
+        _ => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+struct BackfillForm {
+    date: NaiveDate,
+}
+
+async fn backfill_toggle(
+    State(state): State<AppState>,
+    user: AuthUser,
+    LocalDate(today): LocalDate,
+    Path(id): Path<i64>,
+    Form(form): Form<BackfillForm>,
+) -> Response {
+    let current = match find_owned_task(&state.db, id, user.id, today).await {
+        Ok(task) => task,
+        Err(status) => return status.into_response(),
+    };
+    let avoid = current.is_avoid();
+
+    if !in_backfill_window(&state, form.date, today) {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+
+    let was_completed = completion::dates_between(&state.db, id, form.date, form.date)
+        .await
+        .map(|dates| !dates.is_empty())
+        .unwrap_or(false);
+
+    if was_completed {
+        let _ = completion::uncomplete_on(&state.db, id, form.date, today).await;
+    } else {
+        let _ = completion::complete_on(&state.db, id, form.date, today).await;
+        // A completed day no longer needs its freeze, so hand the token back
+        let _ = freeze::unfreeze(&state.db, user.id, id, form.date, today).await;
+    }
+
+    match TaskWithStreak::find_by_id(&state.db, id, today).await {
+        Ok(Some(task)) => {
+            record_events(&state, user.id, &current, &task).await;
+            let reached = settle_milestones(&state, user.id, &current, &task, today).await;
+            let day = form.date.format("%a %b %-d");
+            let message = if let Some(threshold) = reached {
+                milestone_message(&task, threshold)
+            } else if avoid && was_completed {
+                format!("Slip removed from {day}")
+            } else if avoid {
+                format!("Slip logged for {day}")
+            } else if was_completed {
+                format!("'{}' unmarked for {day}", task.name)
+            } else {
+                format!("'{}' marked done for {day}", task.name)
+            };
+
+            let panel = build_backfill_partial(&state, task, today).await.render().unwrap_or_default();
+            let progress = fetch_progress_oob(&state.db, user.id, today).await;
+            let kind = if reached.is_some() {
+                "milestone"
+            } else if avoid || was_completed {
+                "info"
+            } else {
+                "success"
+            };
+            let headers = toast_headers(&message, kind);
+
+            (headers, axum::response::Html(format!("{panel}{progress}"))).into_response()
+        }
+        _ => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+async fn freeze_toggle(
+    State(state): State<AppState>,
+    user: AuthUser,
+    LocalDate(today): LocalDate,
+    Path(id): Path<i64>,
+    Form(form): Form<BackfillForm>,
+) -> Response {
+    let current = match find_owned_task(&state.db, id, user.id, today).await {
+        Ok(task) => task,
+        Err(status) => return status.into_response(),
+    };
+
+    if current.is_avoid()
+        || !in_backfill_window(&state, form.date, today)
+        || !current.schedule.is_due_on(form.date)
+    {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+
+    let completed = completion::dates_between(&state.db, id, form.date, form.date)
+        .await
+        .map(|dates| !dates.is_empty())
+        .unwrap_or(false);
+    if completed {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+
+    let was_frozen = freeze::dates_by_task(&state.db, &[id])
+        .await
+        .map(|dates| dates.get(&id).is_some_and(|d| d.contains(&form.date)))
+        .unwrap_or(false);
+
+    let day = form.date.format("%a %b %-d");
+    let (message, kind) = if was_frozen {
+        let _ = freeze::unfreeze(&state.db, user.id, id, form.date, today).await;
+        (format!("Freeze removed from {day}"), "info")
+    } else {
+        match freeze::freeze(&state.db, user.id, id, form.date, today).await {
+            Ok(true) => (format!("'{}' frozen for {day}", current.name), "success"),
+            Ok(false) => ("No streak freezes left".to_string(), "error"),
+            Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        }
+    };
+
+    match TaskWithStreak::find_by_id(&state.db, id, today).await {
+        Ok(Some(task)) => {
+            record_events(&state, user.id, &current, &task).await;
+            let headers = match settle_milestones(&state, user.id, &current, &task, today).await {
+                Some(threshold) => toast_headers(&milestone_message(&task, threshold), "milestone"),
+                None => toast_headers(&message, kind),
+            };
+            let panel = build_backfill_partial(&state, task, today).await.render().unwrap_or_default();
+            let progress = fetch_progress_oob(&state.db, user.id, today).await;
+
+            (headers, axum::response::Html(format!("{panel}{progress}"))).into_response()
+        }
+        _ => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
@@ -0,0 +1,20 @@
+use askama::Template;
+use askama_web::WebTemplate;
+use chrono::NaiveDate;
+use crate::models::challenge::{Challenge, FinalSummary, Standing};
+
+#[derive(Template, WebTemplate)]
+#[template(path = "challenges/show.html")]
+pub struct ChallengeTemplate {
+    pub challenge: Challenge,
+    /// Participants, best first.
+    pub standings: Vec<Standing>,
+    /// Only once the challenge has ended.
+    pub summary: Option<FinalSummary>,
+    pub today: NaiveDate,
+    /// Whether the viewer has opted in.
+    pub joined: bool,
+    pub user_id: i64,
+    pub flash_message: Option<String>,
+    pub flash_is_error: bool,
+}
@@ -1,27 +1,56 @@
 use askama::Template;
 use askama_web::WebTemplate;
 use crate::models::task::TaskWithStreak;
+use crate::templates::tasks::TaskSection;
 use crate::models::group::GroupWithMembership;
 
+/// Today's progress and streak stats shown above the task list.
+pub struct Progress {
+    pub completed_count: i64,
+    pub total_count: i64,
+    /// Share of today's due work done, counting partial progress on
+    /// quantitative tasks.
+    pub percent: i64,
+    pub active_streak_count: i64,
+    /// Best streak ever reached by any task, not just the running ones.
+    pub longest_streak: i64,
+}
+
+impl Progress {
+    pub fn from_tasks(tasks: &[TaskWithStreak]) -> Self {
+        let due: Vec<&TaskWithStreak> = tasks.iter().filter(|t| t.due_today).collect();
+        let total_count = due.len() as i64;
+        let completed_count = due.iter().filter(|t| t.completed_today).count() as i64;
+        let done: f64 = due.iter().map(|t| t.today_fraction()).sum();
+        let percent = if total_count > 0 {
+            (done * 100.0 / total_count as f64).round() as i64
+        } else {
+            0
+        };
+
+        Self {
+            completed_count,
+            total_count,
+            percent,
+            active_streak_count: tasks.iter().filter(|t| t.current_streak > 0).count() as i64,
+            longest_streak: tasks.iter().map(|t| t.best_streak).max().unwrap_or(0),
+        }
+    }
+}
+
 #[derive(Template, WebTemplate)]
 #[template(path = "dashboard.html")]
 pub struct DashboardTemplate {
     pub username: String,
-    pub tasks: Vec<TaskWithStreak>,
+    pub sections: Vec<TaskSection>,
     pub groups: Vec<GroupWithMembership>,
     pub flash_message: Option<String>,
     pub flash_is_error: bool,
-    pub completed_count: i64,
-    pub total_count: i64,
-    pub active_streak_count: i64,
-    pub longest_streak: i64,
+    pub progress: Progress,
 }
 
 #[derive(Template)]
 #[template(path = "dashboard/_progress_oob.html")]
 pub struct ProgressOobPartial {
-    pub completed_count: i64,
-    pub total_count: i64,
-    pub active_streak_count: i64,
-    pub longest_streak: i64,
+    pub progress: Progress,
 }
@@ -1,12 +1,89 @@
 use askama::Template;
 use askama_web::WebTemplate;
-use crate::models::group::{Group, MemberWithStreaks};
+use chrono::{NaiveDate, NaiveDateTime};
+use crate::models::challenge::Challenge;
+use crate::models::event::TimelineEvent;
+use crate::models::group::{Group, GroupMember, GroupRole, MemberWithStreaks};
+use crate::models::group_habit::HabitStatus;
+use crate::models::invite::Invite;
+use crate::models::reaction::ReactionButton;
 
 #[derive(Template, WebTemplate)]
 #[template(path = "groups/feed.html")]
 pub struct GroupFeedTemplate {
     pub group: Group,
+    /// The viewer's role in the group.
+    pub role: GroupRole,
+    pub members: Vec<GroupMember>,
+    /// Extra invites, only loaded for those who manage the group.
+    pub invites: Vec<Invite>,
+    /// When the page was rendered, to tell which invites have expired.
+    pub now: NaiveDateTime,
+    /// The viewer, so they aren't offered controls over themselves.
+    pub user_id: i64,
     pub members_grouped: Vec<(String, Vec<MemberWithStreaks>)>,
+    pub challenges: Vec<Challenge>,
+    /// Habits the group keeps together, with their team streaks.
+    pub habits: Vec<HabitStatus>,
+    /// The viewer's date, to tell where each challenge stands.
+    pub today: NaiveDate,
+    /// The first page of the activity timeline.
+    pub events: Vec<TimelineEvent>,
+    /// Where the next page of the timeline starts, if there is one.
+    pub next_before: Option<i64>,
+    pub flash_message: Option<String>,
+    pub flash_is_error: bool,
+}
+
+/// Asks before a leave, removal, transfer or delete; posts to `action` once
+/// confirmed.
+#[derive(Template, WebTemplate)]
+#[template(path = "groups/_confirm.html")]
+pub struct ConfirmPartial {
+    pub message: String,
+    pub action: String,
+    pub confirm_label: &'static str,
+}
+
+/// A member task's reaction bar on the group feed.
+#[derive(Template, WebTemplate)]
+#[template(path = "groups/_reactions.html")]
+pub struct ReactionsPartial {
+    pub group_id: i64,
+    pub task_id: i64,
+    pub buttons: Vec<ReactionButton>,
+}
+
+/// One team habit on the group feed, swapped after a check-in.
+#[derive(Template, WebTemplate)]
+#[template(path = "groups/_habit.html")]
+pub struct GroupHabitPartial {
+    pub habit: HabitStatus,
+    pub user_id: i64,
+}
+
+/// A further page of a group's activity timeline, with its own "load more".
+#[derive(Template, WebTemplate)]
+#[template(path = "groups/_activity.html")]
+pub struct ActivityPartial {
+    pub group_id: i64,
+    pub events: Vec<TimelineEvent>,
+    pub next_before: Option<i64>,
+    pub now: NaiveDateTime,
+}
+
+/// Landing page for a `/join/{code}` link.
+#[derive(Template, WebTemplate)]
+#[template(path = "groups/join.html")]
+pub struct JoinTemplate {
+    pub code: String,
+    pub group_name: String,
+    pub member_count: i64,
+    /// Why the code can't be used, in place of the group.
+    pub error: Option<&'static str>,
+    pub logged_in: bool,
+    /// Set when the visitor already belongs to the group.
+    pub member_group_id: Option<i64>,
     pub flash_message: Option<String>,
     pub flash_is_error: bool,
 }
@@ -0,0 +1,20 @@
+use askama::Template;
+use askama_web::WebTemplate;
+use crate::models::journal::JournalEntry;
+
+#[derive(Template, WebTemplate)]
+#[template(path = "journal/_entry_form.html")]
+pub struct JournalFormPartial {
+    pub mood_options: Vec<(i16, &'static str, &'static str, bool)>,
+    pub body: String,
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "journal/index.html")]
+pub struct JournalTemplate {
+    pub mood_options: Vec<(i16, &'static str, &'static str, bool)>,
+    pub body: String,
+    pub entries: Vec<JournalEntry>,
+    pub flash_message: Option<String>,
+    pub flash_is_error: bool,
+}
@@ -2,4 +2,7 @@ pub mod auth;
 pub mod dashboard;
 pub mod tasks;
 pub mod groups;
+pub mod challenges;
+pub mod notifications;
 pub mod profile;
+pub mod journal;
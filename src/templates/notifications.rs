@@ -0,0 +1,28 @@
+use askama::Template;
+use askama_web::WebTemplate;
+use chrono::NaiveDateTime;
+use crate::models::notification::Notification;
+
+#[derive(Template, WebTemplate)]
+#[template(path = "notifications/_bell.html")]
+pub struct BellPartial {
+    pub unread: i64,
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "notifications/_notification.html")]
+pub struct NotificationPartial {
+    pub notification: Notification,
+    pub now: NaiveDateTime,
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "notifications/index.html")]
+pub struct NotificationsTemplate {
+    pub notifications: Vec<Notification>,
+    pub unread: i64,
+    /// When the page was rendered, for each notification's age.
+    pub now: NaiveDateTime,
+    pub flash_message: Option<String>,
+    pub flash_is_error: bool,
+}
@@ -6,6 +6,17 @@ use askama_web::WebTemplate;
 pub struct ProfileTemplate {
     pub username: String,
     pub email: String,
+    pub time_zone: String,
+    /// Every IANA zone name, for the zone picker.
+    pub time_zones: Vec<&'static str>,
+    /// (hour, label, selected) for the day rollover picker.
+    pub day_ends_options: Vec<(i16, String, bool)>,
+    /// (value, label, selected) for the visibility new tasks start with.
+    pub task_visibility_options: Vec<(&'static str, &'static str, bool)>,
+    /// The user's milestone thresholds, comma-separated for the input.
+    pub milestones: String,
+    /// The thresholds new users start with, as a hint.
+    pub default_milestones: String,
     pub flash_message: Option<String>,
     pub flash_is_error: bool,
 }
@@ -1,6 +1,8 @@
 use askama::Template;
 use askama_web::WebTemplate;
-use crate::models::task::TaskWithStreak;
+use chrono::NaiveDate;
+use crate::models::sharing::Visibility;
+use crate::models::task::{Schedule, Section, TaskWithStreak};
 
 #[derive(Template, WebTemplate)]
 #[template(path = "tasks/_task_card.html")]
@@ -8,12 +10,162 @@ pub struct TaskCardPartial {
     pub task: TaskWithStreak,
 }
 
+/// Tasks under one heading on the dashboard.
+pub struct TaskSection {
+    /// Section value sent back when reordering; empty for "Anytime".
+    pub key: &'static str,
+    /// None when no task uses sections, so the list renders without headings.
+    pub label: Option<&'static str>,
+    pub tasks: Vec<TaskWithStreak>,
+}
+
+impl TaskSection {
+    /// Groups tasks, already in dashboard order, by time of day with tasks
+    /// outside any section last. Empty sections are left out.
+    pub fn group(tasks: Vec<TaskWithStreak>) -> Vec<TaskSection> {
+        if tasks.iter().all(|t| t.section.is_none()) {
+            if tasks.is_empty() {
+                return Vec::new();
+            }
+            return vec![TaskSection { key: "", label: None, tasks }];
+        }
+
+        let mut sections: Vec<TaskSection> = Section::ALL
+            .iter()
+            .map(|section| TaskSection {
+                key: section.as_str(),
+                label: Some(section.label()),
+                tasks: Vec::new(),
+            })
+            .chain(std::iter::once(TaskSection { key: "", label: Some("Anytime"), tasks: Vec::new() }))
+            .collect();
+        for task in tasks {
+            let index = task
+                .section
+                .and_then(|s| Section::ALL.iter().position(|other| *other == s))
+                .unwrap_or(Section::ALL.len());
+            sections[index].tasks.push(task);
+        }
+        sections.retain(|section| !section.tasks.is_empty());
+        sections
+    }
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "tasks/_task_list.html")]
+pub struct TaskListPartial {
+    pub sections: Vec<TaskSection>,
+}
+
 #[derive(Template, WebTemplate)]
 #[template(path = "tasks/_task_form.html")]
-pub struct TaskFormPartial;
+pub struct TaskFormPartial {
+    pub schedule: Schedule,
+    pub section_options: Vec<(&'static str, &'static str, bool)>,
+}
 
 #[derive(Template, WebTemplate)]
 #[template(path = "tasks/_task_edit.html")]
 pub struct TaskEditPartial {
     pub task: TaskWithStreak,
+    pub visibility: Visibility,
+    pub visibility_options: Vec<(&'static str, &'static str, bool)>,
+    /// (group id, name, shared) for each of the owner's groups.
+    pub group_options: Vec<(i64, String, bool)>,
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "tasks/_task_note.html")]
+pub struct TaskNotePartial {
+    pub task: TaskWithStreak,
+}
+
+/// One past day in the backfill window, oldest first.
+pub struct BackfillDay {
+    pub date: NaiveDate,
+    pub label: String,
+    pub completed: bool,
+    pub frozen: bool,
+    pub due: bool,
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "tasks/_task_backfill.html")]
+pub struct TaskBackfillPartial {
+    pub task: TaskWithStreak,
+    pub days: Vec<BackfillDay>,
+    pub freeze_tokens: i32,
+}
+
+/// One cell of the month calendar on the history page.
+#[derive(Clone)]
+pub struct CalendarDay {
+    pub day: u32,
+    pub in_month: bool,
+    pub is_today: bool,
+    pub is_future: bool,
+    pub completed: bool,
+    /// Some progress logged toward a target without reaching it.
+    pub partial: bool,
+    pub frozen: bool,
+    pub due: bool,
+    pub has_note: bool,
+}
+
+/// A completion note shown under the history calendar.
+pub struct HistoryNote {
+    pub label: String,
+    pub note: String,
+}
+
+pub struct HistoryCalendar {
+    pub task_id: i64,
+    pub avoid: bool,
+    pub label: String,
+    /// Monday-first rows of seven days covering the whole month.
+    pub weeks: Vec<Vec<CalendarDay>>,
+    pub completed_count: usize,
+    /// Notes left during the month, oldest first.
+    pub notes: Vec<HistoryNote>,
+    pub prev_month: String,
+    /// None when the calendar already shows the current month.
+    pub next_month: Option<String>,
+}
+
+pub struct HeatmapDay {
+    pub date: NaiveDate,
+    /// 0 for nothing logged up to 4 for a completed day.
+    pub level: u8,
+    pub is_future: bool,
+}
+
+pub struct HeatmapWeek {
+    /// Set on the first week of each month.
+    pub month_label: Option<String>,
+    pub days: Vec<HeatmapDay>,
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "tasks/_history_calendar.html")]
+pub struct HistoryCalendarPartial {
+    pub calendar: HistoryCalendar,
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "tasks/history.html")]
+pub struct TaskHistoryTemplate {
+    pub task: TaskWithStreak,
+    pub calendar: HistoryCalendar,
+    pub heatmap: Vec<HeatmapWeek>,
+    pub year_count: usize,
+    pub flash_message: Option<String>,
+    pub flash_is_error: bool,
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "tasks/archived.html")]
+pub struct ArchivedTasksTemplate {
+    pub tasks: Vec<TaskWithStreak>,
+    pub flash_message: Option<String>,
+    pub flash_is_error: bool,
 }
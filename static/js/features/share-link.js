@@ -0,0 +1,16 @@
+async function handleCopyClick(event) {
+  const button = event.target.closest('[data-copy-link]');
+  if (!button) return;
+
+  const url = new URL(button.dataset.copyLink, window.location.origin).href;
+  try {
+    await navigator.clipboard.writeText(url);
+    window.showToast?.('Link copied', 'success');
+  } catch {
+    window.prompt('Copy this link', url);
+  }
+}
+
+export function initShareLink() {
+  document.addEventListener('click', handleCopyClick);
+}
@@ -0,0 +1,35 @@
+function handleScheduleChange(event) {
+  const select = event.target.closest('select[name="schedule"]');
+  if (!select) return;
+
+  const fields = select.closest('.schedule-fields');
+  for (const el of fields.querySelectorAll('[data-schedule]')) {
+    el.hidden = el.dataset.schedule !== select.value;
+  }
+}
+
+function handleKindChange(event) {
+  const select = event.target.closest('select[name="kind"]');
+  if (!select) return;
+
+  const form = select.closest('form');
+  for (const el of form.querySelectorAll('[data-kind]')) {
+    el.hidden = el.dataset.kind !== select.value;
+  }
+}
+
+function handleVisibilityChange(event) {
+  const select = event.target.closest('select[name="visibility"]');
+  if (!select) return;
+
+  const fields = select.closest('.visibility-fields');
+  for (const el of fields.querySelectorAll('[data-visibility]')) {
+    el.hidden = el.dataset.visibility !== select.value;
+  }
+}
+
+export function initTaskForm() {
+  document.addEventListener('change', handleScheduleChange);
+  document.addEventListener('change', handleKindChange);
+  document.addEventListener('change', handleVisibilityChange);
+}
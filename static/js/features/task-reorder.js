@@ -0,0 +1,70 @@
+let dragged = null;
+
+function handlePointerDown(event) {
+  const handle = event.target.closest('.task-drag-handle');
+  if (!handle) return;
+
+  // Only the handle starts a drag, so text and buttons stay usable
+  const wrapper = handle.closest('.task-card-wrapper');
+  if (wrapper) wrapper.draggable = true;
+}
+
+function handlePointerUp() {
+  if (dragged) return;
+  for (const wrapper of document.querySelectorAll('.task-card-wrapper[draggable="true"]')) {
+    wrapper.draggable = false;
+  }
+}
+
+function handleDragStart(event) {
+  const wrapper = event.target.closest?.('.task-card-wrapper');
+  if (!wrapper || !wrapper.draggable) return;
+
+  dragged = wrapper;
+  wrapper.classList.add('task-dragging');
+  event.dataTransfer.effectAllowed = 'move';
+}
+
+function handleDragOver(event) {
+  if (!dragged) return;
+  const list = event.target.closest('.task-section-list');
+  if (!list) return;
+
+  event.preventDefault();
+  const cards = list.querySelectorAll(':scope > .task-card-wrapper:not(.task-dragging)');
+  const before = [...cards].find((card) => {
+    const box = card.getBoundingClientRect();
+    return event.clientY < box.top + box.height / 2;
+  });
+  list.insertBefore(dragged, before ?? null);
+}
+
+function handleDrop(event) {
+  if (dragged) event.preventDefault();
+}
+
+function saveOrder() {
+  const order = [...document.querySelectorAll('.task-section-list > .task-card-wrapper')]
+    .map((card) => `${card.dataset.taskId}:${card.closest('.task-section-list').dataset.section}`)
+    .join(',');
+
+  htmx.ajax('POST', '/tasks/reorder', { values: { order }, swap: 'none' });
+}
+
+function handleDragEnd() {
+  if (!dragged) return;
+
+  dragged.classList.remove('task-dragging');
+  dragged.draggable = false;
+  dragged = null;
+  saveOrder();
+}
+
+export function initTaskReorder() {
+  document.addEventListener('pointerdown', handlePointerDown);
+  document.addEventListener('pointerup', handlePointerUp);
+  document.addEventListener('dragstart', handleDragStart);
+  document.addEventListener('dragover', handleDragOver);
+  document.addEventListener('drop', handleDrop);
+  document.addEventListener('dragend', handleDragEnd);
+}
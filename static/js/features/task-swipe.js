@@ -31,7 +31,8 @@ function fireToggle(taskId) {
 
 function handleTouchStart(event) {
   const wrapper = event.target.closest('.task-card-wrapper');
-  if (!wrapper) return;
+  // Avoid tasks log slips from an explicit button, never by swiping
+  if (!wrapper || wrapper.dataset.kind === 'avoid') return;
 
   const touch = event.touches[0];
   startX = touch.clientX;
@@ -0,0 +1,15 @@
+function browserTimeZone() {
+  return Intl.DateTimeFormat().resolvedOptions().timeZone;
+}
+
+export function initTimeZone() {
+  const zone = browserTimeZone();
+  if (!zone) return;
+
+  for (const input of document.querySelectorAll('input[data-browser-time-zone]')) {
+    input.value = zone;
+  }
+  for (const label of document.querySelectorAll('[data-browser-time-zone-label]')) {
+    label.textContent = zone;
+  }
+}
@@ -15,8 +15,10 @@ function showToast(message, type) {
   }
 
   const toast = document.createElement('div');
-  const bgColor = type === 'success' ? '#10b981' : type === 'error' ? '#ef4444' : '#6b7280';
-  const icon = type === 'success' ? '✓' : type === 'error' ? '✕' : 'ℹ';
+  const colors = { success: '#10b981', error: '#ef4444', milestone: 'linear-gradient(135deg,#f59e0b,#ec4899)' };
+  const icons = { success: '✓', error: '✕', milestone: '🎉' };
+  const bgColor = colors[type] || '#6b7280';
+  const icon = icons[type] || 'ℹ';
   toast.style.cssText = `background:${bgColor};color:white;padding:0.75rem 1rem;border-radius:0.5rem;box-shadow:0 4px 12px rgba(0,0,0,0.3);display:flex;align-items:center;gap:0.5rem;font-size:0.875rem;font-weight:500;animation:slideIn 0.3s ease-out;pointer-events:auto;`;
   toast.innerHTML = `<span style="font-size:1rem;">${icon}</span><span>${message}</span>`;
 
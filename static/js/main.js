@@ -1,15 +1,19 @@
-import { initDateHeader } from './features/date-header.js';
+import { initShareLink } from './features/share-link.js';
+import { initTaskForm } from './features/task-form.js';
+import { initTaskReorder } from './features/task-reorder.js';
 import { initTaskSwipe } from './features/task-swipe.js';
 import { initTaskToggle } from './features/task-toggle.js';
+import { initTimeZone } from './features/time-zone.js';
 import { initToast } from './features/toast.js';
-import { getLocalDate, setCookie } from './utils.js';
 
 function init() {
-  setCookie('local_date', getLocalDate());
-  initDateHeader();
+  initTaskForm();
   initTaskToggle();
+  initTaskReorder();
   initTaskSwipe();
   initToast();
+  initTimeZone();
+  initShareLink();
 }
 
 if (document.readyState === 'loading') {
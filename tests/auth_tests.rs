@@ -138,3 +138,168 @@ async fn logout_clears_session(pool: PgPool) {
     let dashboard = server.get("/").await;
     dashboard.assert_status_see_other();
 }
+
+#[derive(serde::Serialize)]
+struct RegisterWithZoneForm {
+    username: String,
+    email: String,
+    password: String,
+    time_zone: String,
+}
+
+async fn stored_time_zone(pool: &PgPool) -> String {
+    sqlx::query_scalar("SELECT time_zone FROM users WHERE username = 'alice'")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test]
+async fn register_stores_browser_time_zone(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    server
+        .post("/register")
+        .form(&RegisterWithZoneForm {
+            username: "alice".to_string(),
+            email: "alice@test.com".to_string(),
+            password: "password123".to_string(),
+            time_zone: "Europe/Madrid".to_string(),
+        })
+        .await
+        .assert_status_see_other();
+
+    assert_eq!(stored_time_zone(&pool).await, "Europe/Madrid");
+}
+
+#[sqlx::test]
+async fn register_falls_back_to_default_time_zone(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    server
+        .post("/register")
+        .form(&RegisterWithZoneForm {
+            username: "alice".to_string(),
+            email: "alice@test.com".to_string(),
+            password: "password123".to_string(),
+            time_zone: "Mars/Olympus_Mons".to_string(),
+        })
+        .await
+        .assert_status_see_other();
+
+    assert_eq!(stored_time_zone(&pool).await, "America/Chicago");
+}
+
+#[derive(serde::Serialize)]
+struct TimeZoneForm {
+    time_zone: String,
+}
+
+#[sqlx::test]
+async fn profile_updates_time_zone(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+
+    let response = server
+        .post("/profile/time-zone")
+        .form(&TimeZoneForm { time_zone: "Asia/Tokyo".to_string() })
+        .await;
+    response.assert_status_ok();
+    response.assert_text_contains("Time zone updated");
+    assert_eq!(stored_time_zone(&pool).await, "Asia/Tokyo");
+}
+
+#[sqlx::test]
+async fn profile_rejects_unknown_time_zone(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+
+    let response = server
+        .post("/profile/time-zone")
+        .form(&TimeZoneForm { time_zone: "Nowhere/Special".to_string() })
+        .await;
+    response.assert_status_ok();
+    response.assert_text_contains("Unknown time zone");
+    assert_eq!(stored_time_zone(&pool).await, "America/Chicago");
+}
+
+#[derive(serde::Serialize)]
+struct DayEndsForm {
+    day_ends_hour: i16,
+}
+
+async fn stored_day_ends_hour(pool: &PgPool) -> i16 {
+    sqlx::query_scalar("SELECT day_ends_hour FROM users WHERE username = 'alice'")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test]
+async fn profile_updates_day_ends_hour(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+
+    let response = server
+        .post("/profile/day-ends")
+        .form(&DayEndsForm { day_ends_hour: 3 })
+        .await;
+    response.assert_status_ok();
+    response.assert_text_contains("Day rollover updated");
+    assert_eq!(stored_day_ends_hour(&pool).await, 3);
+}
+
+#[sqlx::test]
+async fn profile_rejects_late_day_ends_hour(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+
+    let response = server
+        .post("/profile/day-ends")
+        .form(&DayEndsForm { day_ends_hour: 9 })
+        .await;
+    response.assert_status_ok();
+    response.assert_text_contains("Day must end by 6:00 AM");
+    assert_eq!(stored_day_ends_hour(&pool).await, 0);
+}
+
+async fn stored_milestones(pool: &PgPool) -> Vec<i32> {
+    sqlx::query_scalar("SELECT milestone_thresholds FROM users WHERE username = 'alice'")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test]
+async fn profile_updates_milestones(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    assert_eq!(stored_milestones(&pool).await, vec![7, 30, 100]);
+
+    let response = server
+        .post("/profile/milestones")
+        .form(&[("milestones", "21, 3,21 , 50")])
+        .await;
+    response.assert_status_ok();
+    response.assert_text_contains("Milestones updated");
+    response.assert_text_contains(r#"value="3, 21, 50""#);
+    assert_eq!(stored_milestones(&pool).await, vec![3, 21, 50]);
+}
+
+#[sqlx::test]
+async fn profile_rejects_bad_milestones(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+
+    for milestones in ["7, soon", "0", "5000"] {
+        server
+            .post("/profile/milestones")
+            .form(&[("milestones", milestones)])
+            .await
+            .assert_text_contains("Milestones must be whole numbers from 1 to 3650");
+    }
+    server
+        .post("/profile/milestones")
+        .form(&[("milestones", "1,2,3,4,5,6,7,8,9,10,11")])
+        .await
+        .assert_text_contains("Pick at most 10 milestones");
+    assert_eq!(stored_milestones(&pool).await, vec![7, 30, 100]);
+}
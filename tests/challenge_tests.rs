@@ -0,0 +1,238 @@
+mod common;
+
+use racha::models::challenge;
+use sqlx::PgPool;
+
+#[derive(serde::Serialize)]
+struct CreateGroupForm {
+    name: String,
+}
+
+#[derive(serde::Serialize)]
+struct JoinGroupForm {
+    invite_code: String,
+}
+
+/// Alice creates "Study Group" and Bob joins it; Bob stays logged in.
+async fn group_with_member(pool: &PgPool) -> axum_test::TestServer {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/groups")
+        .form(&CreateGroupForm {
+            name: "Study Group".to_string(),
+        })
+        .await;
+    let invite_code: String = sqlx::query_scalar("SELECT invite_code FROM groups WHERE id = 1")
+        .fetch_one(pool)
+        .await
+        .unwrap();
+
+    server.post("/logout").await;
+    common::register_user(&server, "bob", "bob@test.com", "password123").await;
+    server.post("/groups/join").form(&JoinGroupForm { invite_code }).await;
+    server
+}
+
+async fn create_challenge(server: &axum_test::TestServer, start: &str, end: &str) -> axum_test::TestResponse {
+    server
+        .post("/groups/1/challenges")
+        .form(&[("name", "Push-ups"), ("description", ""), ("start_date", start), ("end_date", end)])
+        .await
+}
+
+/// Bob's group with a "Push-ups" challenge (id 1) from Oct 10 to Oct 20,
+/// created by Alice; Bob stays logged in.
+async fn group_with_challenge(pool: &PgPool) -> axum_test::TestServer {
+    let server = group_with_member(pool).await;
+    server.post("/logout").await;
+    common::login_user(&server, "alice", "password123").await;
+    let response = create_challenge(&server, "2026-10-10", "2026-10-20").await;
+    assert_eq!(response.header("location"), "/groups/1/challenges/1");
+    server.post("/logout").await;
+    common::login_user(&server, "bob", "password123").await;
+    server
+}
+
+async fn complete(pool: &PgPool, task_id: i64, dates: &[&str]) {
+    for date in dates {
+        sqlx::query("INSERT INTO completions (task_id, completed_date) VALUES ($1, $2::DATE)")
+            .bind(task_id)
+            .bind(date)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+}
+
+#[sqlx::test]
+async fn admins_create_challenges(pool: PgPool) {
+    let server = group_with_challenge(&pool).await;
+    create_challenge(&server, "2026-10-16", "2026-10-20").await.assert_status_forbidden();
+
+    let feed = server.get("/groups/1").await;
+    feed.assert_text_contains("Push-ups");
+    feed.assert_text_contains("Day 6 of 11");
+    assert!(!feed.text().contains("Create challenge"));
+}
+
+#[sqlx::test]
+async fn challenge_dates_are_validated(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/groups")
+        .form(&CreateGroupForm {
+            name: "Study Group".to_string(),
+        })
+        .await;
+
+    for (start, end, message) in [
+        ("2026-10-20", "2026-10-16", "end before it starts"),
+        ("2026-10-16", "2027-10-16", "at most 365 days"),
+        ("2026-10-01", "2026-10-14", "would already be over"),
+    ] {
+        create_challenge(&server, start, end).await;
+        server.get("/groups/1").await.assert_text_contains(message);
+    }
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM challenges")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
+
+#[sqlx::test]
+async fn joining_adds_a_linked_task(pool: PgPool) {
+    let server = group_with_challenge(&pool).await;
+    server.get("/groups/1/challenges/1").await.assert_text_contains("Join challenge");
+
+    server.post("/groups/1/challenges/1/join").await;
+    server.post("/groups/1/challenges/1/join").await;
+    let page = server.get("/groups/1/challenges/1").await;
+    assert!(!page.text().contains("Join challenge"));
+    page.assert_text_contains("bob");
+
+    let task_ids: Vec<i64> = sqlx::query_scalar("SELECT task_id FROM challenge_participants WHERE user_id = 2")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(task_ids.len(), 1);
+    server.get("/").await.assert_text_contains("Push-ups");
+}
+
+#[sqlx::test]
+async fn challenge_tasks_stay_in_their_group(pool: PgPool) {
+    let server = group_with_challenge(&pool).await;
+    server
+        .post("/groups")
+        .form(&CreateGroupForm {
+            name: "Family".to_string(),
+        })
+        .await;
+    server.post("/groups/1/challenges/1/join").await;
+
+    server.get("/groups/1").await.assert_text_contains("Push-ups");
+    assert!(!server.get("/groups/2").await.text().contains("Push-ups"));
+    let shared: Vec<i64> = sqlx::query_scalar("SELECT group_id FROM task_group_shares WHERE task_id = 1")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(shared, vec![1]);
+}
+
+#[sqlx::test]
+async fn concurrent_joins_sign_up_once(pool: PgPool) {
+    group_with_challenge(&pool).await;
+    let challenge = challenge::find(&pool, 1, 1).await.unwrap().unwrap();
+
+    let (first, second) = tokio::join!(challenge::join(&pool, &challenge, 2), challenge::join(&pool, &challenge, 2));
+    let mut joined = vec![first.unwrap(), second.unwrap()];
+    joined.sort();
+    assert_eq!(joined, vec![false, true]);
+
+    let tasks: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tasks WHERE user_id = 2")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(tasks, 1);
+}
+
+#[sqlx::test]
+async fn standings_cover_the_challenge_window(pool: PgPool) {
+    let server = group_with_challenge(&pool).await;
+    server.post("/groups/1/challenges/1/join").await;
+    // Completions before the start don't count
+    complete(&pool, 1, &["2026-10-09", "2026-10-10", "2026-10-11", "2026-10-12", "2026-10-14", "2026-10-15"]).await;
+
+    let page = server.get("/groups/1/challenges/1").await.text();
+    let row = page.split("challenge-standing-mine").nth(1).unwrap();
+    let cells: Vec<&str> = row
+        .split("<td>")
+        .skip(1)
+        .take(5)
+        .map(|cell| cell.split("</td>").next().unwrap())
+        .collect();
+    assert_eq!(cells, vec!["bob", "5/6", "83%", "2", "3"]);
+}
+
+#[sqlx::test]
+async fn standings_follow_each_participants_day(pool: PgPool) {
+    // 18:00 UTC: still the 15th for Alice in Chicago, already the 16th for Bob in Tokyo
+    let server = group_with_challenge(&pool).await;
+    sqlx::query("UPDATE users SET time_zone = 'Asia/Tokyo' WHERE username = 'bob'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    server.post("/groups/1/challenges/1/join").await;
+    complete(&pool, 1, &["2026-10-15", "2026-10-16"]).await;
+
+    server.post("/logout").await;
+    common::login_user(&server, "alice", "password123").await;
+    let page = server.get("/groups/1/challenges/1").await.text();
+    let row = page.split("<td>bob</td>").nth(1).unwrap();
+    let cells: Vec<&str> = row
+        .split("<td>")
+        .skip(1)
+        .take(4)
+        .map(|cell| cell.split("</td>").next().unwrap())
+        .collect();
+    assert_eq!(cells, vec!["2/7", "29%", "2", "2"]);
+}
+
+#[sqlx::test]
+async fn ended_challenge_shows_final_summary(pool: PgPool) {
+    let server = group_with_challenge(&pool).await;
+    server.post("/groups/1/challenges/1/join").await;
+    server.post("/logout").await;
+    common::login_user(&server, "alice", "password123").await;
+    server.post("/groups/1/challenges/1/join").await;
+    sqlx::query("UPDATE challenges SET start_date = '2026-10-11', end_date = '2026-10-13'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    complete(&pool, 1, &["2026-10-11", "2026-10-13"]).await;
+    complete(&pool, 2, &["2026-10-11", "2026-10-12", "2026-10-13"]).await;
+
+    let page = server.get("/groups/1/challenges/1").await;
+    page.assert_text_contains("Final results");
+    page.assert_text_contains("Top of the table:</span> alice");
+    page.assert_text_contains("Didn't miss a day:</span> alice");
+    page.assert_text_contains("Average completion:</span> 83%");
+
+    server.post("/logout").await;
+    common::register_user(&server, "carol", "carol@test.com", "password123").await;
+    server.get("/groups/1/challenges/1").await.assert_status_forbidden();
+}
+
+#[sqlx::test]
+async fn ended_challenge_cannot_be_joined(pool: PgPool) {
+    let server = group_with_challenge(&pool).await;
+    sqlx::query("UPDATE challenges SET end_date = '2026-10-14'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    server.post("/groups/1/challenges/1/join").await;
+    server.get("/groups/1/challenges/1").await.assert_text_contains("This challenge has ended");
+    server.get("/groups/1/challenges/2").await.assert_status_not_found();
+}
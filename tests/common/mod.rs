@@ -1,17 +1,39 @@
 use axum_test::TestServer;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use sqlx::PgPool;
 use tower_sessions::SessionManagerLayer;
 use tower_sessions_sqlx_store::PostgresStore;
 
-use racha::{AppState, routes};
+use racha::{AppState, clock::Clock, routes};
+
+/// The day tests run on, matching the `X-Local-Date` most of them send.
+pub const TEST_DATE: &str = "2026-10-15";
 
 pub async fn build_test_server(pool: PgPool) -> TestServer {
+    build_test_server_on(pool, TEST_DATE).await
+}
+
+/// A test server whose clock reads early afternoon, Central time, on `date`.
+pub async fn build_test_server_on(pool: PgPool, date: &str) -> TestServer {
+    let now = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .unwrap()
+        .and_time(NaiveTime::from_hms_opt(18, 0, 0).unwrap())
+        .and_utc();
+    build_test_server_at(pool, now).await
+}
+
+/// A test server whose clock is stopped at `now`.
+pub async fn build_test_server_at(pool: PgPool, now: DateTime<Utc>) -> TestServer {
     let session_store = PostgresStore::new(pool.clone());
     session_store.migrate().await.expect("Failed to migrate session store");
 
     let session_layer = SessionManagerLayer::new(session_store);
 
-    let state = AppState { db: pool };
+    let state = AppState {
+        db: pool,
+        backfill_days: 2,
+        clock: Clock::Fixed(now),
+    };
 
     let app = routes::build_router()
         .layer(session_layer)
@@ -35,6 +57,18 @@ pub async fn register_user(server: &TestServer, username: &str, email: &str, pas
     response.assert_status_see_other();
 }
 
+#[allow(dead_code)]
+pub async fn login_user(server: &TestServer, username: &str, password: &str) {
+    let response = server
+        .post("/login")
+        .form(&LoginForm {
+            username: username.to_string(),
+            password: password.to_string(),
+        })
+        .await;
+    response.assert_status_see_other();
+}
+
 #[derive(serde::Serialize)]
 pub struct RegisterForm {
     pub username: String,
@@ -29,3 +29,158 @@ async fn dashboard_with_no_tasks_or_groups_renders(pool: PgPool) {
     // Should render without error even with empty tasks/groups
     response.assert_text_contains("bob");
 }
+
+#[derive(serde::Serialize)]
+struct SectionTaskForm {
+    name: String,
+    section: String,
+}
+
+#[derive(serde::Serialize)]
+struct ReorderForm {
+    order: String,
+}
+
+async fn create_task(server: &axum_test::TestServer, name: &str, section: &str) {
+    server
+        .post("/tasks")
+        .form(&SectionTaskForm {
+            name: name.to_string(),
+            section: section.to_string(),
+        })
+        .await
+        .assert_status_ok();
+}
+
+/// Byte offset of each name in the page, which must all be present.
+fn positions(body: &str, names: &[&str]) -> Vec<usize> {
+    names.iter().map(|name| body.find(name).expect(name)).collect()
+}
+
+#[sqlx::test]
+async fn new_tasks_appear_first(pool: PgPool) {
+    let server = common::build_test_server(pool).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_task(&server, "Task-A", "").await;
+    create_task(&server, "Task-B", "").await;
+
+    let body = server.get("/").await.text();
+    let at = positions(&body, &["Task-B", "Task-A"]);
+    assert!(at[0] < at[1]);
+}
+
+#[sqlx::test]
+async fn reorder_persists_dashboard_order(pool: PgPool) {
+    let server = common::build_test_server(pool).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_task(&server, "Task-A", "").await;
+    create_task(&server, "Task-B", "").await;
+    create_task(&server, "Task-C", "").await;
+
+    server
+        .post("/tasks/reorder")
+        .form(&ReorderForm {
+            order: "1:,3:,2:".to_string(),
+        })
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+
+    let body = server.get("/").await.text();
+    let at = positions(&body, &["Task-A", "Task-C", "Task-B"]);
+    assert!(at[0] < at[1] && at[1] < at[2]);
+}
+
+#[sqlx::test]
+async fn reorder_rejects_malformed_order(pool: PgPool) {
+    let server = common::build_test_server(pool).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+
+    let response = server
+        .post("/tasks/reorder")
+        .form(&ReorderForm {
+            order: "one:,two:".to_string(),
+        })
+        .await;
+    response.assert_status_bad_request();
+}
+
+#[sqlx::test]
+async fn reorder_ignores_other_users_tasks(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_task(&server, "Task-A", "").await;
+
+    server.post("/logout").await;
+    common::register_user(&server, "bob", "bob@test.com", "password123").await;
+    server
+        .post("/tasks/reorder")
+        .form(&ReorderForm {
+            order: "1:evening".to_string(),
+        })
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+
+    let section: Option<String> = sqlx::query_scalar("SELECT section FROM tasks WHERE id = 1")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(section, None);
+}
+
+#[sqlx::test]
+async fn sections_group_tasks_by_time_of_day(pool: PgPool) {
+    let server = common::build_test_server(pool).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+
+    let body = server.get("/").await.text();
+    assert!(!body.contains("task-section-title"));
+
+    create_task(&server, "Task-Evening", "evening").await;
+    create_task(&server, "Task-Any", "").await;
+    create_task(&server, "Task-Morning", "morning").await;
+
+    let body = server.get("/").await.text();
+    let at = positions(&body, &["Morning", "Task-Morning", "Evening", "Task-Evening", "Anytime", "Task-Any"]);
+    assert!(at.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(!body.contains(">Afternoon<"));
+}
+
+#[sqlx::test]
+async fn reorder_can_move_task_between_sections(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_task(&server, "Task-A", "morning").await;
+    create_task(&server, "Task-B", "morning").await;
+
+    server
+        .post("/tasks/reorder")
+        .form(&ReorderForm {
+            order: "2:morning,1:afternoon".to_string(),
+        })
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+
+    let section: Option<String> = sqlx::query_scalar("SELECT section FROM tasks WHERE id = 1")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(section.as_deref(), Some("afternoon"));
+}
+
+#[sqlx::test]
+async fn editing_section_rerenders_task_list(pool: PgPool) {
+    let server = common::build_test_server(pool).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_task(&server, "Task-A", "").await;
+
+    let response = server
+        .post("/tasks/1/edit")
+        .form(&SectionTaskForm {
+            name: "Task-A".to_string(),
+            section: "evening".to_string(),
+        })
+        .await;
+    response.assert_status_ok();
+    assert_eq!(response.header("HX-Retarget"), "#task-list");
+    response.assert_text_contains("Evening");
+}
@@ -119,6 +119,39 @@ async fn group_feed_shows_member_names(pool: PgPool) {
     response.assert_text_contains("Runners");
 }
 
+#[derive(serde::Serialize)]
+struct AvoidTaskForm {
+    name: String,
+    kind: String,
+}
+
+#[sqlx::test]
+async fn group_feed_shows_slips_on_avoid_tasks(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&AvoidTaskForm {
+            name: "No smoking".to_string(),
+            kind: "avoid".to_string(),
+        })
+        .await;
+    server
+        .post("/groups")
+        .form(&CreateGroupForm {
+            name: "Quitters".to_string(),
+        })
+        .await;
+
+    let response = server.get("/groups/1").await;
+    response.assert_text_contains("No smoking");
+    response.assert_text_contains("clean");
+
+    server.post("/tasks/1/toggle").await.assert_status_ok();
+    let response = server.get("/groups/1").await;
+    response.assert_text_contains("slipped today");
+}
+
 #[sqlx::test]
 async fn nonexistent_group_returns_404(pool: PgPool) {
     let server = common::build_test_server(pool).await;
@@ -161,3 +194,1079 @@ async fn group_routes_require_auth(pool: PgPool) {
         .await;
     response.assert_status_see_other();
 }
+
+#[derive(serde::Serialize)]
+struct RoleForm {
+    role: String,
+}
+
+/// Alice creates "Study Group" and Bob joins it; Bob stays logged in.
+async fn group_with_member(pool: &PgPool) -> axum_test::TestServer {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/groups")
+        .form(&CreateGroupForm {
+            name: "Study Group".to_string(),
+        })
+        .await;
+    let invite_code: String = sqlx::query_scalar("SELECT invite_code FROM groups WHERE id = 1")
+        .fetch_one(pool)
+        .await
+        .unwrap();
+
+    server.post("/logout").await;
+    common::register_user(&server, "bob", "bob@test.com", "password123").await;
+    server.post("/groups/join").form(&JoinGroupForm { invite_code }).await;
+    server
+}
+
+async fn role_of(pool: &PgPool, user_id: i64) -> String {
+    sqlx::query_scalar("SELECT role FROM group_members WHERE group_id = 1 AND user_id = $1")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test]
+async fn group_feed_is_members_only(pool: PgPool) {
+    let server = group_with_member(&pool).await;
+    server.post("/logout").await;
+    common::register_user(&server, "carol", "carol@test.com", "password123").await;
+
+    server.get("/groups/1").await.assert_status_forbidden();
+}
+
+#[sqlx::test]
+async fn creator_is_owner_and_joiner_is_member(pool: PgPool) {
+    group_with_member(&pool).await;
+    assert_eq!(role_of(&pool, 1).await, "owner");
+    assert_eq!(role_of(&pool, 2).await, "member");
+}
+
+#[sqlx::test]
+async fn invite_code_is_shown_to_managers_only(pool: PgPool) {
+    let server = group_with_member(&pool).await;
+    let invite_code: String = sqlx::query_scalar("SELECT invite_code FROM groups WHERE id = 1")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+    let response = server.get("/groups/1").await;
+    response.assert_status_ok();
+    assert!(!response.text().contains(&invite_code));
+
+    server.post("/logout").await;
+    common::login_user(&server, "alice", "password123").await;
+    server.get("/groups/1").await.assert_text_contains(&invite_code);
+}
+
+#[sqlx::test]
+async fn owner_can_promote_and_demote(pool: PgPool) {
+    let server = group_with_member(&pool).await;
+    server.post("/logout").await;
+    common::login_user(&server, "alice", "password123").await;
+
+    server
+        .post("/groups/1/members/2/role")
+        .form(&RoleForm { role: "admin".to_string() })
+        .await
+        .assert_status_see_other();
+    assert_eq!(role_of(&pool, 2).await, "admin");
+    server.get("/groups/1").await.assert_text_contains("Remove admin");
+
+    server
+        .post("/groups/1/members/2/role")
+        .form(&RoleForm { role: "member".to_string() })
+        .await
+        .assert_status_see_other();
+    assert_eq!(role_of(&pool, 2).await, "member");
+}
+
+#[sqlx::test]
+async fn owner_role_cannot_be_changed(pool: PgPool) {
+    let server = group_with_member(&pool).await;
+    server.post("/logout").await;
+    common::login_user(&server, "alice", "password123").await;
+
+    server
+        .post("/groups/1/members/1/role")
+        .form(&RoleForm { role: "member".to_string() })
+        .await
+        .assert_status_bad_request();
+    assert_eq!(role_of(&pool, 1).await, "owner");
+}
+
+#[sqlx::test]
+async fn only_owner_changes_roles(pool: PgPool) {
+    let server = group_with_member(&pool).await;
+    sqlx::query("UPDATE group_members SET role = 'admin' WHERE user_id = 2")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // Even an admin can't hand out roles
+    server
+        .post("/groups/1/members/2/role")
+        .form(&RoleForm { role: "admin".to_string() })
+        .await
+        .assert_status_forbidden();
+
+    server.post("/logout").await;
+    common::register_user(&server, "carol", "carol@test.com", "password123").await;
+    server
+        .post("/groups/1/members/2/role")
+        .form(&RoleForm { role: "member".to_string() })
+        .await
+        .assert_status_forbidden();
+    assert_eq!(role_of(&pool, 2).await, "admin");
+}
+
+async fn member_count(pool: &PgPool) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM group_members WHERE group_id = 1")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test]
+async fn member_can_leave(pool: PgPool) {
+    let server = group_with_member(&pool).await;
+
+    server.get("/groups/1/leave-confirm").await.assert_text_contains("Leave Study Group?");
+    server.post("/groups/1/leave").await.assert_status_see_other();
+    assert_eq!(member_count(&pool).await, 1);
+    server.get("/groups/1").await.assert_status_forbidden();
+}
+
+#[sqlx::test]
+async fn owner_leaving_hands_over_to_admin_first(pool: PgPool) {
+    let server = group_with_member(&pool).await;
+    let invite_code: String = sqlx::query_scalar("SELECT invite_code FROM groups WHERE id = 1")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    server.post("/logout").await;
+    common::register_user(&server, "carol", "carol@test.com", "password123").await;
+    server.post("/groups/join").form(&JoinGroupForm { invite_code }).await;
+    sqlx::query("UPDATE group_members SET role = 'admin' WHERE user_id = 3")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    server.post("/logout").await;
+    common::login_user(&server, "alice", "password123").await;
+    server
+        .get("/groups/1/leave-confirm")
+        .await
+        .assert_text_contains("Ownership will pass to carol.");
+    server.post("/groups/1/leave").await.assert_status_see_other();
+
+    assert_eq!(role_of(&pool, 3).await, "owner");
+    assert_eq!(role_of(&pool, 2).await, "member");
+}
+
+#[sqlx::test]
+async fn last_member_leaving_deletes_group(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/groups")
+        .form(&CreateGroupForm {
+            name: "Solo".to_string(),
+        })
+        .await;
+
+    server
+        .get("/groups/1/leave-confirm")
+        .await
+        .assert_text_contains("the group will be deleted");
+    server.post("/groups/1/leave").await.assert_status_see_other();
+    server.get("/groups/1").await.assert_status_not_found();
+}
+
+#[sqlx::test]
+async fn admin_can_remove_members_but_not_owner(pool: PgPool) {
+    let server = group_with_member(&pool).await;
+    let invite_code: String = sqlx::query_scalar("SELECT invite_code FROM groups WHERE id = 1")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    server.post("/logout").await;
+    common::register_user(&server, "carol", "carol@test.com", "password123").await;
+    server.post("/groups/join").form(&JoinGroupForm { invite_code }).await;
+    sqlx::query("UPDATE group_members SET role = 'admin' WHERE user_id = 3")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    server.get("/groups/1/members/1/remove-confirm").await.assert_status_forbidden();
+    server.post("/groups/1/members/1/remove").await.assert_status_forbidden();
+
+    server
+        .get("/groups/1/members/2/remove-confirm")
+        .await
+        .assert_text_contains("Remove bob from Study Group?");
+    server.post("/groups/1/members/2/remove").await.assert_status_see_other();
+    assert_eq!(member_count(&pool).await, 2);
+}
+
+#[sqlx::test]
+async fn member_cannot_remove_anyone(pool: PgPool) {
+    let server = group_with_member(&pool).await;
+    server.post("/groups/1/members/1/remove").await.assert_status_forbidden();
+    assert_eq!(member_count(&pool).await, 2);
+}
+
+#[sqlx::test]
+async fn owner_can_transfer_ownership(pool: PgPool) {
+    let server = group_with_member(&pool).await;
+
+    // Bob isn't the owner yet
+    server.post("/groups/1/members/2/transfer").await.assert_status_forbidden();
+
+    server.post("/logout").await;
+    common::login_user(&server, "alice", "password123").await;
+    server
+        .get("/groups/1/members/2/transfer-confirm")
+        .await
+        .assert_text_contains("Make bob the owner of Study Group?");
+    server.post("/groups/1/members/2/transfer").await.assert_status_see_other();
+
+    assert_eq!(role_of(&pool, 2).await, "owner");
+    assert_eq!(role_of(&pool, 1).await, "admin");
+    server.post("/groups/1/delete").await.assert_status_forbidden();
+}
+
+#[sqlx::test]
+async fn owner_can_delete_group(pool: PgPool) {
+    let server = group_with_member(&pool).await;
+    server.post("/groups/1/delete").await.assert_status_forbidden();
+
+    server.post("/logout").await;
+    common::login_user(&server, "alice", "password123").await;
+    server.get("/groups/1/delete-confirm").await.assert_text_contains("This cannot be undone.");
+    server.post("/groups/1/delete").await.assert_status_see_other();
+
+    assert_eq!(member_count(&pool).await, 0);
+    server.get("/groups/1").await.assert_status_not_found();
+}
+
+#[derive(serde::Serialize)]
+struct InviteForm {
+    expires_in_hours: String,
+    max_uses: String,
+}
+
+async fn join_with(server: &axum_test::TestServer, code: &str) -> axum_test::TestResponse {
+    server
+        .post("/groups/join")
+        .form(&JoinGroupForm {
+            invite_code: code.to_string(),
+        })
+        .await
+        .assert_status_see_other();
+    server.get("/").await
+}
+
+async fn invite_code(pool: &PgPool) -> String {
+    sqlx::query_scalar("SELECT invite_code FROM groups WHERE id = 1")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test]
+async fn join_explains_unknown_code(pool: PgPool) {
+    let server = common::build_test_server(pool).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+
+    join_with(&server, "BADCODE1")
+        .await
+        .assert_text_contains("That invite code doesn&#39;t match any group");
+    // The message is shown once
+    assert!(!server.get("/").await.text().contains("match any group"));
+}
+
+#[sqlx::test]
+async fn join_reports_success_and_existing_membership(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/groups")
+        .form(&CreateGroupForm {
+            name: "Study Group".to_string(),
+        })
+        .await;
+    let code = invite_code(&pool).await;
+
+    join_with(&server, &code)
+        .await
+        .assert_text_contains("You&#39;re already a member of that group");
+
+    server.post("/logout").await;
+    common::register_user(&server, "bob", "bob@test.com", "password123").await;
+    join_with(&server, &code.to_lowercase()).await.assert_text_contains("You joined Study Group");
+}
+
+#[sqlx::test]
+async fn rotating_code_retires_the_old_one(pool: PgPool) {
+    let server = group_with_member(&pool).await;
+    let old_code = invite_code(&pool).await;
+
+    // Plain members can't rotate
+    server.post("/groups/1/invite-code").await.assert_status_forbidden();
+
+    server.post("/logout").await;
+    common::login_user(&server, "alice", "password123").await;
+    server.post("/groups/1/invite-code").await.assert_status_see_other();
+    let new_code = invite_code(&pool).await;
+    assert_ne!(new_code, old_code);
+    server.get("/groups/1").await.assert_text_contains("Invite code regenerated");
+
+    server.post("/logout").await;
+    common::register_user(&server, "carol", "carol@test.com", "password123").await;
+    join_with(&server, &old_code)
+        .await
+        .assert_text_contains("match any group");
+    join_with(&server, &new_code).await.assert_text_contains("You joined Study Group");
+}
+
+#[sqlx::test]
+async fn limited_invite_runs_out(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/groups")
+        .form(&CreateGroupForm {
+            name: "Study Group".to_string(),
+        })
+        .await;
+    server
+        .post("/groups/1/invites")
+        .form(&InviteForm {
+            expires_in_hours: "24".to_string(),
+            max_uses: "1".to_string(),
+        })
+        .await
+        .assert_status_see_other();
+    let code: String = sqlx::query_scalar("SELECT code FROM group_invites WHERE group_id = 1")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let page = server.get("/groups/1").await;
+    page.assert_text_contains(&code);
+    page.assert_text_contains("0 / 1 uses");
+    page.assert_text_contains("Expires Oct 16, 18:00 UTC");
+
+    server.post("/logout").await;
+    common::register_user(&server, "bob", "bob@test.com", "password123").await;
+    join_with(&server, &code).await.assert_text_contains("You joined Study Group");
+
+    server.post("/logout").await;
+    common::register_user(&server, "carol", "carol@test.com", "password123").await;
+    join_with(&server, &code)
+        .await
+        .assert_text_contains("That invite has already been used as many times as allowed");
+}
+
+#[sqlx::test]
+async fn expired_and_revoked_invites_are_rejected(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/groups")
+        .form(&CreateGroupForm {
+            name: "Study Group".to_string(),
+        })
+        .await;
+    sqlx::query(
+        r#"
+        INSERT INTO group_invites (group_id, code, created_by, expires_at)
+        VALUES (1, 'OLDCODE1', 1, '2026-10-15 17:00:00'), (1, 'NEWCODE1', 1, NULL)
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    server.get("/groups/1").await.assert_text_contains("Expired");
+    server.post("/groups/1/invites/2/revoke").await.assert_status_see_other();
+    assert!(!server.get("/groups/1").await.text().contains("NEWCODE1"));
+
+    server.post("/logout").await;
+    common::register_user(&server, "bob", "bob@test.com", "password123").await;
+    join_with(&server, "OLDCODE1").await.assert_text_contains("That invite has expired");
+    join_with(&server, "NEWCODE1").await.assert_text_contains("That invite has been revoked");
+    server
+        .post("/groups/1/invites")
+        .form(&InviteForm {
+            expires_in_hours: String::new(),
+            max_uses: String::new(),
+        })
+        .await
+        .assert_status_forbidden();
+}
+
+#[sqlx::test]
+async fn invite_limits_are_validated(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/groups")
+        .form(&CreateGroupForm {
+            name: "Study Group".to_string(),
+        })
+        .await;
+
+    server
+        .post("/groups/1/invites")
+        .form(&InviteForm {
+            expires_in_hours: "9999".to_string(),
+            max_uses: String::new(),
+        })
+        .await
+        .assert_status_see_other();
+    server.get("/groups/1").await.assert_text_contains("Invites can last at most 30 days");
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM group_invites")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
+
+#[sqlx::test]
+async fn join_link_shows_group_to_visitors(pool: PgPool) {
+    let server = group_with_member(&pool).await;
+    let code = invite_code(&pool).await;
+    server.post("/logout").await;
+
+    let page = server.get(&format!("/join/{code}")).await;
+    page.assert_status_ok();
+    page.assert_text_contains("Study Group");
+    page.assert_text_contains("2 members");
+    page.assert_text_contains("Sign up to join");
+
+    server.get("/join/BADCODE1").await.assert_status_not_found();
+}
+
+#[sqlx::test]
+async fn join_link_survives_registration(pool: PgPool) {
+    let server = group_with_member(&pool).await;
+    let code = invite_code(&pool).await;
+    server.post("/logout").await;
+
+    server.get(&format!("/join/{code}")).await.assert_status_ok();
+    let response = server
+        .post("/register")
+        .form(&common::RegisterForm {
+            username: "carol".to_string(),
+            email: "carol@test.com".to_string(),
+            password: "password123".to_string(),
+        })
+        .await;
+    response.assert_status_see_other();
+    assert_eq!(response.header("location"), "/groups/1");
+
+    assert_eq!(role_of(&pool, 3).await, "member");
+    server.get("/groups/1").await.assert_text_contains("You joined Study Group");
+}
+
+#[sqlx::test]
+async fn join_link_survives_login(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server.post("/logout").await;
+    common::register_user(&server, "bob", "bob@test.com", "password123").await;
+    server
+        .post("/groups")
+        .form(&CreateGroupForm {
+            name: "Study Group".to_string(),
+        })
+        .await;
+    let code = invite_code(&pool).await;
+    server.post("/logout").await;
+
+    server.get(&format!("/join/{code}")).await.assert_text_contains("Log in to join");
+    let response = server
+        .post("/login")
+        .form(&common::LoginForm {
+            username: "alice".to_string(),
+            password: "password123".to_string(),
+        })
+        .await;
+    assert_eq!(response.header("location"), "/groups/1");
+    assert_eq!(role_of(&pool, 1).await, "member");
+
+    // The pending invite is only joined once
+    server.post("/logout").await;
+    let response = server
+        .post("/login")
+        .form(&common::LoginForm {
+            username: "alice".to_string(),
+            password: "password123".to_string(),
+        })
+        .await;
+    assert_eq!(response.header("location"), "/");
+}
+
+#[sqlx::test]
+async fn signed_in_visitor_joins_from_link(pool: PgPool) {
+    let server = group_with_member(&pool).await;
+    let code = invite_code(&pool).await;
+
+    // Bob is already in
+    server.get(&format!("/join/{code}")).await.assert_text_contains("Open group");
+
+    server.post("/logout").await;
+    common::register_user(&server, "carol", "carol@test.com", "password123").await;
+    server.get(&format!("/join/{code}")).await.assert_text_contains("Join group");
+    let response = server.post(&format!("/join/{code}")).await;
+    assert_eq!(response.header("location"), "/groups/1");
+    assert_eq!(role_of(&pool, 3).await, "member");
+}
+
+#[sqlx::test]
+async fn login_returns_to_requested_page(pool: PgPool) {
+    let server = group_with_member(&pool).await;
+    server.post("/logout").await;
+
+    server.get("/groups/1").await.assert_status_see_other();
+    let response = server
+        .post("/login")
+        .form(&common::LoginForm {
+            username: "alice".to_string(),
+            password: "password123".to_string(),
+        })
+        .await;
+    assert_eq!(response.header("location"), "/groups/1");
+}
+
+/// Alice in two groups, "Work" (id 1) and "Family" (id 2), with Bob in both
+/// and a "Run" task (id 1); Alice stays logged in.
+async fn two_groups_with_task(pool: &PgPool) -> axum_test::TestServer {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    for name in ["Work", "Family"] {
+        server
+            .post("/groups")
+            .form(&CreateGroupForm {
+                name: name.to_string(),
+            })
+            .await;
+    }
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Run".to_string(),
+            description: None,
+        })
+        .await
+        .assert_status_ok();
+
+    let codes: Vec<String> = sqlx::query_scalar("SELECT invite_code FROM groups ORDER BY id")
+        .fetch_all(pool)
+        .await
+        .unwrap();
+    server.post("/logout").await;
+    common::register_user(&server, "bob", "bob@test.com", "password123").await;
+    for invite_code in codes {
+        server.post("/groups/join").form(&JoinGroupForm { invite_code }).await;
+    }
+    server.post("/logout").await;
+    common::login_user(&server, "alice", "password123").await;
+    server
+}
+
+async fn set_visibility(server: &axum_test::TestServer, fields: &[(&str, &str)]) {
+    let mut form = vec![("name", "Run"), ("schedule", "daily")];
+    form.extend_from_slice(fields);
+    server.post("/tasks/1/edit").form(&form).await.assert_status_ok();
+}
+
+#[sqlx::test]
+async fn private_task_is_hidden_from_groups(pool: PgPool) {
+    let server = two_groups_with_task(&pool).await;
+    server.get("/groups/1").await.assert_text_contains("Run");
+
+    set_visibility(&server, &[("visibility", "private")]).await;
+    for group in [1, 2] {
+        let feed = server.get(&format!("/groups/{group}")).await;
+        assert!(!feed.text().contains("Run"));
+    }
+    server.get("/tasks/1/edit").await.assert_text_contains(r#"<option value="private" selected>"#);
+}
+
+#[sqlx::test]
+async fn task_can_be_shared_with_selected_groups(pool: PgPool) {
+    let server = two_groups_with_task(&pool).await;
+    set_visibility(&server, &[("visibility", "selected"), ("share_2", "on")]).await;
+
+    assert!(!server.get("/groups/1").await.text().contains("Run"));
+    server.get("/groups/2").await.assert_text_contains("Run");
+    let form = server.get("/tasks/1/edit").await;
+    form.assert_text_contains(r#"name="share_2" checked"#);
+    assert!(!form.text().contains(r#"name="share_1" checked"#));
+
+    // Groups the owner isn't in can't be picked
+    set_visibility(&server, &[("visibility", "selected"), ("share_2", "on"), ("share_99", "on")]).await;
+    let shared: Vec<i64> = sqlx::query_scalar("SELECT group_id FROM task_group_shares WHERE task_id = 1")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(shared, vec![2]);
+}
+
+#[sqlx::test]
+async fn editing_without_visibility_leaves_it_alone(pool: PgPool) {
+    let server = two_groups_with_task(&pool).await;
+    set_visibility(&server, &[("visibility", "private")]).await;
+    set_visibility(&server, &[]).await;
+    assert!(!server.get("/groups/1").await.text().contains("Run"));
+}
+
+#[sqlx::test]
+async fn new_tasks_follow_default_visibility(pool: PgPool) {
+    let server = two_groups_with_task(&pool).await;
+    server
+        .post("/profile/task-visibility")
+        .form(&[("visibility", "private")])
+        .await
+        .assert_text_contains("Task visibility updated");
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Therapy".to_string(),
+            description: None,
+        })
+        .await
+        .assert_status_ok();
+
+    let feed = server.get("/groups/1").await;
+    feed.assert_text_contains("Run");
+    assert!(!feed.text().contains("Therapy"));
+}
+
+async fn react(server: &axum_test::TestServer, group: i64, task: i64, reaction: &str) -> axum_test::TestResponse {
+    server
+        .post(&format!("/groups/{group}/tasks/{task}/reactions"))
+        .form(&[("reaction", reaction)])
+        .await
+}
+
+#[sqlx::test]
+async fn members_react_to_each_others_tasks(pool: PgPool) {
+    let server = two_groups_with_task(&pool).await;
+    server.post("/logout").await;
+    common::login_user(&server, "bob", "password123").await;
+
+    let partial = react(&server, 1, 1, "fire").await;
+    partial.assert_status_ok();
+    partial.assert_text_contains("reaction-mine");
+    partial.assert_text_contains("🔥 <span>1</span>");
+    partial.assert_text_contains(r#"title="bob""#);
+    server.get("/groups/1").await.assert_text_contains("🔥 <span>1</span>");
+
+    // The recipient sees it on their own card
+    server.post("/logout").await;
+    common::login_user(&server, "alice", "password123").await;
+    server.get("/").await.assert_text_contains("🔥 1");
+    server.get("/groups/1").await.assert_text_contains("🔥 1");
+}
+
+#[sqlx::test]
+async fn reacting_again_takes_it_back(pool: PgPool) {
+    let server = two_groups_with_task(&pool).await;
+    server.post("/logout").await;
+    common::login_user(&server, "bob", "password123").await;
+
+    react(&server, 1, 1, "clap").await.assert_status_ok();
+    let partial = react(&server, 1, 1, "clap").await;
+    partial.assert_status_ok();
+    assert!(!partial.text().contains("reaction-mine"));
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM reactions")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
+
+#[sqlx::test]
+async fn reactions_land_on_the_owners_day(pool: PgPool) {
+    // 18:00 UTC: still the 15th in Chicago, already the 16th in Tokyo
+    let server = two_groups_with_task(&pool).await;
+    sqlx::query("UPDATE users SET time_zone = 'Asia/Tokyo' WHERE username = 'alice'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    server.post("/logout").await;
+    common::login_user(&server, "bob", "password123").await;
+
+    react(&server, 1, 1, "fire").await.assert_status_ok();
+    let date: chrono::NaiveDate = sqlx::query_scalar("SELECT reaction_date FROM reactions")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(date.to_string(), "2026-10-16");
+
+    server.post("/logout").await;
+    common::login_user(&server, "alice", "password123").await;
+    server.get("/").await.assert_text_contains("🔥 1");
+}
+
+#[sqlx::test]
+async fn feed_shows_members_on_their_own_day(pool: PgPool) {
+    // 18:00 UTC: still the 15th for Bob in Chicago, already the 16th for Alice in Tokyo
+    let server = two_groups_with_task(&pool).await;
+    sqlx::query("UPDATE users SET time_zone = 'Asia/Tokyo' WHERE username = 'alice'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO completions (task_id, completed_date) VALUES (1, '2026-10-16')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    server.post("/logout").await;
+    common::login_user(&server, "bob", "password123").await;
+
+    server.get("/groups/1").await.assert_text_contains("task-completed-text");
+    // The cheer is stored on Alice's day and read back on it
+    react(&server, 1, 1, "fire").await.assert_status_ok();
+    server.get("/groups/1").await.assert_text_contains("🔥 <span>1</span>");
+}
+
+#[sqlx::test]
+async fn reactions_are_checked(pool: PgPool) {
+    let server = two_groups_with_task(&pool).await;
+    // Not on your own task
+    react(&server, 1, 1, "fire").await.assert_status_forbidden();
+
+    server.post("/logout").await;
+    common::login_user(&server, "bob", "password123").await;
+    react(&server, 1, 1, "thumbs").await.assert_status_bad_request();
+    react(&server, 1, 99, "fire").await.assert_status_not_found();
+
+    server.post("/logout").await;
+    common::register_user(&server, "carol", "carol@test.com", "password123").await;
+    react(&server, 1, 1, "fire").await.assert_status_forbidden();
+}
+
+#[sqlx::test]
+async fn hidden_tasks_cannot_be_reacted_to(pool: PgPool) {
+    let server = two_groups_with_task(&pool).await;
+    set_visibility(&server, &[("visibility", "selected"), ("share_2", "on")]).await;
+    server.post("/logout").await;
+    common::login_user(&server, "bob", "password123").await;
+
+    react(&server, 1, 1, "fire").await.assert_status_not_found();
+    react(&server, 2, 1, "fire").await.assert_status_ok();
+}
+
+/// The activity list's text, so assertions don't match the rest of the feed.
+async fn activity(server: &axum_test::TestServer, group: i64) -> String {
+    let feed = server.get(&format!("/groups/{group}")).await.text();
+    feed.split("<h2 class=\"font-semibold mb-3\">Activity</h2>").nth(1).unwrap_or_default().to_string()
+}
+
+#[sqlx::test]
+async fn timeline_shows_completions_newest_first(pool: PgPool) {
+    let server = two_groups_with_task(&pool).await;
+    server.post("/tasks/1/toggle").await.assert_status_ok();
+    server.post("/tasks/1/toggle").await.assert_status_ok();
+
+    let timeline = activity(&server, 1).await;
+    let unmarked = timeline.find("unmarked Run").expect("un-completion recorded");
+    let completed = timeline.find("completed Run").expect("completion recorded");
+    assert!(unmarked < completed);
+    assert!(timeline.contains("bob</span> joined the group"));
+}
+
+#[sqlx::test]
+async fn timeline_records_milestones_and_broken_streaks(pool: PgPool) {
+    let server = two_groups_with_task(&pool).await;
+    for day in 9..=14 {
+        sqlx::query("INSERT INTO completions (task_id, completed_date) VALUES (1, $1::DATE)")
+            .bind(format!("2026-10-{day}"))
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    server.post("/tasks/1/toggle").await.assert_status_ok();
+    assert!(activity(&server, 1).await.contains("hit 7 days on Run"));
+    server.get("/groups/1").await.assert_text_contains("7-day milestone");
+
+    server
+        .post("/tasks/1/backfill")
+        .form(&[("date", "2026-10-14")])
+        .await
+        .assert_status_ok();
+    let timeline = activity(&server, 1).await;
+    assert!(timeline.contains("lost a 7-day streak on Run"));
+    // The milestone and its badge go once the streak no longer covers it
+    assert!(!timeline.contains("hit 7 days on Run"));
+    assert!(!server.get("/groups/1").await.text().contains("7-day milestone"));
+}
+
+#[sqlx::test]
+async fn timeline_counts_weekly_streaks_in_weeks(pool: PgPool) {
+    let server = two_groups_with_task(&pool).await;
+    sqlx::query("UPDATE tasks SET schedule_kind = 'weekly', weekly_target = 2 WHERE id = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO events (kind, user_id, task_id, streak) VALUES ('milestone', 1, 1, 3), ('streak_broken', 1, 1, 3)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let timeline = activity(&server, 1).await;
+    assert!(timeline.contains("hit 3 weeks on Run"));
+    assert!(timeline.contains("lost a 3-week streak on Run"));
+}
+
+#[sqlx::test]
+async fn timeline_records_members_leaving(pool: PgPool) {
+    let server = group_with_member(&pool).await;
+    server.post("/groups/1/leave").await;
+    server.post("/logout").await;
+    common::login_user(&server, "alice", "password123").await;
+
+    let timeline = activity(&server, 1).await;
+    assert!(timeline.contains("bob</span> joined the group"));
+    assert!(timeline.contains("bob</span> left the group"));
+}
+
+#[sqlx::test]
+async fn timeline_hides_tasks_not_shared_with_the_group(pool: PgPool) {
+    let server = two_groups_with_task(&pool).await;
+    set_visibility(&server, &[("visibility", "selected"), ("share_2", "on")]).await;
+    server.post("/tasks/1/toggle").await.assert_status_ok();
+
+    assert!(!activity(&server, 1).await.contains("completed Run"));
+    assert!(activity(&server, 2).await.contains("completed Run"));
+}
+
+#[sqlx::test]
+async fn timeline_loads_more(pool: PgPool) {
+    let server = group_with_member(&pool).await;
+    for _ in 0..24 {
+        sqlx::query("INSERT INTO events (kind, user_id, group_id) VALUES ('joined', 2, 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    // Bob's own join plus the 24 above: the first page stops above event 6
+    let timeline = activity(&server, 1).await;
+    assert_eq!(timeline.matches("joined the group").count(), 20);
+    assert!(timeline.contains("/groups/1/activity?before=6"));
+
+    let more = server.get("/groups/1/activity?before=6").await;
+    more.assert_status_ok();
+    assert_eq!(more.text().matches("joined the group").count(), 5);
+    assert!(!more.text().contains("Load more"));
+
+    server.post("/logout").await;
+    common::register_user(&server, "carol", "carol@test.com", "password123").await;
+    server.get("/groups/1/activity").await.assert_status_forbidden();
+}
+
+/// Alice's group with Bob, and a "Walk" team habit (id 1) both take part in
+/// since Oct 10 that needs `percent`% of them; Bob stays logged in.
+async fn group_with_habit(pool: &PgPool, percent: &str) -> axum_test::TestServer {
+    let server = group_with_member(pool).await;
+    server.post("/logout").await;
+    common::login_user(&server, "alice", "password123").await;
+    server
+        .post("/groups/1/habits")
+        .form(&[("name", "Walk"), ("required_percent", percent)])
+        .await;
+    server.post("/logout").await;
+    common::login_user(&server, "bob", "password123").await;
+    server.post("/groups/1/habits/1/join").await;
+    sqlx::query("UPDATE group_habit_members SET joined_on = '2026-10-10'")
+        .execute(pool)
+        .await
+        .unwrap();
+    server
+}
+
+async fn check_in(pool: &PgPool, user_id: i64, dates: &[&str]) {
+    for date in dates {
+        sqlx::query("INSERT INTO group_habit_checkins (habit_id, user_id, checkin_date) VALUES (1, $1, $2::DATE)")
+            .bind(user_id)
+            .bind(date)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+}
+
+/// The number on the first team streak badge in `html`.
+fn team_streak(html: &str) -> String {
+    html.split(r#"title="Team streak">"#)
+        .nth(1)
+        .and_then(|badge| badge.split("</div>").next())
+        .and_then(|badge| badge.split("</svg>").nth(1))
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+#[sqlx::test]
+async fn team_streak_needs_everyone(pool: PgPool) {
+    let server = group_with_habit(&pool, "100").await;
+    check_in(&pool, 1, &["2026-10-12", "2026-10-13", "2026-10-14", "2026-10-15"]).await;
+    // Bob missed the 12th
+    check_in(&pool, 2, &["2026-10-13", "2026-10-14"]).await;
+
+    let feed = server.get("/groups/1").await;
+    feed.assert_text_contains("1/2 today");
+    feed.assert_text_contains("Still missing: bob");
+    // Today isn't over, so the streak still stands at two days
+    assert_eq!(team_streak(&feed.text()), "2");
+
+    let card = server.post("/groups/1/habits/1/check-in").await;
+    card.assert_status_ok();
+    card.assert_text_contains("2/2 today");
+    card.assert_text_contains("Today is covered");
+    assert!(!card.text().contains("Still missing"));
+    assert_eq!(team_streak(&card.text()), "3");
+
+    // Checking in again takes it back
+    let card = server.post("/groups/1/habits/1/check-in").await;
+    card.assert_text_contains("1/2 today");
+}
+
+#[sqlx::test]
+async fn team_streak_can_need_a_share_of_members(pool: PgPool) {
+    let server = group_with_habit(&pool, "50").await;
+    check_in(&pool, 1, &["2026-10-13", "2026-10-14"]).await;
+
+    let feed = server.get("/groups/1").await;
+    feed.assert_text_contains("Needs 50% of 2");
+    assert_eq!(team_streak(&feed.text()), "2");
+}
+
+#[sqlx::test]
+async fn only_participants_check_in(pool: PgPool) {
+    let server = group_with_member(&pool).await;
+    server
+        .post("/groups/1/habits")
+        .form(&[("name", "Walk"), ("required_percent", "")])
+        .await
+        .assert_status_forbidden();
+
+    server.post("/logout").await;
+    common::login_user(&server, "alice", "password123").await;
+    server
+        .post("/groups/1/habits")
+        .form(&[("name", "Walk"), ("required_percent", "150")])
+        .await;
+    server.get("/groups/1").await.assert_text_contains("must be from 1 to 100%");
+    server
+        .post("/groups/1/habits")
+        .form(&[("name", "Walk"), ("required_percent", "")])
+        .await;
+
+    server.post("/logout").await;
+    common::login_user(&server, "bob", "password123").await;
+    server.get("/groups/1").await.assert_text_contains("Take part");
+    server.post("/groups/1/habits/1/check-in").await.assert_status_forbidden();
+    server.post("/groups/1/habits/9/check-in").await.assert_status_not_found();
+}
+
+#[sqlx::test]
+async fn members_who_leave_stop_counting(pool: PgPool) {
+    let server = group_with_habit(&pool, "100").await;
+    server.post("/groups/1/leave").await;
+    server.post("/logout").await;
+    common::login_user(&server, "alice", "password123").await;
+
+    let card = server.post("/groups/1/habits/1/check-in").await;
+    card.assert_text_contains("1/1 today");
+    card.assert_text_contains("Today is covered");
+}
+
+#[sqlx::test]
+async fn leaving_a_habit_keeps_past_days(pool: PgPool) {
+    let server = group_with_habit(&pool, "100").await;
+    check_in(&pool, 1, &["2026-10-12", "2026-10-13", "2026-10-14"]).await;
+    // Bob missed the 13th, so the streak starts on the 14th
+    check_in(&pool, 2, &["2026-10-12", "2026-10-14"]).await;
+    assert_eq!(team_streak(&server.get("/groups/1").await.text()), "1");
+
+    server.post("/groups/1/habits/1/leave").await;
+    server.get("/groups/1").await.assert_text_contains("Take part");
+    server.post("/logout").await;
+    common::login_user(&server, "alice", "password123").await;
+    // The 13th still counts against the group
+    assert_eq!(team_streak(&server.get("/groups/1").await.text()), "1");
+    let card = server.post("/groups/1/habits/1/check-in").await;
+    card.assert_text_contains("1/1 today");
+    assert_eq!(team_streak(&card.text()), "2");
+}
+
+#[sqlx::test]
+async fn leaving_the_group_keeps_past_days(pool: PgPool) {
+    let server = group_with_habit(&pool, "100").await;
+    check_in(&pool, 1, &["2026-10-13", "2026-10-14"]).await;
+    check_in(&pool, 2, &["2026-10-14"]).await;
+
+    server.post("/groups/1/leave").await;
+    server.post("/logout").await;
+    common::login_user(&server, "alice", "password123").await;
+    assert_eq!(team_streak(&server.get("/groups/1").await.text()), "1");
+
+    // Bob's stint ends on the day he left
+    let left_on: Option<chrono::NaiveDate> =
+        sqlx::query_scalar("SELECT left_on FROM group_habit_members WHERE user_id = 2")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(left_on.map(|d| d.to_string()).as_deref(), Some("2026-10-15"));
+}
+
+#[sqlx::test]
+async fn removed_members_leave_their_habits(pool: PgPool) {
+    let server = group_with_habit(&pool, "100").await;
+    server.post("/logout").await;
+    common::login_user(&server, "alice", "password123").await;
+
+    server.post("/groups/1/members/2/remove").await;
+    let open: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM group_habit_members WHERE left_on IS NULL")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(open, 1);
+    let card = server.post("/groups/1/habits/1/check-in").await;
+    card.assert_text_contains("1/1 today");
+    assert_eq!(team_streak(&card.text()), "1");
+}
+
+#[sqlx::test]
+async fn team_habits_follow_each_participants_day(pool: PgPool) {
+    // 18:00 UTC: still the 15th for Bob in Chicago, already the 16th for Alice in Tokyo
+    let server = group_with_habit(&pool, "100").await;
+    sqlx::query("UPDATE users SET time_zone = 'Asia/Tokyo' WHERE username = 'alice'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    check_in(&pool, 1, &["2026-10-14", "2026-10-15", "2026-10-16"]).await;
+    check_in(&pool, 2, &["2026-10-14"]).await;
+
+    // Bob's 15th isn't over, so it can't break the streak yet
+    let feed = server.get("/groups/1").await;
+    feed.assert_text_contains("1/2 today");
+    assert_eq!(team_streak(&feed.text()), "1");
+
+    server.post("/groups/1/habits/1/check-in").await.assert_status_ok();
+
+    // Alice is on her 16th, and Bob's check-in on his 15th still counts for him
+    server.post("/logout").await;
+    common::login_user(&server, "alice", "password123").await;
+    let feed = server.get("/groups/1").await;
+    feed.assert_text_contains("2/2 today");
+    assert_eq!(team_streak(&feed.text()), "2");
+}
@@ -0,0 +1,160 @@
+mod common;
+
+use sqlx::PgPool;
+
+#[derive(serde::Serialize)]
+struct CreateTaskForm {
+    name: String,
+    description: Option<String>,
+}
+
+async fn create_task(server: &axum_test::TestServer, name: &str) {
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: name.to_string(),
+            description: None,
+        })
+        .await
+        .assert_status_ok();
+}
+
+async fn insert_completions(pool: &PgPool, task_id: i64, dates: &[&str]) {
+    for date in dates {
+        sqlx::query("INSERT INTO completions (task_id, completed_date) VALUES ($1, $2::DATE)")
+            .bind(task_id)
+            .bind(date)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+}
+
+#[sqlx::test]
+async fn history_requires_auth(pool: PgPool) {
+    let server = common::build_test_server(pool).await;
+    let response = server.get("/tasks/1/history").await;
+    response.assert_status_see_other();
+}
+
+#[sqlx::test]
+async fn history_shows_current_month_and_year(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_task(&server, "Meditate").await;
+    insert_completions(&pool, 1, &["2026-10-02", "2026-10-03", "2026-09-20", "2025-06-01"]).await;
+
+    let response = server
+        .get("/tasks/1/history")
+        .add_header("X-Local-Date", "2026-10-15")
+        .await;
+    response.assert_status_ok();
+    response.assert_text_contains("Meditate");
+    response.assert_text_contains("October 2026");
+    response.assert_text_contains("2 days done");
+    // The heatmap covers the past year but not completions older than that
+    response.assert_text_contains("3 days done");
+    response.assert_text_contains("history-day-today");
+}
+
+#[sqlx::test]
+async fn history_navigates_to_previous_month(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_task(&server, "Meditate").await;
+    insert_completions(&pool, 1, &["2026-09-20"]).await;
+
+    let response = server
+        .get("/tasks/1/history?month=2026-09")
+        .add_header("X-Local-Date", "2026-10-15")
+        .add_header("HX-Request", "true")
+        .await;
+    response.assert_status_ok();
+    let body = response.text();
+    assert!(body.contains("September 2026"));
+    assert!(body.contains("1 day done"));
+    assert!(body.contains("month=2026-08"));
+    assert!(body.contains("month=2026-10"));
+    // htmx requests get only the calendar partial
+    assert!(!body.contains("<html"));
+}
+
+#[sqlx::test]
+async fn history_does_not_go_past_current_month(pool: PgPool) {
+    let server = common::build_test_server(pool).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_task(&server, "Meditate").await;
+
+    let response = server
+        .get("/tasks/1/history?month=2027-01")
+        .add_header("X-Local-Date", "2026-10-15")
+        .await;
+    response.assert_status_ok();
+    response.assert_text_contains("October 2026");
+    assert!(!response.text().contains("month=2026-11"));
+}
+
+#[sqlx::test]
+async fn history_handles_far_past_months(pool: PgPool) {
+    let server = common::build_test_server(pool).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_task(&server, "Meditate").await;
+
+    let response = server.get("/tasks/1/history?month=1066-10").await;
+    response.assert_status_ok();
+    response.assert_text_contains("October 1066");
+    response.assert_text_contains("month=1066-09");
+
+    // The very first months can't be drawn, so the current month is shown
+    for month in ["-262144-01", "-262143-01"] {
+        let response = server.get(&format!("/tasks/1/history?month={month}")).await;
+        response.assert_status_ok();
+        response.assert_text_contains("October 2026");
+    }
+}
+
+#[sqlx::test]
+async fn history_of_other_users_task_returns_403(pool: PgPool) {
+    let server = common::build_test_server(pool).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_task(&server, "Meditate").await;
+
+    server.post("/logout").await;
+    common::register_user(&server, "bob", "bob@test.com", "password123").await;
+
+    let response = server.get("/tasks/1/history").await;
+    response.assert_status_forbidden();
+}
+
+#[sqlx::test]
+async fn history_of_missing_task_returns_404(pool: PgPool) {
+    let server = common::build_test_server(pool).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+
+    let response = server.get("/tasks/999/history").await;
+    response.assert_status_not_found();
+}
+
+#[sqlx::test]
+async fn history_lists_notes_for_the_month(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_task(&server, "Run").await;
+    insert_completions(&pool, 1, &["2026-10-03", "2026-09-12"]).await;
+    sqlx::query("UPDATE completions SET note = 'ran 5k, knee hurt' WHERE completed_date = '2026-10-03'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE completions SET note = 'hill repeats' WHERE completed_date = '2026-09-12'")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let response = server
+        .get("/tasks/1/history")
+        .add_header("X-Local-Date", "2026-10-15")
+        .await;
+    response.assert_text_contains("ran 5k, knee hurt");
+    response.assert_text_contains("Sat Oct 3");
+    assert!(!response.text().contains("hill repeats"));
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use sqlx::PgPool;
+
+#[derive(serde::Serialize)]
+struct JournalForm {
+    mood: Option<String>,
+    body: String,
+}
+
+#[sqlx::test]
+async fn journal_requires_auth(pool: PgPool) {
+    let server = common::build_test_server(pool).await;
+    let response = server.get("/journal").await;
+    response.assert_status_see_other();
+}
+
+#[sqlx::test]
+async fn journal_entry_is_saved_for_today(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+
+    let response = server
+        .post("/journal")
+        .add_header("X-Local-Date", "2026-10-15")
+        .form(&JournalForm {
+            mood: Some("4".to_string()),
+            body: "Good run, early night.".to_string(),
+        })
+        .await;
+    response.assert_status_ok();
+    response.assert_text_contains("Good run, early night.");
+
+    let (date, mood): (chrono::NaiveDate, Option<i16>) =
+        sqlx::query_as("SELECT entry_date, mood FROM journal_entries")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(date.to_string(), "2026-10-15");
+    assert_eq!(mood, Some(4));
+
+    // Saving again the same day updates the entry
+    server
+        .post("/journal")
+        .add_header("X-Local-Date", "2026-10-15")
+        .form(&JournalForm {
+            mood: Some("2".to_string()),
+            body: "Actually, a long day.".to_string(),
+        })
+        .await
+        .assert_status_ok();
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM journal_entries")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 1);
+}
+
+#[sqlx::test]
+async fn journal_ignores_out_of_range_mood(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+
+    server
+        .post("/journal")
+        .form(&JournalForm {
+            mood: Some("9".to_string()),
+            body: "Hello".to_string(),
+        })
+        .await
+        .assert_status_ok();
+    let mood: Option<i16> = sqlx::query_scalar("SELECT mood FROM journal_entries")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(mood, None);
+}
+
+#[sqlx::test]
+async fn journal_page_lists_past_entries(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    for (date, body) in [("2026-10-13", "Rainy"), ("2026-10-14", "Sunny")] {
+        sqlx::query("INSERT INTO journal_entries (user_id, entry_date, body) VALUES (1, $1::date, $2)")
+            .bind(date)
+            .bind(body)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let response = server.get("/journal").add_header("X-Local-Date", "2026-10-15").await;
+    response.assert_status_ok();
+    let body = response.text();
+    let sunny = body.find("Sunny").unwrap();
+    let rainy = body.find("Rainy").unwrap();
+    assert!(sunny < rainy);
+}
+
+#[sqlx::test]
+async fn empty_journal_entry_is_removed(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/journal")
+        .form(&JournalForm {
+            mood: Some("3".to_string()),
+            body: String::new(),
+        })
+        .await;
+    server
+        .post("/journal")
+        .form(&JournalForm {
+            mood: None,
+            body: "   ".to_string(),
+        })
+        .await
+        .assert_status_ok();
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM journal_entries")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
@@ -0,0 +1,189 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+use racha::models::notification;
+use sqlx::PgPool;
+
+#[derive(serde::Serialize)]
+struct CreateGroupForm {
+    name: String,
+}
+
+#[derive(serde::Serialize)]
+struct JoinGroupForm {
+    invite_code: String,
+}
+
+/// Alice creates a "Run" task (id 1) and "Study Group", then Bob joins;
+/// Bob stays logged in.
+async fn group_with_task(pool: &PgPool) -> axum_test::TestServer {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server.post("/tasks").form(&[("name", "Run")]).await;
+    server
+        .post("/groups")
+        .form(&CreateGroupForm {
+            name: "Study Group".to_string(),
+        })
+        .await;
+    let invite_code: String = sqlx::query_scalar("SELECT invite_code FROM groups WHERE id = 1")
+        .fetch_one(pool)
+        .await
+        .unwrap();
+
+    server.post("/logout").await;
+    common::register_user(&server, "bob", "bob@test.com", "password123").await;
+    server.post("/groups/join").form(&JoinGroupForm { invite_code }).await;
+    server
+}
+
+async fn switch_to(server: &axum_test::TestServer, username: &str) {
+    server.post("/logout").await;
+    common::login_user(server, username, "password123").await;
+}
+
+async fn complete(pool: &PgPool, task_id: i64, dates: &[&str]) {
+    for date in dates {
+        sqlx::query("INSERT INTO completions (task_id, completed_date) VALUES ($1, $2::DATE)")
+            .bind(task_id)
+            .bind(date)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+}
+
+#[sqlx::test]
+async fn joining_notifies_other_members(pool: PgPool) {
+    let server = group_with_task(&pool).await;
+    assert!(!server.get("/notifications/bell").await.text().contains("notification-count"));
+    server.get("/notifications").await.assert_text_contains("All caught up");
+
+    switch_to(&server, "alice").await;
+    server
+        .get("/notifications/bell")
+        .await
+        .assert_text_contains(r#"<span class="notification-count">1</span>"#);
+    let page = server.get("/notifications").await;
+    page.assert_text_contains("bob joined Study Group");
+    page.assert_text_contains(r#"href="/groups/1""#);
+    page.assert_text_contains("1 unread");
+}
+
+#[sqlx::test]
+async fn cheers_notify_the_owner(pool: PgPool) {
+    let server = group_with_task(&pool).await;
+    let cheer = || server.post("/groups/1/tasks/1/reactions").form(&[("reaction", "fire")]);
+    cheer().await.assert_status_ok();
+
+    switch_to(&server, "alice").await;
+    server.get("/notifications").await.assert_text_contains("bob cheered Run 🔥");
+
+    // Taking the cheer back takes back its notification
+    switch_to(&server, "bob").await;
+    cheer().await.assert_status_ok();
+    switch_to(&server, "alice").await;
+    assert!(!server.get("/notifications").await.text().contains("cheered"));
+}
+
+#[sqlx::test]
+async fn milestones_notify_the_group(pool: PgPool) {
+    let server = group_with_task(&pool).await;
+    complete(&pool, 1, &["2026-10-09", "2026-10-10", "2026-10-11", "2026-10-12", "2026-10-13", "2026-10-14"]).await;
+
+    switch_to(&server, "alice").await;
+    server.post("/tasks/1/toggle").await.assert_status_ok();
+    assert!(!server.get("/notifications").await.text().contains("streak on Run"));
+
+    switch_to(&server, "bob").await;
+    server
+        .get("/notifications")
+        .await
+        .assert_text_contains("alice hit a 7-day streak on Run");
+}
+
+#[sqlx::test]
+async fn undone_milestones_are_announced_once(pool: PgPool) {
+    let server = group_with_task(&pool).await;
+    complete(&pool, 1, &["2026-10-09", "2026-10-10", "2026-10-11", "2026-10-12", "2026-10-13", "2026-10-14"]).await;
+
+    switch_to(&server, "alice").await;
+    for _ in 0..3 {
+        server.post("/tasks/1/toggle").await.assert_status_ok();
+    }
+    let events: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM events WHERE kind = 'milestone'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(events, 1);
+
+    switch_to(&server, "bob").await;
+    let page = server.get("/notifications").await.text();
+    assert_eq!(page.matches("alice hit a 7-day streak on Run").count(), 1);
+}
+
+#[sqlx::test]
+async fn private_milestones_stay_private(pool: PgPool) {
+    let server = group_with_task(&pool).await;
+    sqlx::query("UPDATE tasks SET visibility = 'private' WHERE id = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+    complete(&pool, 1, &["2026-10-09", "2026-10-10", "2026-10-11", "2026-10-12", "2026-10-13", "2026-10-14"]).await;
+
+    switch_to(&server, "alice").await;
+    server.post("/tasks/1/toggle").await.assert_status_ok();
+    switch_to(&server, "bob").await;
+    assert!(!server.get("/notifications").await.text().contains("streak on Run"));
+}
+
+#[sqlx::test]
+async fn notifications_are_marked_read(pool: PgPool) {
+    let server = group_with_task(&pool).await;
+    server
+        .post("/groups/1/tasks/1/reactions")
+        .form(&[("reaction", "clap")])
+        .await
+        .assert_status_ok();
+    // Alice now has two: Bob joining and Bob's cheer
+    server.post("/notifications/1/read").await.assert_status_not_found();
+
+    switch_to(&server, "alice").await;
+    let item = server.post("/notifications/1/read").await;
+    item.assert_status_ok();
+    assert_eq!(item.header("HX-Trigger"), "notifications-changed");
+    item.assert_text_contains("bob joined Study Group");
+    assert!(!item.text().contains("Mark read"));
+    server
+        .get("/notifications/bell")
+        .await
+        .assert_text_contains(r#"<span class="notification-count">1</span>"#);
+
+    let response = server.post("/notifications/read-all").await;
+    assert_eq!(response.header("location"), "/notifications");
+    assert!(!server.get("/notifications/bell").await.text().contains("notification-count"));
+    server.get("/notifications").await.assert_text_contains("All caught up");
+}
+
+#[sqlx::test]
+async fn streaks_at_risk_are_flagged_late_in_the_day(pool: PgPool) {
+    let server = group_with_task(&pool).await;
+    switch_to(&server, "alice").await;
+    server.post("/tasks").form(&[("name", "Read")]).await;
+    server.post("/tasks").form(&[("name", "Stretch")]).await;
+    complete(&pool, 1, &["2026-10-13", "2026-10-14"]).await;
+    complete(&pool, 2, &["2026-10-14", "2026-10-15"]).await;
+
+    // Early afternoon in Chicago: too soon to worry
+    let afternoon = Utc.with_ymd_and_hms(2026, 10, 15, 18, 0, 0).unwrap();
+    assert_eq!(notification::flag_streaks_at_risk(&pool, afternoon).await.unwrap(), 0);
+
+    // 10pm: Run is due and undone, Read is done, Stretch has no streak
+    let evening = Utc.with_ymd_and_hms(2026, 10, 16, 3, 0, 0).unwrap();
+    assert_eq!(notification::flag_streaks_at_risk(&pool, evening).await.unwrap(), 1);
+    assert_eq!(notification::flag_streaks_at_risk(&pool, evening).await.unwrap(), 0);
+    server
+        .get("/notifications")
+        .await
+        .assert_text_contains("Your 2-day streak on Run ends today unless you check in");
+}
@@ -0,0 +1,233 @@
+mod common;
+
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+use racha::models::completion;
+use racha::models::streak_cache::{self, CachedStreak, RebuildReport};
+use racha::models::task::TaskWithStreak;
+
+#[derive(serde::Serialize)]
+struct CreateTaskForm {
+    name: String,
+    description: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+struct EditTaskForm {
+    name: String,
+    schedule: String,
+    weekly_target: String,
+}
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+/// 18:00 UTC on `day`, mid-afternoon in Chicago.
+fn afternoon(day: &str) -> chrono::DateTime<chrono::Utc> {
+    format!("{day}T18:00:00Z").parse().unwrap()
+}
+
+async fn setup(pool: &PgPool) -> axum_test::TestServer {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Read".to_string(),
+            description: None,
+        })
+        .await
+        .assert_status_ok();
+    server
+}
+
+async fn insert_completions(pool: &PgPool, dates: &[&str]) {
+    for date in dates {
+        sqlx::query("INSERT INTO completions (task_id, completed_date) VALUES (1, $1::DATE)")
+            .bind(date)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+}
+
+async fn cached(pool: &PgPool) -> Option<CachedStreak> {
+    sqlx::query_as(
+        r#"
+        SELECT task_id, current_streak, best_streak, frozen, total_completions, last_completed, as_of
+        FROM task_streaks WHERE task_id = 1
+        "#,
+    )
+    .fetch_optional(pool)
+    .await
+    .unwrap()
+}
+
+#[sqlx::test]
+async fn toggle_updates_cache(pool: PgPool) {
+    let server = setup(&pool).await;
+    insert_completions(&pool, &["2026-10-13", "2026-10-14"]).await;
+
+    server.post("/tasks/1/toggle").await.assert_status_ok();
+    assert_eq!(
+        cached(&pool).await,
+        Some(CachedStreak {
+            task_id: 1,
+            current_streak: 3,
+            best_streak: 3,
+            frozen: false,
+            total_completions: 3,
+            last_completed: Some(date("2026-10-15")),
+            as_of: date("2026-10-15"),
+        })
+    );
+
+    server.post("/tasks/1/toggle").await.assert_status_ok();
+    let row = cached(&pool).await.unwrap();
+    assert_eq!(row.current_streak, 2);
+    assert_eq!(row.last_completed, Some(date("2026-10-14")));
+}
+
+#[sqlx::test]
+async fn concurrent_writes_leave_cache_consistent(pool: PgPool) {
+    setup(&pool).await;
+    let today = date("2026-10-15");
+    for _ in 0..5 {
+        let days = ["2026-10-11", "2026-10-12", "2026-10-13", "2026-10-14", "2026-10-15"];
+        let writes = days.map(|day| {
+            let pool = pool.clone();
+            tokio::spawn(async move { completion::complete_on(&pool, 1, date(day), today).await })
+        });
+        for write in writes {
+            write.await.unwrap().unwrap();
+        }
+        assert_eq!(cached(&pool).await.unwrap().current_streak, 5);
+
+        let writes = days.map(|day| {
+            let pool = pool.clone();
+            tokio::spawn(async move { completion::uncomplete_on(&pool, 1, date(day), today).await })
+        });
+        for write in writes {
+            write.await.unwrap().unwrap();
+        }
+        assert_eq!(cached(&pool).await.unwrap().total_completions, 0);
+    }
+}
+
+#[sqlx::test]
+async fn dashboard_reads_fresh_cache(pool: PgPool) {
+    let server = setup(&pool).await;
+    server.post("/tasks/1/toggle").await.assert_status_ok();
+
+    // Doctor the row; a fresh row is trusted over the raw history
+    sqlx::query("UPDATE task_streaks SET current_streak = 42, best_streak = 42 WHERE task_id = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    assert_eq!(task.current_streak, 42);
+    assert!(task.completed_today);
+
+    // ...but a stale one is not
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-16")).await.unwrap().unwrap();
+    assert_eq!(task.current_streak, 1);
+}
+
+#[sqlx::test]
+async fn editing_schedule_invalidates_cache(pool: PgPool) {
+    let server = setup(&pool).await;
+    server.post("/tasks/1/toggle").await.assert_status_ok();
+    assert!(cached(&pool).await.is_some());
+
+    server
+        .post("/tasks/1/edit")
+        .form(&EditTaskForm {
+            name: "Read".to_string(),
+            schedule: "weekly".to_string(),
+            weekly_target: "2".to_string(),
+        })
+        .await
+        .assert_status_ok();
+    assert_eq!(cached(&pool).await, None);
+}
+
+#[sqlx::test]
+async fn roll_over_recomputes_for_new_day(pool: PgPool) {
+    let server = setup(&pool).await;
+    insert_completions(&pool, &["2026-10-14"]).await;
+    server.post("/tasks/1/toggle").await.assert_status_ok();
+    assert_eq!(cached(&pool).await.unwrap().current_streak, 2);
+
+    // Same day: nothing to do
+    assert_eq!(streak_cache::roll_over(&pool, afternoon("2026-10-15")).await.unwrap(), 0);
+
+    // Friday passes without a check-in, so by Saturday the streak is gone
+    assert_eq!(streak_cache::roll_over(&pool, afternoon("2026-10-17")).await.unwrap(), 1);
+    let row = cached(&pool).await.unwrap();
+    assert_eq!(row.as_of, date("2026-10-17"));
+    assert_eq!(row.current_streak, 0);
+    assert_eq!(row.best_streak, 2);
+}
+
+#[sqlx::test]
+async fn roll_over_logs_streaks_lost_to_missed_days(pool: PgPool) {
+    let server = setup(&pool).await;
+    insert_completions(&pool, &["2026-10-14"]).await;
+    server.post("/tasks/1/toggle").await.assert_status_ok();
+    let broken = || async {
+        sqlx::query_scalar::<_, i32>("SELECT streak FROM events WHERE kind = 'streak_broken' AND task_id = 1")
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+    };
+
+    // Friday is still open, so the streak is still running
+    streak_cache::roll_over(&pool, afternoon("2026-10-16")).await.unwrap();
+    assert!(broken().await.is_empty());
+
+    // Missing Friday ends it, once
+    streak_cache::roll_over(&pool, afternoon("2026-10-17")).await.unwrap();
+    streak_cache::roll_over(&pool, afternoon("2026-10-18")).await.unwrap();
+    assert_eq!(broken().await, vec![2]);
+}
+
+#[sqlx::test]
+async fn roll_over_follows_owner_time_zone(pool: PgPool) {
+    let server = setup(&pool).await;
+    server.post("/tasks/1/toggle").await.assert_status_ok();
+    sqlx::query("UPDATE users SET time_zone = 'Asia/Tokyo' WHERE id = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // 18:00 UTC is already Friday morning in Tokyo
+    assert_eq!(streak_cache::roll_over(&pool, afternoon("2026-10-15")).await.unwrap(), 1);
+    assert_eq!(cached(&pool).await.unwrap().as_of, date("2026-10-16"));
+}
+
+#[sqlx::test]
+async fn rebuild_repairs_drifted_cache(pool: PgPool) {
+    let server = setup(&pool).await;
+    server.post("/tasks/1/toggle").await.assert_status_ok();
+    // Written behind the cache's back
+    insert_completions(&pool, &["2026-10-12", "2026-10-13", "2026-10-14"]).await;
+
+    let report = streak_cache::rebuild(&pool, afternoon("2026-10-15")).await.unwrap();
+    assert_eq!(report, RebuildReport { checked: 1, mismatched: 1, missing: 0 });
+    assert_eq!(cached(&pool).await.unwrap().current_streak, 4);
+
+    let report = streak_cache::rebuild(&pool, afternoon("2026-10-15")).await.unwrap();
+    assert_eq!(report, RebuildReport { checked: 1, mismatched: 0, missing: 0 });
+}
+
+#[sqlx::test]
+async fn rebuild_fills_missing_rows(pool: PgPool) {
+    setup(&pool).await;
+    insert_completions(&pool, &["2026-10-15"]).await;
+
+    let report = streak_cache::rebuild(&pool, afternoon("2026-10-15")).await.unwrap();
+    assert_eq!(report, RebuildReport { checked: 1, mismatched: 0, missing: 1 });
+    assert_eq!(cached(&pool).await.unwrap().current_streak, 1);
+}
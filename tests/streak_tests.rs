@@ -0,0 +1,341 @@
+mod common;
+
+use std::collections::HashSet;
+use std::time::Instant;
+
+use chrono::{Datelike, Days, NaiveDate};
+use sqlx::PgPool;
+
+use racha::models::streak::{self, Streak, StreakRun};
+use racha::models::task::{Schedule, TaskWithStreak};
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn dates(list: &[&str]) -> HashSet<NaiveDate> {
+    list.iter().map(|s| date(s)).collect()
+}
+
+/// Small deterministic generator so long histories are reproducible.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.next() % 100 < percent
+    }
+}
+
+/// `years` of history ending on `today`, done on roughly `done_percent` of
+/// days with the odd freeze on a missed one.
+fn history(seed: u64, years: u64, done_percent: u64, today: NaiveDate) -> (HashSet<NaiveDate>, HashSet<NaiveDate>) {
+    let mut rng = Lcg(seed);
+    let mut completed = HashSet::new();
+    let mut frozen = HashSet::new();
+    for day in (today - Days::new(years * 365)).iter_days().take_while(|d| *d <= today) {
+        if rng.chance(done_percent) {
+            completed.insert(day);
+        } else if rng.chance(10) {
+            frozen.insert(day);
+        }
+    }
+    (completed, frozen)
+}
+
+// The original day-by-day walk, kept as an oracle for the engine.
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Days::new(date.weekday().num_days_from_monday() as u64)
+}
+
+fn week_count(completed: &HashSet<NaiveDate>, start: NaiveDate, until: NaiveDate) -> usize {
+    start
+        .iter_days()
+        .take(7)
+        .take_while(|d| *d <= until)
+        .filter(|d| completed.contains(d))
+        .count()
+}
+
+fn reference_current(
+    schedule: Schedule,
+    completed: &HashSet<NaiveDate>,
+    frozen: &HashSet<NaiveDate>,
+    today: NaiveDate,
+) -> i64 {
+    let Some(&first) = completed.iter().min() else {
+        return 0;
+    };
+    let mut length = 0;
+    if let Schedule::Weekly(target) = schedule {
+        let mut week = week_start(today);
+        if week_count(completed, week, today) >= target as usize {
+            length += 1;
+        }
+        while week > first {
+            week = week - Days::new(7);
+            if week_count(completed, week, today) >= target as usize {
+                length += 1;
+            } else if !week.iter_days().take(7).any(|d| frozen.contains(&d)) {
+                break;
+            }
+        }
+    } else {
+        if schedule.is_due_on(today) && completed.contains(&today) {
+            length += 1;
+        }
+        let mut day = today;
+        while day > first {
+            day = day.pred_opt().unwrap();
+            if !schedule.is_due_on(day) {
+                continue;
+            }
+            if completed.contains(&day) {
+                length += 1;
+            } else if !frozen.contains(&day) {
+                break;
+            }
+        }
+    }
+    length
+}
+
+fn reference_runs(
+    schedule: Schedule,
+    completed: &HashSet<NaiveDate>,
+    frozen: &HashSet<NaiveDate>,
+    today: NaiveDate,
+) -> Vec<StreakRun> {
+    let Some(&first) = completed.iter().min() else {
+        return Vec::new();
+    };
+    let mut runs = Vec::new();
+    let mut open: Option<StreakRun> = None;
+    if let Schedule::Weekly(target) = schedule {
+        let mut week = week_start(first);
+        while week <= today {
+            let days: Vec<NaiveDate> = week
+                .iter_days()
+                .take(7)
+                .take_while(|d| *d <= today)
+                .filter(|d| completed.contains(d))
+                .collect();
+            if days.len() >= target as usize {
+                let run = open.get_or_insert(StreakRun { start: days[0], end: days[0], length: 0 });
+                run.end = days[days.len() - 1];
+                run.length += 1;
+            } else if week.iter_days().take(7).any(|d| frozen.contains(&d)) || week == week_start(today) {
+            } else if let Some(run) = open.take() {
+                runs.push(run);
+            }
+            week = week + Days::new(7);
+        }
+    } else {
+        for day in first.iter_days().take_while(|d| *d <= today) {
+            if !schedule.is_due_on(day) {
+                continue;
+            }
+            if completed.contains(&day) {
+                let run = open.get_or_insert(StreakRun { start: day, end: day, length: 0 });
+                run.end = day;
+                run.length += 1;
+            } else if frozen.contains(&day) || day == today {
+            } else if let Some(run) = open.take() {
+                runs.push(run);
+            }
+        }
+    }
+    runs.extend(open);
+    runs
+}
+
+const SCHEDULES: [Schedule; 4] = [
+    Schedule::Daily,
+    Schedule::Weekdays(0x15),
+    Schedule::Weekdays(0x60),
+    Schedule::Weekly(3),
+];
+
+#[test]
+fn engine_matches_day_walk_on_long_histories() {
+    let today = date("2026-10-15");
+    for seed in 1..=20 {
+        for done_percent in [60, 85, 97] {
+            let (completed, frozen) = history(seed, 5, done_percent, today);
+            for schedule in SCHEDULES {
+                let summary = streak::summarize(schedule, &completed, &frozen, today);
+                let runs = reference_runs(schedule, &completed, &frozen, today);
+                assert_eq!(summary.runs, runs, "runs: seed {seed}, {done_percent}%, {schedule:?}");
+                assert_eq!(
+                    summary.current.length,
+                    reference_current(schedule, &completed, &frozen, today),
+                    "current: seed {seed}, {done_percent}%, {schedule:?}"
+                );
+                assert_eq!(summary.best, runs.iter().map(|r| r.length).max().unwrap_or(0));
+            }
+        }
+    }
+}
+
+#[test]
+fn unbroken_years_count_every_day() {
+    let today = date("2026-10-15");
+    let start = date("2021-10-16");
+    let completed: HashSet<NaiveDate> = start.iter_days().take_while(|d| *d <= today).collect();
+
+    let summary = streak::summarize(Schedule::Daily, &completed, &HashSet::new(), today);
+    assert_eq!(summary.current, Streak { length: 1826, frozen: false });
+    assert_eq!(summary.runs, vec![StreakRun { start, end: today, length: 1826 }]);
+}
+
+#[test]
+fn frozen_gap_keeps_a_long_run_going() {
+    let today = date("2026-10-15");
+    let mut completed: HashSet<NaiveDate> = date("2024-01-01").iter_days().take_while(|d| *d <= today).collect();
+    completed.remove(&date("2025-06-01"));
+    completed.remove(&date("2025-06-02"));
+    let frozen = dates(&["2025-06-01", "2025-06-02"]);
+
+    let summary = streak::summarize(Schedule::Daily, &completed, &frozen, today);
+    assert_eq!(summary.current.length, completed.len() as i64);
+    assert!(summary.current.frozen);
+    assert_eq!(summary.runs.len(), 1);
+}
+
+#[test]
+fn missed_day_splits_runs_and_best_survives() {
+    let today = date("2026-10-15");
+    let mut completed: HashSet<NaiveDate> = date("2025-01-01").iter_days().take_while(|d| *d <= today).collect();
+    completed.remove(&date("2026-10-05"));
+
+    let summary = streak::summarize(Schedule::Daily, &completed, &HashSet::new(), today);
+    assert_eq!(summary.current, Streak { length: 10, frozen: false });
+    assert_eq!(summary.best, 642);
+    assert_eq!(
+        summary.runs,
+        vec![
+            StreakRun { start: date("2025-01-01"), end: date("2026-10-04"), length: 642 },
+            StreakRun { start: date("2026-10-06"), end: today, length: 10 },
+        ]
+    );
+}
+
+#[test]
+fn freeze_before_a_fresh_start_does_not_mark_it_frozen() {
+    let today = date("2026-10-15");
+    let completed = dates(&["2026-10-10", "2026-10-14", "2026-10-15"]);
+    let frozen = dates(&["2026-10-13"]);
+
+    let summary = streak::summarize(Schedule::Daily, &completed, &frozen, today);
+    assert_eq!(summary.current, Streak { length: 2, frozen: false });
+}
+
+#[test]
+fn unfinished_today_does_not_break_the_streak() {
+    let today = date("2026-10-15");
+    let completed = dates(&["2026-10-13", "2026-10-14"]);
+
+    let summary = streak::summarize(Schedule::Daily, &completed, &HashSet::new(), today);
+    assert_eq!(summary.current.length, 2);
+
+    let tomorrow = date("2026-10-16");
+    let summary = streak::summarize(Schedule::Daily, &completed, &HashSet::new(), tomorrow);
+    assert_eq!(summary.current.length, 0);
+    assert_eq!(summary.best, 2);
+}
+
+#[test]
+fn weekly_streak_bridges_frozen_week() {
+    // Thu 2026-10-15; weeks of Sep 21, Sep 28 met, Oct 5 frozen, Oct 12 met
+    let today = date("2026-10-15");
+    let completed = dates(&["2026-09-22", "2026-09-24", "2026-09-29", "2026-10-02", "2026-10-13", "2026-10-14"]);
+    let frozen = dates(&["2026-10-07"]);
+
+    let summary = streak::summarize(Schedule::Weekly(2), &completed, &frozen, today);
+    assert_eq!(summary.current, Streak { length: 3, frozen: true });
+    assert_eq!(summary.runs, vec![StreakRun { start: date("2026-09-22"), end: date("2026-10-14"), length: 3 }]);
+}
+
+#[test]
+fn future_completions_are_ignored() {
+    let today = date("2026-10-15");
+    let completed = dates(&["2026-10-14", "2026-10-15", "2026-10-16", "2026-10-17"]);
+
+    let summary = streak::summarize(Schedule::Daily, &completed, &HashSet::new(), today);
+    assert_eq!(summary.current.length, 2);
+    assert_eq!(summary.runs.last().unwrap().end, today);
+}
+
+#[test]
+fn clean_summary_over_years_of_slips() {
+    let today = date("2026-10-15");
+    let created = date("2022-01-01");
+    let slips = dates(&["2023-03-10", "2023-03-11", "2026-10-01"]);
+
+    let summary = streak::summarize_clean(&slips, created, today);
+    assert_eq!(summary.current.length, 14);
+    assert_eq!(summary.best, 1299);
+    assert_eq!(summary.runs.len(), 3);
+}
+
+#[sqlx::test]
+async fn task_streak_over_long_history(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    sqlx::query(
+        "INSERT INTO tasks (user_id, name, created_at) VALUES (1, 'Walk', '2023-01-01 08:00:00')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    // Every day from 2023 through today except Christmas 2025
+    sqlx::query(
+        r#"
+        INSERT INTO completions (task_id, completed_date)
+        SELECT 1, d::date FROM generate_series('2023-01-01'::date, '2026-10-15'::date, '1 day') d
+        WHERE d::date <> '2025-12-25'
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let task = TaskWithStreak::find_with_history(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    assert_eq!(task.current_streak, 294);
+    assert_eq!(task.best_streak, 1089);
+    assert_eq!(task.total_completions, 1383);
+    assert_eq!(task.runs.len(), 2);
+}
+
+/// Rough timing for a dashboard's worth of long-lived tasks. Run with
+/// `cargo test --test streak_tests -- --ignored --nocapture`.
+#[test]
+#[ignore]
+fn benchmark_ten_year_histories() {
+    let today = date("2026-10-15");
+    let histories: Vec<_> = (1..=50).map(|seed| history(seed, 10, 90, today)).collect();
+
+    let started = Instant::now();
+    for (completed, frozen) in &histories {
+        for schedule in SCHEDULES {
+            streak::summarize(schedule, completed, frozen, today);
+        }
+    }
+    let engine = started.elapsed();
+
+    let started = Instant::now();
+    for (completed, frozen) in &histories {
+        for schedule in SCHEDULES {
+            reference_runs(schedule, completed, frozen, today);
+            reference_current(schedule, completed, frozen, today);
+        }
+    }
+    let walk = started.elapsed();
+
+    println!("engine: {engine:?}, day walk: {walk:?} for {} summaries", histories.len() * SCHEDULES.len());
+}
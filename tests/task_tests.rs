@@ -1,5 +1,6 @@
 mod common;
 
+use racha::models::task::TaskWithStreak;
 use sqlx::PgPool;
 
 #[derive(serde::Serialize)]
@@ -209,3 +210,1222 @@ async fn archive_task_returns_200(pool: PgPool) {
     let text = dashboard.text();
     assert!(!text.contains("Old habit"), "Archived task should not appear on dashboard");
 }
+
+#[derive(serde::Serialize, Default)]
+struct ScheduledTaskForm {
+    name: String,
+    schedule: String,
+    weekly_target: Option<String>,
+    mon: Option<String>,
+    wed: Option<String>,
+    fri: Option<String>,
+}
+
+async fn insert_completions(pool: &PgPool, task_id: i64, dates: &[&str]) {
+    for date in dates {
+        sqlx::query("INSERT INTO completions (task_id, completed_date) VALUES ($1, $2::DATE)")
+            .bind(task_id)
+            .bind(date)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+}
+
+fn date(s: &str) -> chrono::NaiveDate {
+    chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+#[sqlx::test]
+async fn create_task_with_weekday_schedule(pool: PgPool) {
+    let server = common::build_test_server(pool).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+
+    let response = server
+        .post("/tasks")
+        .form(&ScheduledTaskForm {
+            name: "Gym".to_string(),
+            schedule: "weekdays".to_string(),
+            weekly_target: None,
+            mon: Some("on".to_string()),
+            wed: Some("on".to_string()),
+            fri: Some("on".to_string()),
+        })
+        .await;
+    response.assert_status_ok();
+    response.assert_text_contains("Mon, Wed, Fri");
+}
+
+#[sqlx::test]
+async fn weekday_streak_skips_rest_days(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&ScheduledTaskForm {
+            name: "Gym".to_string(),
+            schedule: "weekdays".to_string(),
+            weekly_target: None,
+            mon: Some("on".to_string()),
+            wed: Some("on".to_string()),
+            fri: Some("on".to_string()),
+        })
+        .await;
+
+    // Mon 2026-10-05 through Fri 2026-10-16, every scheduled day
+    insert_completions(
+        &pool,
+        1,
+        &["2026-10-05", "2026-10-07", "2026-10-09", "2026-10-12", "2026-10-14", "2026-10-16"],
+    )
+    .await;
+
+    // Saturday is not due: the streak survives and the task is not counted
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-17")).await.unwrap().unwrap();
+    assert_eq!(task.current_streak, 6);
+    assert!(!task.due_today);
+
+    // Monday is due but still in progress
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-19")).await.unwrap().unwrap();
+    assert_eq!(task.current_streak, 6);
+    assert!(task.due_today);
+
+    // Missing Monday breaks it by Tuesday
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-20")).await.unwrap().unwrap();
+    assert_eq!(task.current_streak, 0);
+}
+
+#[sqlx::test]
+async fn weekly_quota_streak_counts_weeks(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&ScheduledTaskForm {
+            name: "Gym".to_string(),
+            schedule: "weekly".to_string(),
+            weekly_target: Some("3".to_string()),
+            mon: None,
+            wed: None,
+            fri: None,
+        })
+        .await;
+
+    insert_completions(
+        &pool,
+        1,
+        &[
+            "2026-09-29", "2026-10-01", "2026-10-03", // week of Sep 28: met
+            "2026-10-06", "2026-10-08", "2026-10-10", // week of Oct 5: met
+            "2026-10-13", // week of Oct 12: in progress
+        ],
+    )
+    .await;
+
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    assert_eq!(task.current_streak, 2);
+    assert_eq!(task.completed_this_week, 1);
+    assert!(task.due_today);
+
+    // Falling short of the quota for a finished week resets the streak
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-20")).await.unwrap().unwrap();
+    assert_eq!(task.current_streak, 0);
+}
+
+#[sqlx::test]
+async fn weekly_quota_met_is_not_due(pool: PgPool) {
+    let server = common::build_test_server_on(pool.clone(), "2026-10-14").await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&ScheduledTaskForm {
+            name: "Swim".to_string(),
+            schedule: "weekly".to_string(),
+            weekly_target: Some("1".to_string()),
+            mon: None,
+            wed: None,
+            fri: None,
+        })
+        .await;
+    insert_completions(&pool, 1, &["2026-10-12"]).await;
+
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-14")).await.unwrap().unwrap();
+    assert_eq!(task.current_streak, 1);
+    assert!(!task.due_today);
+
+    let response = server.get("/").add_header("X-Local-Date", "2026-10-14").await;
+    response.assert_status_ok();
+    response.assert_text_contains("0/0");
+}
+
+#[derive(serde::Serialize)]
+struct BackfillForm {
+    date: String,
+}
+
+#[sqlx::test]
+async fn backfill_panel_lists_window(pool: PgPool) {
+    let server = common::build_test_server(pool).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Journal".to_string(),
+            description: None,
+        })
+        .await;
+
+    let response = server.get("/tasks/1/backfill").add_header("X-Local-Date", "2026-10-15").await;
+    response.assert_status_ok();
+    response.assert_text_contains("Yesterday");
+    response.assert_text_contains("2026-10-13");
+    assert!(!response.text().contains("2026-10-12"), "Window should be two days");
+}
+
+#[sqlx::test]
+async fn backfill_yesterday_restores_streak(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Journal".to_string(),
+            description: None,
+        })
+        .await;
+    insert_completions(&pool, 1, &["2026-10-12", "2026-10-13"]).await;
+
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    assert_eq!(task.current_streak, 0);
+
+    let response = server
+        .post("/tasks/1/backfill")
+        .add_header("X-Local-Date", "2026-10-15")
+        .form(&BackfillForm {
+            date: "2026-10-14".to_string(),
+        })
+        .await;
+    response.assert_status_ok();
+    response.assert_text_contains("dashboard-progress");
+
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    assert_eq!(task.current_streak, 3);
+
+    // Toggling the same day again unmarks it
+    server
+        .post("/tasks/1/backfill")
+        .add_header("X-Local-Date", "2026-10-15")
+        .form(&BackfillForm {
+            date: "2026-10-14".to_string(),
+        })
+        .await
+        .assert_status_ok();
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    assert_eq!(task.current_streak, 0);
+}
+
+#[sqlx::test]
+async fn backfill_outside_window_is_rejected(pool: PgPool) {
+    let server = common::build_test_server(pool).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Journal".to_string(),
+            description: None,
+        })
+        .await;
+
+    for date in ["2026-10-12", "2026-10-15", "2026-10-16"] {
+        let response = server
+            .post("/tasks/1/backfill")
+            .add_header("X-Local-Date", "2026-10-15")
+            .form(&BackfillForm {
+                date: date.to_string(),
+            })
+            .await;
+        response.assert_status_bad_request();
+    }
+}
+
+#[sqlx::test]
+async fn backfill_other_users_task_returns_403(pool: PgPool) {
+    let server = common::build_test_server(pool).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Alice task".to_string(),
+            description: None,
+        })
+        .await;
+
+    server.post("/logout").await;
+    common::register_user(&server, "bob", "bob@test.com", "password123").await;
+
+    let response = server
+        .post("/tasks/1/backfill")
+        .add_header("X-Local-Date", "2026-10-15")
+        .form(&BackfillForm {
+            date: "2026-10-14".to_string(),
+        })
+        .await;
+    response.assert_status_forbidden();
+}
+
+async fn freeze_tokens(pool: &PgPool) -> i32 {
+    sqlx::query_scalar("SELECT freeze_tokens FROM users WHERE id = 1")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test]
+async fn freeze_bridges_missed_day(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Read".to_string(),
+            description: None,
+        })
+        .await;
+    insert_completions(&pool, 1, &["2026-10-11", "2026-10-12", "2026-10-13"]).await;
+
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    assert_eq!(task.current_streak, 0);
+
+    let response = server
+        .post("/tasks/1/freeze")
+        .add_header("X-Local-Date", "2026-10-15")
+        .form(&BackfillForm {
+            date: "2026-10-14".to_string(),
+        })
+        .await;
+    response.assert_status_ok();
+    assert_eq!(freeze_tokens(&pool).await, 1);
+
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    assert_eq!(task.current_streak, 3);
+    assert!(task.frozen);
+
+    let card = server.get("/tasks/1/card").add_header("X-Local-Date", "2026-10-15").await;
+    card.assert_text_contains("streak-frozen");
+
+    // Undoing the freeze refunds the token
+    server
+        .post("/tasks/1/freeze")
+        .add_header("X-Local-Date", "2026-10-15")
+        .form(&BackfillForm {
+            date: "2026-10-14".to_string(),
+        })
+        .await
+        .assert_status_ok();
+    assert_eq!(freeze_tokens(&pool).await, 2);
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    assert_eq!(task.current_streak, 0);
+}
+
+#[sqlx::test]
+async fn freeze_without_tokens_is_refused(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Read".to_string(),
+            description: None,
+        })
+        .await;
+    sqlx::query("UPDATE users SET freeze_tokens = 0").execute(&pool).await.unwrap();
+
+    let response = server
+        .post("/tasks/1/freeze")
+        .add_header("X-Local-Date", "2026-10-15")
+        .form(&BackfillForm {
+            date: "2026-10-14".to_string(),
+        })
+        .await;
+    response.assert_status_ok();
+    response.assert_text_contains("0 streak freezes left");
+
+    let frozen: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM streak_freezes")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(frozen, 0);
+}
+
+#[sqlx::test]
+async fn seven_day_streak_earns_freeze_token(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Read".to_string(),
+            description: None,
+        })
+        .await;
+    insert_completions(
+        &pool,
+        1,
+        &["2026-10-09", "2026-10-10", "2026-10-11", "2026-10-12", "2026-10-13", "2026-10-14"],
+    )
+    .await;
+
+    server.post("/tasks/1/toggle").add_header("X-Local-Date", "2026-10-15").await;
+    assert_eq!(freeze_tokens(&pool).await, 3);
+
+    // Undoing the seventh day takes the token back
+    server.post("/tasks/1/toggle").add_header("X-Local-Date", "2026-10-15").await;
+    assert_eq!(freeze_tokens(&pool).await, 2);
+}
+
+async fn milestones_reached(pool: &PgPool) -> Vec<(i32, chrono::NaiveDate)> {
+    sqlx::query_as("SELECT threshold, reached_on FROM milestones WHERE task_id = 1 ORDER BY threshold")
+        .fetch_all(pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test]
+async fn reaching_a_milestone_is_celebrated_and_recorded(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Read".to_string(),
+            description: None,
+        })
+        .await;
+    insert_completions(
+        &pool,
+        1,
+        &["2026-10-09", "2026-10-10", "2026-10-11", "2026-10-12", "2026-10-13", "2026-10-14"],
+    )
+    .await;
+
+    let response = server.post("/tasks/1/toggle").add_header("X-Local-Date", "2026-10-15").await;
+    let trigger = response.header("HX-Trigger");
+    let trigger = trigger.to_str().unwrap();
+    assert!(trigger.contains("'Read' hit a 7-day streak!"));
+    assert!(trigger.contains(r#""type":"milestone""#));
+    assert_eq!(milestones_reached(&pool).await, vec![(7, date("2026-10-15"))]);
+
+    // Undoing the seventh day takes the milestone back
+    server.post("/tasks/1/toggle").add_header("X-Local-Date", "2026-10-15").await;
+    assert!(milestones_reached(&pool).await.is_empty());
+}
+
+#[sqlx::test]
+async fn milestones_follow_the_users_thresholds(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server.post("/profile/milestones").form(&[("milestones", "3")]).await;
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Read".to_string(),
+            description: None,
+        })
+        .await;
+    insert_completions(&pool, 1, &["2026-10-12", "2026-10-13"]).await;
+
+    // Backfilling yesterday joins the runs and passes 3
+    let response = server
+        .post("/tasks/1/backfill")
+        .add_header("X-Local-Date", "2026-10-15")
+        .form(&BackfillForm {
+            date: "2026-10-14".to_string(),
+        })
+        .await;
+    assert!(response.header("HX-Trigger").to_str().unwrap().contains("hit a 3-day streak"));
+
+    let response = server.post("/tasks/1/toggle").add_header("X-Local-Date", "2026-10-15").await;
+    assert!(!response.header("HX-Trigger").to_str().unwrap().contains("milestone"));
+    assert_eq!(milestones_reached(&pool).await, vec![(3, date("2026-10-15"))]);
+}
+
+#[derive(serde::Serialize)]
+struct TargetTaskForm {
+    name: String,
+    target_value: String,
+    unit: String,
+}
+
+#[derive(serde::Serialize)]
+struct AmountForm {
+    amount: String,
+}
+
+async fn create_water_task(server: &axum_test::TestServer) {
+    server
+        .post("/tasks")
+        .form(&TargetTaskForm {
+            name: "Water".to_string(),
+            target_value: "8".to_string(),
+            unit: "glasses".to_string(),
+        })
+        .await
+        .assert_status_ok();
+}
+
+#[sqlx::test]
+async fn increment_accumulates_toward_target(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_water_task(&server).await;
+
+    let response = server
+        .post("/tasks/1/increment")
+        .add_header("X-Local-Date", "2026-10-15")
+        .form(&AmountForm {
+            amount: "5".to_string(),
+        })
+        .await;
+    response.assert_status_ok();
+    response.assert_text_contains("5/8 glasses");
+    // Partial progress counts toward the bar but not the done count
+    response.assert_text_contains("0/1");
+    response.assert_text_contains("width: 63%");
+
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    assert!(!task.completed_today);
+    assert_eq!(task.current_streak, 0);
+
+    let response = server
+        .post("/tasks/1/increment")
+        .add_header("X-Local-Date", "2026-10-15")
+        .form(&AmountForm {
+            amount: "3".to_string(),
+        })
+        .await;
+    response.assert_status_ok();
+    response.assert_text_contains("1/1");
+
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    assert!(task.completed_today);
+    assert_eq!(task.current_streak, 1);
+}
+
+#[sqlx::test]
+async fn decrement_below_target_uncompletes(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_water_task(&server).await;
+
+    server
+        .post("/tasks/1/increment")
+        .add_header("X-Local-Date", "2026-10-15")
+        .form(&AmountForm {
+            amount: "8".to_string(),
+        })
+        .await;
+    let response = server
+        .post("/tasks/1/decrement")
+        .add_header("X-Local-Date", "2026-10-15")
+        .form(&AmountForm {
+            amount: "1".to_string(),
+        })
+        .await;
+    response.assert_status_ok();
+    response.assert_text_contains("7/8 glasses");
+
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    assert!(!task.completed_today);
+
+    // Going below zero clears the day entirely
+    server
+        .post("/tasks/1/decrement")
+        .add_header("X-Local-Date", "2026-10-15")
+        .form(&AmountForm {
+            amount: "20".to_string(),
+        })
+        .await
+        .assert_status_ok();
+    let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM completions")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(rows, 0);
+}
+
+#[sqlx::test]
+async fn new_target_applies_from_today(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Read".to_string(),
+            description: None,
+        })
+        .await;
+    insert_completions(&pool, 1, &["2026-10-12", "2026-10-13", "2026-10-14"]).await;
+    server.post("/tasks/1/toggle").await.assert_status_ok();
+
+    server
+        .post("/tasks/1/edit")
+        .form(&TargetTaskForm {
+            name: "Read".to_string(),
+            target_value: "8".to_string(),
+            unit: "pages".to_string(),
+        })
+        .await
+        .assert_status_ok();
+
+    // Past days were done under the old target; today now needs 8 pages
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    assert!(!task.completed_today);
+    assert_eq!(task.today_value, 1);
+    assert_eq!(task.current_streak, 3);
+    server.get("/tasks/1/history").await.assert_text_contains("3 days done");
+
+    // Lowering it again leaves those days alone too
+    server
+        .post("/tasks/1/edit")
+        .form(&TargetTaskForm {
+            name: "Read".to_string(),
+            target_value: "1".to_string(),
+            unit: "pages".to_string(),
+        })
+        .await
+        .assert_status_ok();
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    assert!(task.completed_today);
+    assert_eq!(task.current_streak, 4);
+}
+
+#[sqlx::test]
+async fn toggle_fills_quantitative_task_to_target(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_water_task(&server).await;
+
+    let response = server.post("/tasks/1/toggle").add_header("X-Local-Date", "2026-10-15").await;
+    response.assert_status_ok();
+    response.assert_text_contains("8/8 glasses");
+}
+
+#[sqlx::test]
+async fn increment_plain_task_is_rejected(pool: PgPool) {
+    let server = common::build_test_server(pool).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Read".to_string(),
+            description: None,
+        })
+        .await;
+
+    let response = server
+        .post("/tasks/1/increment")
+        .form(&AmountForm {
+            amount: "1".to_string(),
+        })
+        .await;
+    response.assert_status_bad_request();
+}
+
+#[derive(serde::Serialize)]
+struct AvoidTaskForm {
+    name: String,
+    kind: String,
+    target_value: String,
+    schedule: String,
+}
+
+/// Creates an avoid task (id 1) that has existed since 2026-10-01.
+async fn create_avoid_task(server: &axum_test::TestServer, pool: &PgPool) {
+    server
+        .post("/tasks")
+        .form(&AvoidTaskForm {
+            name: "No sugar".to_string(),
+            kind: "avoid".to_string(),
+            target_value: "5".to_string(),
+            schedule: "weekly".to_string(),
+        })
+        .await
+        .assert_status_ok();
+    sqlx::query("UPDATE tasks SET created_at = '2026-10-01 08:00:00' WHERE id = 1")
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn avoid_task_counts_clean_days_since_creation(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_avoid_task(&server, &pool).await;
+
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    assert!(task.is_avoid());
+    // Avoid tasks ignore submitted schedules and targets
+    assert!(task.schedule.is_daily());
+    assert_eq!(task.target_value, None);
+    assert_eq!(task.current_streak, 14);
+    assert!(task.completed_today);
+}
+
+#[sqlx::test]
+async fn avoid_task_streak_restarts_after_slip(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_avoid_task(&server, &pool).await;
+    insert_completions(&pool, 1, &["2026-10-05", "2026-10-11"]).await;
+
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    assert_eq!(task.current_streak, 4);
+
+    let response = server.post("/tasks/1/toggle").add_header("X-Local-Date", "2026-10-15").await;
+    response.assert_status_ok();
+    response.assert_text_contains("Undo slip");
+
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    assert_eq!(task.current_streak, 0);
+    assert!(!task.completed_today);
+
+    // Undoing the slip restores the clean run
+    server
+        .post("/tasks/1/toggle")
+        .add_header("X-Local-Date", "2026-10-15")
+        .await
+        .assert_status_ok();
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    assert_eq!(task.current_streak, 4);
+}
+
+#[sqlx::test]
+async fn avoid_task_slip_does_not_award_freeze_tokens(pool: PgPool) {
+    let server = common::build_test_server_on(pool.clone(), "2026-10-08").await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_avoid_task(&server, &pool).await;
+    let before = freeze_tokens(&pool).await;
+
+    server
+        .post("/tasks/1/toggle")
+        .add_header("X-Local-Date", "2026-10-08")
+        .await
+        .assert_status_ok();
+    server
+        .post("/tasks/1/toggle")
+        .add_header("X-Local-Date", "2026-10-08")
+        .await
+        .assert_status_ok();
+
+    assert_eq!(freeze_tokens(&pool).await, before);
+}
+
+#[sqlx::test]
+async fn avoid_task_clean_today_counts_toward_progress(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_avoid_task(&server, &pool).await;
+
+    let dashboard = server.get("/").add_header("X-Local-Date", "2026-10-15").await;
+    dashboard.assert_status_ok();
+    dashboard.assert_text_contains("1/1");
+    dashboard.assert_text_contains("Log slip");
+}
+
+#[sqlx::test]
+async fn best_streak_and_runs_cover_broken_streaks(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Meditate".to_string(),
+            description: None,
+        })
+        .await;
+    insert_completions(
+        &pool,
+        1,
+        &[
+            "2026-10-01", "2026-10-02", "2026-10-03", "2026-10-04", "2026-10-05",
+            "2026-10-08", "2026-10-09", "2026-10-10",
+            "2026-10-13", "2026-10-14",
+        ],
+    )
+    .await;
+
+    let task = TaskWithStreak::find_with_history(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    assert_eq!(task.current_streak, 2);
+    assert_eq!(task.best_streak, 5);
+    assert_eq!(task.total_completions, 10);
+    let runs: Vec<(chrono::NaiveDate, chrono::NaiveDate, i64)> =
+        task.runs.iter().map(|r| (r.start, r.end, r.length)).collect();
+    assert_eq!(
+        runs,
+        vec![
+            (date("2026-10-01"), date("2026-10-05"), 5),
+            (date("2026-10-08"), date("2026-10-10"), 3),
+            (date("2026-10-13"), date("2026-10-14"), 2),
+        ]
+    );
+}
+
+#[sqlx::test]
+async fn frozen_day_joins_runs(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Meditate".to_string(),
+            description: None,
+        })
+        .await;
+    insert_completions(&pool, 1, &["2026-10-10", "2026-10-11", "2026-10-13"]).await;
+    sqlx::query("INSERT INTO streak_freezes (task_id, frozen_date) VALUES (1, '2026-10-12')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let task = TaskWithStreak::find_with_history(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    assert_eq!(task.runs.len(), 1);
+    assert_eq!(task.runs[0].length, 3);
+    assert_eq!(task.best_streak, 3);
+    // Missing yesterday broke it, so nothing is running now
+    assert_eq!(task.current_streak, 0);
+}
+
+#[sqlx::test]
+async fn weekly_runs_count_weeks(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&ScheduledTaskForm {
+            name: "Gym".to_string(),
+            schedule: "weekly".to_string(),
+            weekly_target: Some("2".to_string()),
+            ..Default::default()
+        })
+        .await;
+    // Weeks of Sep 21 and Sep 28 met the target, Oct 5 did not, Oct 12 did
+    insert_completions(
+        &pool,
+        1,
+        &["2026-09-22", "2026-09-24", "2026-09-29", "2026-10-02", "2026-10-06", "2026-10-13", "2026-10-14"],
+    )
+    .await;
+
+    let task = TaskWithStreak::find_with_history(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    let lengths: Vec<i64> = task.runs.iter().map(|r| r.length).collect();
+    assert_eq!(lengths, vec![2, 1]);
+    assert_eq!(task.runs[0].start, date("2026-09-22"));
+    assert_eq!(task.runs[0].end, date("2026-10-02"));
+    assert_eq!(task.best_streak, 2);
+    assert_eq!(task.current_streak, 1);
+}
+
+#[sqlx::test]
+async fn avoid_task_runs_split_at_slips(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_avoid_task(&server, &pool).await;
+    insert_completions(&pool, 1, &["2026-10-05", "2026-10-06"]).await;
+
+    let task = TaskWithStreak::find_with_history(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    let runs: Vec<(chrono::NaiveDate, chrono::NaiveDate, i64)> =
+        task.runs.iter().map(|r| (r.start, r.end, r.length)).collect();
+    assert_eq!(
+        runs,
+        vec![
+            (date("2026-10-02"), date("2026-10-04"), 3),
+            (date("2026-10-07"), date("2026-10-15"), 9),
+        ]
+    );
+    assert_eq!(task.current_streak, 9);
+    assert_eq!(task.best_streak, 9);
+    assert_eq!(task.total_completions, 2);
+}
+
+#[sqlx::test]
+async fn dashboard_shows_best_streak_after_it_breaks(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Meditate".to_string(),
+            description: None,
+        })
+        .await;
+    insert_completions(&pool, 1, &["2026-10-01", "2026-10-02", "2026-10-03", "2026-10-04"]).await;
+
+    let dashboard = server.get("/").add_header("X-Local-Date", "2026-10-15").await;
+    dashboard.assert_text_contains("Best Streak");
+    dashboard.assert_text_contains("Best 4 days");
+
+    let history = server.get("/tasks/1/history").add_header("X-Local-Date", "2026-10-15").await;
+    history.assert_text_contains("All streaks");
+    history.assert_text_contains("Oct 1, 2026");
+}
+
+async fn create_archived_task(server: &axum_test::TestServer, pool: &PgPool) {
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Journal".to_string(),
+            description: None,
+        })
+        .await;
+    insert_completions(pool, 1, &["2026-10-01", "2026-10-02"]).await;
+    server.post("/tasks/1/archive").await.assert_status_ok();
+}
+
+#[sqlx::test]
+async fn archived_page_lists_archived_tasks_with_stats(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_archived_task(&server, &pool).await;
+
+    let dashboard = server.get("/").await;
+    assert!(!dashboard.text().contains(r#"id="task-1""#));
+
+    let response = server.get("/tasks/archived").await;
+    response.assert_status_ok();
+    response.assert_text_contains("Journal");
+    response.assert_text_contains("Best 2 days");
+    response.assert_text_contains("2 done");
+}
+
+#[sqlx::test]
+async fn restore_brings_task_back_with_history(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_archived_task(&server, &pool).await;
+
+    server.post("/tasks/1/restore").await.assert_status_ok();
+
+    let dashboard = server.get("/").await;
+    dashboard.assert_text_contains(r#"id="task-1""#);
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    assert!(!task.archived);
+    assert_eq!(task.total_completions, 2);
+}
+
+#[sqlx::test]
+async fn delete_removes_task_and_completions(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_archived_task(&server, &pool).await;
+    sqlx::query("INSERT INTO streak_freezes (task_id, frozen_date) VALUES (1, '2026-10-03')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    server.post("/tasks/1/delete").await.assert_status_ok();
+
+    let tasks: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tasks").fetch_one(&pool).await.unwrap();
+    let completions: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM completions").fetch_one(&pool).await.unwrap();
+    let freezes: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM streak_freezes").fetch_one(&pool).await.unwrap();
+    assert_eq!((tasks, completions, freezes), (0, 0, 0));
+}
+
+#[sqlx::test]
+async fn delete_refuses_active_task(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Journal".to_string(),
+            description: None,
+        })
+        .await;
+
+    server.post("/tasks/1/delete").await.assert_status_bad_request();
+    let tasks: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tasks").fetch_one(&pool).await.unwrap();
+    assert_eq!(tasks, 1);
+}
+
+#[sqlx::test]
+async fn delete_other_users_task_returns_403(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    create_archived_task(&server, &pool).await;
+
+    server.post("/logout").await;
+    common::register_user(&server, "bob", "bob@test.com", "password123").await;
+
+    server.post("/tasks/1/delete").await.assert_status_forbidden();
+    server.post("/tasks/1/restore").await.assert_status_forbidden();
+}
+
+#[derive(serde::Serialize)]
+struct NoteForm {
+    note: String,
+}
+
+#[sqlx::test]
+async fn note_can_be_added_after_completing(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Run".to_string(),
+            description: None,
+        })
+        .await;
+    let card = server.post("/tasks/1/toggle").add_header("X-Local-Date", "2026-10-15").await;
+    card.assert_text_contains("Add note");
+
+    server
+        .get("/tasks/1/note")
+        .add_header("X-Local-Date", "2026-10-15")
+        .await
+        .assert_status_ok();
+    let response = server
+        .post("/tasks/1/note")
+        .add_header("X-Local-Date", "2026-10-15")
+        .form(&NoteForm {
+            note: "  ran 5k, knee hurt  ".to_string(),
+        })
+        .await;
+    response.assert_status_ok();
+    response.assert_text_contains("ran 5k, knee hurt");
+    response.assert_text_contains("Edit note");
+
+    let note: Option<String> = sqlx::query_scalar("SELECT note FROM completions WHERE task_id = 1")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(note.as_deref(), Some("ran 5k, knee hurt"));
+
+    // Saving an empty note clears it
+    server
+        .post("/tasks/1/note")
+        .add_header("X-Local-Date", "2026-10-15")
+        .form(&NoteForm { note: String::new() })
+        .await
+        .assert_status_ok();
+    let note: Option<String> = sqlx::query_scalar("SELECT note FROM completions WHERE task_id = 1")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(note, None);
+}
+
+#[sqlx::test]
+async fn note_survives_unticking(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Run".to_string(),
+            description: None,
+        })
+        .await;
+    server.post("/tasks/1/toggle").await.assert_status_ok();
+    server
+        .post("/tasks/1/note")
+        .form(&NoteForm {
+            note: "ran 5k, knee hurt".to_string(),
+        })
+        .await
+        .assert_status_ok();
+
+    let card = server.post("/tasks/1/toggle").await;
+    card.assert_text_contains("ran 5k, knee hurt");
+    card.assert_text_contains("Edit note");
+    let task = TaskWithStreak::find_by_id(&pool, 1, date("2026-10-15")).await.unwrap().unwrap();
+    assert!(!task.completed_today);
+    assert_eq!(task.total_completions, 0);
+    server.get("/tasks/1/history").await.assert_text_contains("ran 5k, knee hurt");
+
+    // Clearing the note then leaves nothing behind
+    server
+        .post("/tasks/1/note")
+        .form(&NoteForm { note: String::new() })
+        .await
+        .assert_status_ok();
+    let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM completions")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(rows, 0);
+}
+
+#[sqlx::test]
+async fn note_needs_a_completion(pool: PgPool) {
+    let server = common::build_test_server(pool).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Run".to_string(),
+            description: None,
+        })
+        .await;
+
+    server.get("/tasks/1/note").await.assert_status_bad_request();
+    server
+        .post("/tasks/1/note")
+        .form(&NoteForm {
+            note: "not yet".to_string(),
+        })
+        .await
+        .assert_status_bad_request();
+}
+
+#[sqlx::test]
+async fn note_on_other_users_task_returns_403(pool: PgPool) {
+    let server = common::build_test_server(pool).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Run".to_string(),
+            description: None,
+        })
+        .await;
+    server.post("/tasks/1/toggle").await;
+
+    server.post("/logout").await;
+    common::register_user(&server, "bob", "bob@test.com", "password123").await;
+    server
+        .post("/tasks/1/note")
+        .form(&NoteForm {
+            note: "hi".to_string(),
+        })
+        .await
+        .assert_status_forbidden();
+}
+
+async fn completion_dates(pool: &PgPool) -> Vec<chrono::NaiveDate> {
+    sqlx::query_scalar("SELECT completed_date FROM completions WHERE task_id = 1 ORDER BY completed_date")
+        .fetch_all(pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test]
+async fn forged_future_date_falls_back_to_zone_today(pool: PgPool) {
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Read".to_string(),
+            description: None,
+        })
+        .await;
+
+    server
+        .post("/tasks/1/toggle")
+        .add_header("X-Local-Date", "2026-10-20")
+        .await
+        .assert_status_ok();
+
+    assert_eq!(completion_dates(&pool).await, vec![date("2026-10-15")]);
+}
+
+#[sqlx::test]
+async fn next_day_client_date_falls_back_to_zone_today(pool: PgPool) {
+    // 13:00 in Chicago: tomorrow has begun further east, but not here
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Read".to_string(),
+            description: None,
+        })
+        .await;
+
+    server
+        .post("/tasks/1/toggle")
+        .add_header("X-Local-Date", "2026-10-16")
+        .add_header("cookie", "local_date=2026-10-16")
+        .await
+        .assert_status_ok();
+
+    assert_eq!(completion_dates(&pool).await, vec![date("2026-10-15")]);
+}
+
+#[sqlx::test]
+async fn today_follows_stored_time_zone(pool: PgPool) {
+    // 18:00 UTC is already the next morning in Tokyo
+    let server = common::build_test_server(pool.clone()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    sqlx::query("UPDATE users SET time_zone = 'Asia/Tokyo' WHERE id = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Read".to_string(),
+            description: None,
+        })
+        .await;
+
+    server.post("/tasks/1/toggle").await.assert_status_ok();
+
+    assert_eq!(completion_dates(&pool).await, vec![date("2026-10-16")]);
+}
+
+/// 01:30 on Friday 2026-10-16 in Chicago.
+fn after_midnight() -> chrono::DateTime<chrono::Utc> {
+    "2026-10-16T06:30:00Z".parse().unwrap()
+}
+
+async fn create_task_after_midnight(pool: &PgPool, day_ends_hour: i16) -> axum_test::TestServer {
+    let server = common::build_test_server_at(pool.clone(), after_midnight()).await;
+    common::register_user(&server, "alice", "alice@test.com", "password123").await;
+    sqlx::query("UPDATE users SET day_ends_hour = $1 WHERE id = 1")
+        .bind(day_ends_hour)
+        .execute(pool)
+        .await
+        .unwrap();
+    server
+        .post("/tasks")
+        .form(&CreateTaskForm {
+            name: "Read".to_string(),
+            description: None,
+        })
+        .await;
+    server
+}
+
+#[sqlx::test]
+async fn check_in_before_rollover_counts_for_previous_day(pool: PgPool) {
+    let server = create_task_after_midnight(&pool, 3).await;
+
+    server.post("/tasks/1/toggle").await.assert_status_ok();
+
+    assert_eq!(completion_dates(&pool).await, vec![date("2026-10-15")]);
+}
+
+#[sqlx::test]
+async fn browser_date_is_moved_back_before_rollover(pool: PgPool) {
+    let server = create_task_after_midnight(&pool, 3).await;
+
+    server
+        .post("/tasks/1/toggle")
+        .add_header("X-Local-Date", "2026-10-16")
+        .await
+        .assert_status_ok();
+
+    assert_eq!(completion_dates(&pool).await, vec![date("2026-10-15")]);
+}
+
+#[sqlx::test]
+async fn midnight_rollover_credits_new_day(pool: PgPool) {
+    let server = create_task_after_midnight(&pool, 0).await;
+
+    server.post("/tasks/1/toggle").await.assert_status_ok();
+
+    assert_eq!(completion_dates(&pool).await, vec![date("2026-10-16")]);
+}